@@ -0,0 +1,1366 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::inventory_error::InventoryError;
+use crate::inventory_item::{InventoryItem, ItemInstanceId};
+use crate::item_database::{BrokenItemOutcome, ItemDatabase};
+use crate::item_filter::ItemFilter;
+use crate::item_query::ItemQuery;
+
+/// The position of a slot in an inventory.
+///
+/// `x` is the row and `y` the column of the slot on the page `page`, matching the convention of `IconComponent`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SlotPosition {
+    pub page: usize,
+    pub x: usize,
+    pub y: usize,
+}
+
+impl SlotPosition {
+    pub fn new(page: usize, x: usize, y: usize) -> Self {
+        Self { page, x, y }
+    }
+}
+
+/// The location of an item inside an inventory: a slot of the inventory itself or of a container item inside of it.
+///
+/// `container` is the instance id of the container item, or `None` for the slots of the inventory itself.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ItemLocation {
+    pub container: Option<ItemInstanceId>,
+    pub position: SlotPosition,
+}
+
+impl ItemLocation {
+    pub fn new(container: Option<ItemInstanceId>, position: SlotPosition) -> Self {
+        Self { container, position }
+    }
+}
+
+/// Describes what happened to the items of an inventory, when it was resized.
+///
+/// Items, that are still inside the grid after resizing, keep their position. Items, that are outside, are relocated
+/// to free slots. Items, that did not fit anymore, are ejected from the inventory and handed back to the caller.
+#[derive(Clone, Debug, Default)]
+pub struct ResizeReport {
+    relocated: Vec<(SlotPosition, SlotPosition)>, // (from, to)
+    ejected: Vec<(SlotPosition, InventoryItem)>,  // (previous position, item)
+}
+
+impl ResizeReport {
+    /// Returns the items that were moved to a free slot, as pairs of their previous and new position.
+    pub fn get_relocated(&self) -> &[(SlotPosition, SlotPosition)] {
+        &self.relocated
+    }
+
+    /// Returns the items that did not fit into the resized inventory together with their previous position.
+    pub fn get_ejected(&self) -> &[(SlotPosition, InventoryItem)] {
+        &self.ejected
+    }
+
+    /// Takes the ejected items out of the report.
+    pub fn take_ejected(&mut self) -> Vec<(SlotPosition, InventoryItem)> {
+        std::mem::take(&mut self.ejected)
+    }
+
+    /// Returns true, if every item still fits into the inventory.
+    pub fn is_complete(&self) -> bool {
+        self.ejected.is_empty()
+    }
+
+    fn append(&mut self, mut other: ResizeReport) {
+        self.relocated.append(&mut other.relocated);
+        self.ejected.append(&mut other.ejected);
+    }
+}
+
+/// A page of an inventory, like a tab of a bank stash.
+///
+/// Every page has its own name, optional icon and grid size. Cells of the grid can be disabled to build
+/// non-rectangular containers. Disabled cells never hold an item and are not shown in the inventory ui.
+///
+/// A slot can have a filter, an `ItemQuery` the items put into it have to match, e.g. `armor.helmet` for the head
+/// slot of an equipment page. Broken items are never accepted by a slot with a filter. The inventory ui rejects drops,
+/// that do not match. Code putting items into an inventory can check the filters with `Inventory::accepts`.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let mut page = InventoryPage::new("Potions", 2, 3).with_icon("Icons/potion_tab.png");
+/// assert_eq!(page.get_name(), "Potions");
+/// assert_eq!(page.get_icon(), Some("Icons/potion_tab.png"));
+/// let database = ItemDatabase::default();
+/// assert_eq!(page.first_free_slot(&InventoryItem::new("potion", 1), &database), Some((0, 0)));
+/// assert!(page.get_item(1, 2).is_none());
+/// assert!(page.get_item(2, 0).is_none()); // Out of bounds
+///
+/// // A belt with a hole in the middle.
+/// let belt = InventoryPage::new("Belt", 1, 3).with_disabled_cells(&[(0, 1)]);
+/// assert!(belt.is_cell_enabled(0, 0));
+/// assert!(!belt.is_cell_enabled(0, 1));
+///
+/// // Only helmets go into the head slot.
+/// let equipment = InventoryPage::new("Equipment", 1, 2).with_slot_filter(0, 0, "armor.helmet".parse().unwrap());
+/// assert_eq!(equipment.get_slot_filter(0, 0).unwrap().get_text(), "armor.helmet");
+/// assert!(equipment.get_slot_filter(0, 1).is_none());
+/// assert_eq!(equipment.first_free_slot(&InventoryItem::new("potion", 1), &database), Some((0, 1)));
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InventoryPage {
+    name: String,
+    icon: Option<String>,
+    rows: usize,
+    columns: usize,
+    slots: Vec<Option<InventoryItem>>, // Row major
+    enabled: Vec<bool>,                // Row major cell mask
+    #[serde(default)]
+    filters: BTreeMap<(usize, usize), ItemQuery>, // (row, column) -> filter
+}
+
+impl InventoryPage {
+    pub fn new(name: impl Into<String>, rows: usize, columns: usize) -> Self {
+        Self {
+            name: name.into(),
+            icon: None,
+            rows,
+            columns,
+            slots: vec![None; rows * columns],
+            enabled: vec![true; rows * columns],
+            filters: BTreeMap::new(),
+        }
+    }
+
+    /// Disables the given cells, given as pairs of row and column.
+    pub fn with_disabled_cells(mut self, cells: &[(usize, usize)]) -> Self {
+        for &(x, y) in cells {
+            self.set_cell_enabled(x, y, false);
+        }
+        self
+    }
+
+    /// Returns true, if the cell in row `x` and column `y` exists and is enabled.
+    pub fn is_cell_enabled(&self, x: usize, y: usize) -> bool {
+        x < self.rows && y < self.columns && self.enabled[x * self.columns + y]
+    }
+
+    /// Enables or disables the cell in row `x` and column `y`.
+    ///
+    /// An item stored in a cell, that gets disabled, is removed and returned.
+    pub fn set_cell_enabled(&mut self, x: usize, y: usize, enabled: bool) -> Option<InventoryItem> {
+        if x >= self.rows || y >= self.columns {
+            return None;
+        }
+
+        let i = x * self.columns + y;
+        self.enabled[i] = enabled;
+
+        if enabled {
+            None
+        } else {
+            self.slots[i].take()
+        }
+    }
+
+    /// Sets the filter of the slot in row `x` and column `y`.
+    pub fn with_slot_filter(mut self, x: usize, y: usize, filter: ItemQuery) -> Self {
+        self.set_slot_filter(x, y, Some(filter));
+        self
+    }
+
+    /// Sets or removes the filter of the slot in row `x` and column `y`. Items already in the slot are kept.
+    pub fn set_slot_filter(&mut self, x: usize, y: usize, filter: Option<ItemQuery>) {
+        match filter {
+            Some(filter) if x < self.rows && y < self.columns => {
+                self.filters.insert((x, y), filter);
+            }
+            _ => {
+                self.filters.remove(&(x, y));
+            }
+        }
+    }
+
+    pub fn get_slot_filter(&self, x: usize, y: usize) -> Option<&ItemQuery> {
+        self.filters.get(&(x, y))
+    }
+
+    /// Sets the path to the icon shown on the tab of this page.
+    pub fn with_icon(mut self, icon: impl Into<String>) -> Self {
+        self.icon = Some(icon.into());
+        self
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_icon(&self) -> Option<&str> {
+        self.icon.as_deref()
+    }
+
+    pub fn get_rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn get_columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns the index of an enabled cell.
+    fn index(&self, x: usize, y: usize) -> Option<usize> {
+        if self.is_cell_enabled(x, y) {
+            Some(x * self.columns + y)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the item in row `x` and column `y`, if there is any.
+    pub fn get_item(&self, x: usize, y: usize) -> Option<&InventoryItem> {
+        self.index(x, y).and_then(|i| self.slots[i].as_ref())
+    }
+
+    pub(crate) fn get_slot_mut(&mut self, x: usize, y: usize) -> Option<&mut Option<InventoryItem>> {
+        self.index(x, y).map(|i| &mut self.slots[i])
+    }
+
+    /// Returns true, if the cell in row `x` and column `y` exists, is enabled and its filter, if any, matches `item`.
+    /// Slots with a filter do not accept broken items. Whether the slot is occupied does not matter.
+    pub fn accepts(&self, x: usize, y: usize, item: &InventoryItem, database: &ItemDatabase) -> bool {
+        if !self.is_cell_enabled(x, y) {
+            return false;
+        }
+
+        match self.get_slot_filter(x, y) {
+            Some(filter) => !item.is_broken() && filter.matches(item, database),
+            None => true,
+        }
+    }
+
+    /// Returns the row and column of the first empty slot, that accepts `item`, scanning row by row.
+    pub fn first_free_slot(&self, item: &InventoryItem, database: &ItemDatabase) -> Option<(usize, usize)> {
+        (0..self.slots.len())
+            .map(|i| (i / self.columns, i % self.columns))
+            .find(|&(x, y)| self.slots[x * self.columns + y].is_none() && self.accepts(x, y, item, database))
+    }
+
+    /// Returns true, if there is an empty enabled slot, regardless of its filter.
+    fn has_free_slot(&self) -> bool {
+        (0..self.slots.len()).any(|i| self.enabled[i] && self.slots[i].is_none())
+    }
+
+    /// Returns the first free slot accepting `item`, like `first_free_slot`. Fails with `FilterRejected`, if there are
+    /// free slots, but none accepts the item, and with `InventoryFull` otherwise.
+    fn find_slot(&self, item: &InventoryItem, database: &ItemDatabase) -> Result<(usize, usize), InventoryError> {
+        match self.first_free_slot(item, database) {
+            Some(slot) => Ok(slot),
+            None if self.has_free_slot() => Err(InventoryError::FilterRejected),
+            None => Err(InventoryError::InventoryFull),
+        }
+    }
+
+    /// Changes the grid size of the page.
+    ///
+    /// Items keep their position if possible. Items outside of the new grid are moved into the first free slots, that
+    /// accept them, and ejected, if there are none left. `page` is the index of this page and is used for the positions
+    /// in the report. Cells inside the old grid keep their mask and filter, new cells are enabled.
+    fn resize(&mut self, page: usize, rows: usize, columns: usize, database: &ItemDatabase) -> ResizeReport {
+        let mut report = ResizeReport::default();
+        let mut slots = vec![None; rows * columns];
+        let mut enabled = vec![true; rows * columns];
+        let mut outside = Vec::new();
+
+        for (i, slot) in self.slots.iter_mut().enumerate() {
+            let (x, y) = (i / self.columns, i % self.columns);
+            if x < rows && y < columns {
+                enabled[x * columns + y] = self.enabled[i];
+            }
+
+            let Some(item) = slot.take() else {
+                continue;
+            };
+
+            if x < rows && y < columns {
+                slots[x * columns + y] = Some(item);
+            } else {
+                outside.push((SlotPosition::new(page, x, y), item));
+            }
+        }
+
+        self.filters.retain(|&(x, y), _| x < rows && y < columns);
+        self.rows = rows;
+        self.columns = columns;
+        self.enabled = enabled;
+
+        for (from, item) in outside {
+            let free = (0..slots.len()).find(|&i| {
+                let (x, y) = (i / columns, i % columns);
+                slots[i].is_none() && self.accepts(x, y, &item, database)
+            });
+            match free {
+                Some(i) => {
+                    slots[i] = Some(item);
+                    report.relocated.push((from, SlotPosition::new(page, i / columns, i % columns)));
+                }
+                None => report.ejected.push((from, item)),
+            }
+        }
+
+        self.slots = slots;
+
+        report
+    }
+
+    /// Iterates over all items on this page together with their row and column.
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &InventoryItem)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.as_ref().map(|item| ((i / self.columns, i % self.columns), item)))
+    }
+}
+
+/// An inventory made of one or more pages.
+///
+/// The inventory ui shows the current page of the inventory entity set in `InventoryState`. If the inventory has more
+/// than one page, a tab is shown for every page.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let database = ItemDatabase::default();
+/// let mut inventory = Inventory::from_pages(vec![
+///     InventoryPage::new("Weapons", 4, 4),
+///     InventoryPage::new("Potions", 2, 8),
+/// ]);
+///
+/// let sword = SlotPosition::new(0, 1, 1);
+/// assert!(inventory.insert_item(sword, InventoryItem::new("sword", 1), &database).is_ok());
+/// assert_eq!(inventory.insert_item(sword, InventoryItem::new("axe", 1), &database), Err(InventoryError::Occupied));
+///
+/// // Move the sword onto the second page.
+/// let moved = inventory.move_item_to_page(sword, 1, &database).unwrap();
+/// assert_eq!(moved, SlotPosition::new(1, 0, 0));
+/// assert_eq!(inventory.get_item(moved).unwrap().get_item_id(), "sword");
+///
+/// assert!(inventory.take_item(moved).is_ok());
+/// assert!(inventory.get_item(moved).is_none());
+/// ```
+#[derive(Component, Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Inventory {
+    pages: Vec<InventoryPage>,
+    current_page: usize,
+    #[serde(default)]
+    max_weight: Option<f32>,
+}
+
+impl Inventory {
+    /// Creates an inventory with a single page.
+    pub fn new(rows: usize, columns: usize) -> Self {
+        Self::from_pages(vec![InventoryPage::new("", rows, columns)])
+    }
+
+    /// Creates an inventory from a list of pages. An inventory has at least one page.
+    pub fn from_pages(mut pages: Vec<InventoryPage>) -> Self {
+        if pages.is_empty() {
+            pages.push(InventoryPage::new("", 0, 0));
+        }
+
+        Self {
+            pages,
+            current_page: 0,
+            max_weight: None,
+        }
+    }
+
+    /// Limits the total weight of the items, see `get_weight`. Items, that would exceed it, are rejected with
+    /// `InventoryError::OverWeight`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_inventory_system::prelude::*;
+    /// let mut database = ItemDatabase::default();
+    /// database.add_item(ItemDefinition::new("anvil", "Anvil", "Items/anvil.png").with_weight(40.0));
+    ///
+    /// let mut backpack = Inventory::new(2, 2).with_max_weight(50.0);
+    /// assert!(backpack.add_item(InventoryItem::new("anvil", 1), &database).is_ok());
+    /// assert_eq!(backpack.add_item(InventoryItem::new("anvil", 1), &database), Err(InventoryError::OverWeight));
+    /// assert_eq!(backpack.count_item("anvil"), 1);
+    /// ```
+    pub fn with_max_weight(mut self, max_weight: f32) -> Self {
+        self.max_weight = Some(max_weight);
+        self
+    }
+
+    pub fn get_max_weight(&self) -> Option<f32> {
+        self.max_weight
+    }
+
+    /// Sets the maximum weight, or removes the limit with `None`. Items already in the inventory are kept.
+    pub fn set_max_weight(&mut self, max_weight: Option<f32>) {
+        self.max_weight = max_weight;
+    }
+
+    /// Returns true, if adding `extra` weight would exceed the maximum weight.
+    fn exceeds_max_weight(&self, extra: f32, database: &ItemDatabase) -> bool {
+        self.max_weight.is_some_and(|max| self.get_weight(database) + extra > max)
+    }
+
+    pub fn add_page(&mut self, page: InventoryPage) {
+        self.pages.push(page);
+    }
+
+    pub fn get_pages(&self) -> &[InventoryPage] {
+        &self.pages
+    }
+
+    pub fn get_page(&self, page: usize) -> Option<&InventoryPage> {
+        self.pages.get(page)
+    }
+
+    pub fn get_page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    // Getter for `current_page`
+    pub fn get_current_page(&self) -> usize {
+        self.current_page
+    }
+
+    // Setter for `current_page`. The value is clamped to the existing pages.
+    pub fn set_current_page(&mut self, value: usize) {
+        self.current_page = value.min(self.pages.len() - 1);
+    }
+
+    /// Changes the grid size of every page of the inventory, e.g. when the player unlocks a larger backpack.
+    ///
+    /// Growing keeps every item in place. When shrinking, items outside of the new grid are relocated to free slots of
+    /// their page. The returned report lists the relocated items and hands back the items that did not fit.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_inventory_system::prelude::*;
+    /// let database = ItemDatabase::default();
+    /// let mut inventory = Inventory::new(2, 2);
+    /// inventory.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("apple", 1), &database);
+    /// inventory.insert_item(SlotPosition::new(0, 1, 1), InventoryItem::new("pear", 1), &database);
+    ///
+    /// // Growing keeps the positions.
+    /// assert!(inventory.resize_inventory(3, 3, &database).is_complete());
+    /// assert!(inventory.get_item(SlotPosition::new(0, 1, 1)).is_some());
+    ///
+    /// // The pear is relocated into the free slot of the single remaining row.
+    /// let report = inventory.resize_inventory(1, 2, &database);
+    /// assert_eq!(report.get_relocated(), &[(SlotPosition::new(0, 1, 1), SlotPosition::new(0, 0, 1))]);
+    ///
+    /// // There is no room left for the pear.
+    /// let mut report = inventory.resize_inventory(1, 1, &database);
+    /// let ejected = report.take_ejected();
+    /// assert_eq!(ejected[0].1.get_item_id(), "pear");
+    /// ```
+    pub fn resize_inventory(&mut self, rows: usize, columns: usize, database: &ItemDatabase) -> ResizeReport {
+        let mut report = ResizeReport::default();
+        for page in 0..self.pages.len() {
+            report.append(self.pages[page].resize(page, rows, columns, database));
+        }
+
+        report
+    }
+
+    /// Changes the grid size of a single page. See `resize_inventory`.
+    pub fn resize_page(&mut self, page: usize, rows: usize, columns: usize, database: &ItemDatabase) -> ResizeReport {
+        match self.pages.get_mut(page) {
+            Some(p) => p.resize(page, rows, columns, database),
+            None => ResizeReport::default(),
+        }
+    }
+
+    /// Returns true, if the slot at the given position exists and is enabled.
+    pub fn is_cell_enabled(&self, pos: SlotPosition) -> bool {
+        self.pages.get(pos.page).is_some_and(|p| p.is_cell_enabled(pos.x, pos.y))
+    }
+
+    /// Enables or disables the slot at the given position. An item stored in a slot, that gets disabled, is removed
+    /// and returned.
+    pub fn set_cell_enabled(&mut self, pos: SlotPosition, enabled: bool) -> Option<InventoryItem> {
+        self.pages.get_mut(pos.page).and_then(|p| p.set_cell_enabled(pos.x, pos.y, enabled))
+    }
+
+    /// Returns the item at the given position, if there is any.
+    pub fn get_item(&self, pos: SlotPosition) -> Option<&InventoryItem> {
+        self.pages.get(pos.page).and_then(|p| p.get_item(pos.x, pos.y))
+    }
+
+    pub(crate) fn get_item_mut(&mut self, pos: SlotPosition) -> Option<&mut InventoryItem> {
+        self.get_slot_mut(pos).and_then(|slot| slot.as_mut())
+    }
+
+    fn get_slot_mut(&mut self, pos: SlotPosition) -> Option<&mut Option<InventoryItem>> {
+        self.pages.get_mut(pos.page).and_then(|p| p.get_slot_mut(pos.x, pos.y))
+    }
+
+    /// Returns the slot at the given position, or `OutOfBounds`, if it does not exist or is disabled.
+    fn slot_mut(&mut self, pos: SlotPosition) -> Result<&mut Option<InventoryItem>, InventoryError> {
+        self.get_slot_mut(pos).ok_or(InventoryError::OutOfBounds)
+    }
+
+    /// Returns true, if the slot at `pos` exists, is enabled and its filter, if any, matches `item`. Whether the slot
+    /// is occupied does not matter.
+    ///
+    /// Slots with a filter, like equipment slots, do not accept broken items, see `BrokenItemOutcome::KeepUnusable`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_inventory_system::prelude::*;
+    /// let mut database = ItemDatabase::default();
+    /// database.add_item(ItemDefinition::new("helmet", "Helmet", "Items/helmet.png").with_tag("armor.helmet"));
+    ///
+    /// let head = SlotPosition::new(0, 0, 0);
+    /// let page = InventoryPage::new("Equipment", 1, 2).with_slot_filter(0, 0, "armor.helmet".parse().unwrap());
+    /// let mut inventory = Inventory::from_pages(vec![page]);
+    ///
+    /// let mut helmet = InventoryItem::new("helmet", 1).with_durability(10);
+    /// assert!(inventory.accepts(head, &helmet, &database));
+    ///
+    /// helmet.consume_durability(10);
+    /// assert!(!inventory.accepts(head, &helmet, &database));
+    /// assert!(inventory.accepts(SlotPosition::new(0, 0, 1), &helmet, &database));
+    /// ```
+    pub fn accepts(&self, pos: SlotPosition, item: &InventoryItem, database: &ItemDatabase) -> bool {
+        self.pages.get(pos.page).is_some_and(|p| p.accepts(pos.x, pos.y, item, database))
+    }
+
+    /// Puts an item into an empty slot. Fails, if the slot does not exist, is disabled, is occupied, its filter rejects
+    /// the item, see `accepts`, or the item would exceed the maximum weight.
+    pub fn insert_item(
+        &mut self,
+        pos: SlotPosition,
+        item: InventoryItem,
+        database: &ItemDatabase,
+    ) -> Result<(), InventoryError> {
+        if self.slot_mut(pos)?.is_some() {
+            return Err(InventoryError::Occupied);
+        }
+        if !self.accepts(pos, &item, database) {
+            return Err(InventoryError::FilterRejected);
+        }
+        if self.exceeds_max_weight(item_weight(&item, database), database) {
+            return Err(InventoryError::OverWeight);
+        }
+
+        *self.slot_mut(pos)? = Some(item);
+        Ok(())
+    }
+
+    /// Replaces the contents of the slot at `pos` without checking its filter, e.g. to restore an earlier state of
+    /// the slot. Returns the previous item.
+    pub(crate) fn replace_slot(
+        &mut self,
+        pos: SlotPosition,
+        item: Option<InventoryItem>,
+    ) -> Result<Option<InventoryItem>, InventoryError> {
+        Ok(std::mem::replace(self.slot_mut(pos)?, item))
+    }
+
+    /// Removes the item at the given position and returns it.
+    pub fn take_item(&mut self, pos: SlotPosition) -> Result<InventoryItem, InventoryError> {
+        self.slot_mut(pos)?.take().ok_or(InventoryError::ItemNotFound)
+    }
+
+    /// Moves the item at `from` to `to`. If `to` is occupied, the two items are swapped.
+    ///
+    /// Fails, if one of the slots does not exist or is disabled, there is no item at `from`, both are the same or the
+    /// filter of a slot rejects the item, that would end up in it, see `accepts`.
+    pub fn move_item(
+        &mut self,
+        from: SlotPosition,
+        to: SlotPosition,
+        database: &ItemDatabase,
+    ) -> Result<(), InventoryError> {
+        self.slot_mut(to)?;
+        if self.slot_mut(from)?.is_none() {
+            return Err(InventoryError::ItemNotFound);
+        }
+        if from == to {
+            return Err(InventoryError::SameSlot);
+        }
+
+        let rejects = |pos, item: Option<&InventoryItem>| item.is_some_and(|item| !self.accepts(pos, item, database));
+        if rejects(to, self.get_item(from)) || rejects(from, self.get_item(to)) {
+            return Err(InventoryError::FilterRejected);
+        }
+
+        let item = self.slot_mut(from)?.take();
+        let previous = self.slot_mut(to)?.take();
+
+        *self.slot_mut(to)? = item;
+        *self.slot_mut(from)? = previous;
+
+        Ok(())
+    }
+
+    /// Puts an item into the first free slot of a page, that accepts it. Returns the position of the item.
+    ///
+    /// Fails, if the page does not exist, is full or the filters of all free slots reject the item.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_inventory_system::prelude::*;
+    /// let mut database = ItemDatabase::default();
+    /// database.add_item(ItemDefinition::new("ring", "Ring", "Items/ring.png").with_tag("jewelry.ring"));
+    ///
+    /// let page = InventoryPage::new("Equipment", 1, 2).with_slot_filter(0, 0, "jewelry.ring".parse().unwrap());
+    /// let mut inventory = Inventory::from_pages(vec![page]);
+    ///
+    /// // The bread skips the ring slot.
+    /// assert_eq!(inventory.add_item(InventoryItem::new("bread", 1), &database), Ok(SlotPosition::new(0, 0, 1)));
+    /// assert_eq!(inventory.add_item(InventoryItem::new("bread", 1), &database), Err(InventoryError::FilterRejected));
+    /// assert_eq!(inventory.add_item(InventoryItem::new("ring", 1), &database), Ok(SlotPosition::new(0, 0, 0)));
+    ///
+    /// let result = inventory.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("ring", 1), &database);
+    /// assert_eq!(result, Err(InventoryError::Occupied));
+    /// ```
+    pub fn add_item_to_page(
+        &mut self,
+        page: usize,
+        item: InventoryItem,
+        database: &ItemDatabase,
+    ) -> Result<SlotPosition, InventoryError> {
+        let p = self.pages.get(page).ok_or(InventoryError::OutOfBounds)?;
+        let (x, y) = p.find_slot(&item, database)?;
+        let pos = SlotPosition::new(page, x, y);
+        self.insert_item(pos, item, database)?;
+        Ok(pos)
+    }
+
+    /// Puts an item into the first free slot of the inventory, that accepts it, searching the pages in order. Returns
+    /// the position of the item. Fails, if the inventory is full or the filters of all free slots reject the item.
+    pub fn add_item(&mut self, item: InventoryItem, database: &ItemDatabase) -> Result<SlotPosition, InventoryError> {
+        match (0..self.pages.len()).find(|&p| self.pages[p].first_free_slot(&item, database).is_some()) {
+            Some(page) => self.add_item_to_page(page, item, database),
+            None if self.pages.iter().any(|p| p.has_free_slot()) => Err(InventoryError::FilterRejected),
+            None => Err(InventoryError::InventoryFull),
+        }
+    }
+
+    /// Moves the item at `from` into the first free slot of another page, that accepts it. Returns the new position of
+    /// the item, which is `from`, if the item is already on the page.
+    pub fn move_item_to_page(
+        &mut self,
+        from: SlotPosition,
+        page: usize,
+        database: &ItemDatabase,
+    ) -> Result<SlotPosition, InventoryError> {
+        let item = self.get_item(from).ok_or(InventoryError::ItemNotFound)?;
+        if from.page == page {
+            return Ok(from);
+        }
+
+        let p = self.pages.get(page).ok_or(InventoryError::OutOfBounds)?;
+        let (x, y) = p.find_slot(item, database)?;
+        let to = SlotPosition::new(page, x, y);
+
+        self.move_item(from, to, database)?;
+        Ok(to)
+    }
+
+    /// Returns the position of the item instance with the given id.
+    pub fn find_item(&self, instance_id: ItemInstanceId) -> Option<SlotPosition> {
+        self.pages.iter().enumerate().find_map(|(page, p)| {
+            p.iter()
+                .find(|(_, item)| item.get_instance_id() == instance_id)
+                .map(|((x, y), _)| SlotPosition::new(page, x, y))
+        })
+    }
+
+    /// Moves as many items as possible from the stack at `from` onto the stack at `to`, without exceeding
+    /// `max_stack`. The stack at `from` is removed, once it is empty.
+    ///
+    /// Fails and moves nothing, if one of the stacks is missing, both are the same, the items do not stack, see
+    /// `InventoryItem::can_stack_with`, or the target stack is full.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_inventory_system::prelude::*;
+    /// let database = ItemDatabase::default();
+    /// let mut inventory = Inventory::new(1, 3);
+    /// let (a, b) = (SlotPosition::new(0, 0, 0), SlotPosition::new(0, 0, 1));
+    /// inventory.insert_item(a, InventoryItem::new("arrow", 15), &database);
+    /// inventory.insert_item(b, InventoryItem::new("arrow", 10), &database);
+    ///
+    /// assert!(inventory.merge_stacks(a, b, 20).is_ok());
+    /// assert_eq!(inventory.get_item(a).unwrap().get_count(), 5);
+    /// assert_eq!(inventory.get_item(b).unwrap().get_count(), 20);
+    /// assert_eq!(inventory.merge_stacks(a, b, 20), Err(InventoryError::StackFull));
+    /// ```
+    pub fn merge_stacks(
+        &mut self,
+        from: SlotPosition,
+        to: SlotPosition,
+        max_stack: u32,
+    ) -> Result<(), InventoryError> {
+        let (Some(source), Some(target)) = (self.get_item(from), self.get_item(to)) else {
+            return Err(InventoryError::ItemNotFound);
+        };
+
+        if from == to {
+            return Err(InventoryError::SameSlot);
+        }
+        if !source.can_stack_with(target) {
+            return Err(InventoryError::NotStackable);
+        }
+
+        let amount = source.get_count().min(max_stack.saturating_sub(target.get_count()));
+        if amount == 0 {
+            return Err(InventoryError::StackFull);
+        }
+
+        let remaining = source.get_count() - amount;
+        let target = self.get_item_mut(to).unwrap();
+        target.set_count(target.get_count() + amount);
+
+        let slot = self.get_slot_mut(from).unwrap();
+        match remaining {
+            0 => *slot = None,
+            n => slot.as_mut().unwrap().set_count(n),
+        }
+
+        Ok(())
+    }
+
+    /// Moves the item at `from` into the empty slot `to` of another inventory. The item keeps its instance id and
+    /// data.
+    ///
+    /// Fails, if there is no item at `from` or `to` does not exist, is disabled, is occupied or does not accept the
+    /// item, or the item would exceed the maximum weight of `target`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_inventory_system::prelude::*;
+    /// let database = ItemDatabase::default();
+    /// let mut backpack = Inventory::new(2, 2);
+    /// let mut chest = Inventory::new(4, 4);
+    ///
+    /// let pos = SlotPosition::new(0, 0, 0);
+    /// let mut ring = InventoryItem::new("ring", 1);
+    /// ring.get_data_mut().set("engraving", "For Ada");
+    /// let id = ring.get_instance_id();
+    /// backpack.insert_item(pos, ring, &database);
+    ///
+    /// assert!(backpack.transfer_item(pos, &mut chest, SlotPosition::new(0, 3, 3), &database).is_ok());
+    /// assert_eq!(chest.find_item(id), Some(SlotPosition::new(0, 3, 3)));
+    /// assert!(backpack.find_item(id).is_none());
+    ///
+    /// // Instances and their data survive saving and loading.
+    /// let saved = ron::to_string(&chest).unwrap();
+    /// let loaded: Inventory = ron::from_str(&saved).unwrap();
+    /// let ring = loaded.get_item(SlotPosition::new(0, 3, 3)).unwrap();
+    /// assert_eq!(ring.get_instance_id(), id);
+    /// assert_eq!(ring.get_data().get_text("engraving"), Some("For Ada"));
+    /// ```
+    pub fn transfer_item(
+        &mut self,
+        from: SlotPosition,
+        target: &mut Inventory,
+        to: SlotPosition,
+        database: &ItemDatabase,
+    ) -> Result<(), InventoryError> {
+        let item = self.get_item(from).ok_or(InventoryError::ItemNotFound)?;
+        if target.slot_mut(to)?.is_some() {
+            return Err(InventoryError::Occupied);
+        }
+        if !target.accepts(to, item, database) {
+            return Err(InventoryError::FilterRejected);
+        }
+        if target.exceeds_max_weight(item_weight(item, database), database) {
+            return Err(InventoryError::OverWeight);
+        }
+
+        let item = self.take_item(from)?;
+        target.insert_item(to, item, database)
+    }
+
+    /// Returns the number of items with the given id on all pages.
+    pub fn count_item(&self, item_id: &str) -> u32 {
+        self.pages
+            .iter()
+            .flat_map(|p| p.iter())
+            .filter(|(_, item)| item.get_item_id() == item_id)
+            .map(|(_, item)| item.get_count())
+            .sum()
+    }
+
+    /// Removes `count` items with the given id, taking them from the stacks in page, row and column order.
+    ///
+    /// Fails and removes nothing, if there are not enough items.
+    pub fn remove_items(&mut self, item_id: &str, count: u32) -> Result<(), InventoryError> {
+        if self.count_item(item_id) < count {
+            return Err(InventoryError::ItemNotFound);
+        }
+
+        let mut remaining = count;
+        for page in self.pages.iter_mut() {
+            for slot in page.slots.iter_mut() {
+                if remaining == 0 {
+                    return Ok(());
+                }
+
+                let Some(item) = slot.as_mut().filter(|i| i.get_item_id() == item_id) else {
+                    continue;
+                };
+
+                let amount = item.get_count().min(remaining);
+                remaining -= amount;
+                if amount == item.get_count() {
+                    *slot = None;
+                } else {
+                    item.set_count(item.get_count() - amount);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of items matching `filter`, including the contents of containers. The contents of a
+    /// matching container are counted as part of it and not on their own.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_inventory_system::prelude::*;
+    /// let mut database = ItemDatabase::default();
+    /// database.add_item(ItemDefinition::new("wolf_pelt", "Wolf Pelt", "Items/wolf_pelt.png").with_tag("pelt"));
+    /// database.add_item(ItemDefinition::new("bear_pelt", "Bear Pelt", "Items/bear_pelt.png").with_tag("pelt"));
+    ///
+    /// let mut pouch = Inventory::new(1, 2);
+    /// pouch.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("wolf_pelt", 2), &database);
+    /// let mut inventory = Inventory::new(2, 2);
+    /// let pouch = InventoryItem::new("pouch", 1).with_contents(pouch);
+    /// inventory.insert_item(SlotPosition::new(0, 0, 0), pouch, &database);
+    /// inventory.insert_item(SlotPosition::new(0, 1, 0), InventoryItem::new("wolf_pelt", 2), &database);
+    /// inventory.insert_item(SlotPosition::new(0, 1, 1), InventoryItem::new("bear_pelt", 1), &database);
+    ///
+    /// let pelts = ItemFilter::tag("pelt");
+    /// assert_eq!(inventory.count_matching(&pelts, &database), 5);
+    /// assert_eq!(inventory.find_matching(&ItemFilter::id("wolf_pelt"), &database).len(), 2);
+    ///
+    /// // The stacks are emptied in the order of `get_nested_items`: the pouch first.
+    /// assert_eq!(inventory.remove_matching(&ItemFilter::id("wolf_pelt"), &database, 3), 3);
+    /// assert_eq!(inventory.count_matching(&pelts, &database), 2);
+    /// assert_eq!(inventory.get_item(SlotPosition::new(0, 1, 0)).unwrap().get_count(), 1);
+    /// ```
+    pub fn count_matching(&self, filter: &ItemFilter, database: &ItemDatabase) -> u32 {
+        self.pages
+            .iter()
+            .flat_map(|p| p.iter())
+            .map(|(_, item)| {
+                if filter.matches(item, database) {
+                    item.get_count()
+                } else {
+                    item.get_contents().map_or(0, |c| c.count_matching(filter, database))
+                }
+            })
+            .sum()
+    }
+
+    /// Returns the locations of the stacks matching `filter` in the order of `get_nested_items`. The contents of a
+    /// matching container are left out.
+    pub fn find_matching(&self, filter: &ItemFilter, database: &ItemDatabase) -> Vec<ItemLocation> {
+        let mut locations = Vec::new();
+        self.collect_matching(None, filter, database, &mut locations);
+        locations
+    }
+
+    fn collect_matching(
+        &self,
+        container: Option<ItemInstanceId>,
+        filter: &ItemFilter,
+        database: &ItemDatabase,
+        locations: &mut Vec<ItemLocation>,
+    ) {
+        for (page, p) in self.pages.iter().enumerate() {
+            for ((x, y), item) in p.iter() {
+                if filter.matches(item, database) {
+                    locations.push(ItemLocation::new(container, SlotPosition::new(page, x, y)));
+                } else if let Some(contents) = item.get_contents() {
+                    contents.collect_matching(Some(item.get_instance_id()), filter, database, locations);
+                }
+            }
+        }
+    }
+
+    /// Removes up to `count` items matching `filter`, taking them from the stacks in the order of `get_nested_items`.
+    /// A matching container is removed together with its contents.
+    ///
+    /// Returns the number of removed items.
+    pub fn remove_matching(&mut self, filter: &ItemFilter, database: &ItemDatabase, count: u32) -> u32 {
+        let mut remaining = count;
+        for slot in self.pages.iter_mut().flat_map(|p| p.slots.iter_mut()) {
+            if remaining == 0 {
+                break;
+            }
+
+            let Some(item) = slot.as_mut() else {
+                continue;
+            };
+
+            if filter.matches(item, database) {
+                let amount = item.get_count().min(remaining);
+                remaining -= amount;
+                if amount == item.get_count() {
+                    *slot = None;
+                } else {
+                    item.set_count(item.get_count() - amount);
+                }
+            } else if let Some(contents) = item.get_contents_mut() {
+                remaining -= contents.remove_matching(filter, database, remaining);
+            }
+        }
+
+        count - remaining
+    }
+
+    /// Reduces the durability of the item at the given position by `amount`.
+    ///
+    /// Returns true, if the item broke because of this call. The item stays in its slot, the outcome of breaking is
+    /// applied with `break_item`.
+    pub fn consume_durability(&mut self, pos: SlotPosition, amount: u32) -> Result<bool, InventoryError> {
+        let item = self.get_item_mut(pos).ok_or(InventoryError::ItemNotFound)?;
+        Ok(item.consume_durability(amount))
+    }
+
+    /// Applies the outcome of breaking to the item at the given position and returns a copy of the broken item.
+    ///
+    /// With `BrokenItemOutcome::KeepUnusable` the item is marked as broken, even if its durability has not been used
+    /// up, and stays in its slot.
+    pub fn break_item(
+        &mut self,
+        pos: SlotPosition,
+        outcome: &BrokenItemOutcome,
+    ) -> Result<InventoryItem, InventoryError> {
+        let broken = self.get_item(pos).ok_or(InventoryError::ItemNotFound)?.clone();
+
+        match outcome {
+            BrokenItemOutcome::Destroy => {
+                self.take_item(pos)?;
+            }
+            BrokenItemOutcome::Replace(item_id) => {
+                let item = self.get_item_mut(pos).ok_or(InventoryError::ItemNotFound)?;
+                item.set_item_id(item_id.clone());
+                item.set_durability(None);
+            }
+            BrokenItemOutcome::KeepUnusable => {
+                self.get_item_mut(pos).ok_or(InventoryError::ItemNotFound)?.set_broken();
+            }
+        }
+
+        Ok(broken)
+    }
+
+    /// Restores the full durability of the item at the given position, consuming the given materials, given as pairs
+    /// of item id and count, from this inventory.
+    ///
+    /// Fails and consumes nothing, if the item is not damaged or there are not enough materials.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_inventory_system::prelude::*;
+    /// let database = ItemDatabase::default();
+    /// let mut inventory = Inventory::new(2, 2);
+    /// let sword = SlotPosition::new(0, 0, 0);
+    /// inventory.insert_item(sword, InventoryItem::new("sword", 1).with_durability(50), &database);
+    /// inventory.insert_item(SlotPosition::new(0, 1, 0), InventoryItem::new("iron_ingot", 3), &database);
+    ///
+    /// let materials = vec![("iron_ingot".to_string(), 2)];
+    /// assert_eq!(inventory.repair_item(sword, &materials), Err(InventoryError::NotDamaged));
+    ///
+    /// assert_eq!(inventory.consume_durability(sword, 50), Ok(true));
+    /// inventory.break_item(sword, &BrokenItemOutcome::KeepUnusable).unwrap();
+    /// assert!(inventory.get_item(sword).unwrap().is_broken());
+    ///
+    /// assert!(inventory.repair_item(sword, &materials).is_ok());
+    /// assert!(!inventory.get_item(sword).unwrap().is_broken());
+    /// assert_eq!(inventory.count_item("iron_ingot"), 1);
+    ///
+    /// inventory.consume_durability(sword, 10).unwrap();
+    /// assert_eq!(inventory.repair_item(sword, &materials), Err(InventoryError::MissingMaterials));
+    /// ```
+    pub fn repair_item(&mut self, pos: SlotPosition, materials: &[(String, u32)]) -> Result<(), InventoryError> {
+        let item = self.get_item(pos).ok_or(InventoryError::ItemNotFound)?;
+        if !item.get_durability().is_some_and(|d| d.is_damaged()) {
+            return Err(InventoryError::NotDamaged);
+        }
+
+        // The repaired item itself is never consumed as material.
+        let mut item = self.take_item(pos)?;
+
+        let affordable = materials.iter().all(|(id, count)| self.count_item(id) >= *count);
+        if affordable {
+            for (id, count) in materials {
+                self.remove_items(id, *count)?;
+            }
+            item.repair();
+        }
+
+        self.replace_slot(pos, Some(item))?;
+        if affordable {
+            Ok(())
+        } else {
+            Err(InventoryError::MissingMaterials)
+        }
+    }
+
+    /// Returns the total weight of all items, including the contents of containers.
+    ///
+    /// Items without a definition weigh nothing.
+    pub fn get_weight(&self, database: &ItemDatabase) -> f32 {
+        self.pages.iter().flat_map(|p| p.iter()).map(|(_, item)| item_weight(item, database)).sum()
+    }
+
+    /// Returns all items together with their location, including the contents of containers.
+    ///
+    /// The items are ordered by page, row and column. The contents of a container follow right after it.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_inventory_system::prelude::*;
+    /// let database = ItemDatabase::default();
+    /// let mut inventory = Inventory::new(1, 2);
+    /// let pouch = InventoryItem::new("pouch", 1).with_contents(Inventory::new(1, 1));
+    /// let pouch_id = pouch.get_instance_id();
+    /// let (first, second) = (SlotPosition::new(0, 0, 0), SlotPosition::new(0, 0, 1));
+    /// inventory.insert_item(first, pouch, &database);
+    /// inventory.insert_item(second, InventoryItem::new("coin", 5), &database);
+    /// inventory.move_nested_item(&[], second, &[first], first, &database);
+    ///
+    /// let items = inventory.get_nested_items();
+    /// assert_eq!(items.len(), 2);
+    /// assert_eq!(items[1].0, ItemLocation::new(Some(pouch_id), SlotPosition::new(0, 0, 0)));
+    /// assert_eq!(items[1].1.get_item_id(), "coin");
+    /// ```
+    pub fn get_nested_items(&self) -> Vec<(ItemLocation, &InventoryItem)> {
+        let mut items = Vec::new();
+        self.collect_nested_items(None, &mut items);
+        items
+    }
+
+    fn collect_nested_items<'a>(
+        &'a self,
+        container: Option<ItemInstanceId>,
+        items: &mut Vec<(ItemLocation, &'a InventoryItem)>,
+    ) {
+        for (page, p) in self.pages.iter().enumerate() {
+            for ((x, y), item) in p.iter() {
+                items.push((ItemLocation::new(container, SlotPosition::new(page, x, y)), item));
+                if let Some(contents) = item.get_contents() {
+                    contents.collect_nested_items(Some(item.get_instance_id()), items);
+                }
+            }
+        }
+    }
+
+    /// Returns the inventory at the end of a path of container positions. Every position of the path refers to a
+    /// container item inside the inventory of the previous one. An empty path refers to this inventory.
+    pub fn get_container(&self, path: &[SlotPosition]) -> Option<&Inventory> {
+        match path.split_first() {
+            None => Some(self),
+            Some((first, rest)) => self.get_item(*first)?.get_contents()?.get_container(rest),
+        }
+    }
+
+    /// Returns the inventory at the end of a path of container positions. See `get_container`.
+    pub fn get_container_mut(&mut self, path: &[SlotPosition]) -> Option<&mut Inventory> {
+        match path.split_first() {
+            None => Some(self),
+            Some((first, rest)) => self.get_item_mut(*first)?.get_contents_mut()?.get_container_mut(rest),
+        }
+    }
+
+    /// Returns the path to the item instance with the given id, searching the contents of containers, too. The last
+    /// position of the path is the position of the item, the others are the positions of its containers.
+    pub fn find_item_path(&self, instance_id: ItemInstanceId) -> Option<Vec<SlotPosition>> {
+        if let Some(pos) = self.find_item(instance_id) {
+            return Some(vec![pos]);
+        }
+
+        self.pages.iter().enumerate().find_map(|(page, p)| {
+            p.iter().find_map(|((x, y), item)| {
+                let mut path = item.get_contents()?.find_item_path(instance_id)?;
+                path.insert(0, SlotPosition::new(page, x, y));
+                Some(path)
+            })
+        })
+    }
+
+    /// Moves the item at `from` inside the container at `from_path` into the empty slot `to` inside the container at
+    /// `to_path`. The paths are relative to this inventory, see `get_container`.
+    ///
+    /// Fails, if there is no item at `from`, `to` is not an empty enabled slot accepting the item, the item would be
+    /// put inside itself or exceed the maximum weight of a container it enters.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_inventory_system::prelude::*;
+    /// let mut database = ItemDatabase::default();
+    /// database.add_item(ItemDefinition::new("pouch", "Pouch", "Items/pouch.png").with_weight(0.5));
+    /// database.add_item(ItemDefinition::new("coin", "Coin", "Items/coin.png").with_weight(0.1));
+    ///
+    /// let mut inventory = Inventory::new(2, 2);
+    /// let pouch = SlotPosition::new(0, 0, 0);
+    /// let coins = SlotPosition::new(0, 0, 1);
+    /// inventory.insert_item(pouch, InventoryItem::new("pouch", 1).with_contents(Inventory::new(1, 4)), &database);
+    /// inventory.insert_item(coins, InventoryItem::new("coin", 10), &database);
+    ///
+    /// // Put the coins into the pouch. The weight of the pouch includes its contents.
+    /// let inside = SlotPosition::new(0, 0, 3);
+    /// assert!(inventory.move_nested_item(&[], coins, &[pouch], inside, &database).is_ok());
+    /// assert_eq!(inventory.get_container(&[pouch]).unwrap().get_item(inside).unwrap().get_count(), 10);
+    /// assert!((inventory.get_weight(&database) - 1.5).abs() < 0.001);
+    ///
+    /// // A container can not be put inside itself.
+    /// let result = inventory.move_nested_item(&[], pouch, &[pouch], SlotPosition::new(0, 0, 0), &database);
+    /// assert_eq!(result, Err(InventoryError::InsideItself));
+    ///
+    /// // Nested contents survive saving and loading.
+    /// let loaded: Inventory = ron::from_str(&ron::to_string(&inventory).unwrap()).unwrap();
+    /// assert_eq!(loaded, inventory);
+    /// ```
+    pub fn move_nested_item(
+        &mut self,
+        from_path: &[SlotPosition],
+        from: SlotPosition,
+        to_path: &[SlotPosition],
+        to: SlotPosition,
+        database: &ItemDatabase,
+    ) -> Result<(), InventoryError> {
+        // The target must not be the moved item itself or lie inside of it.
+        if to_path.len() > from_path.len() && to_path.starts_with(from_path) && to_path[from_path.len()] == from {
+            return Err(InventoryError::InsideItself);
+        }
+
+        let item = self.get_container(from_path).and_then(|c| c.get_item(from)).ok_or(InventoryError::ItemNotFound)?;
+        let target = self.get_container(to_path).ok_or(InventoryError::OutOfBounds)?;
+        if !target.is_cell_enabled(to) {
+            return Err(InventoryError::OutOfBounds);
+        }
+        if target.get_item(to).is_some() {
+            return Err(InventoryError::Occupied);
+        }
+        if !target.accepts(to, item, database) {
+            return Err(InventoryError::FilterRejected);
+        }
+
+        // Every container, that the item enters, has to carry its weight.
+        let weight = item_weight(item, database);
+        for i in 0..=to_path.len() {
+            let entered = !from_path.starts_with(&to_path[..i]);
+            if entered && self.get_container(&to_path[..i]).is_some_and(|c| c.exceeds_max_weight(weight, database)) {
+                return Err(InventoryError::OverWeight);
+            }
+        }
+
+        let item = self.get_container_mut(from_path).ok_or(InventoryError::OutOfBounds)?.take_item(from)?;
+        self.get_container_mut(to_path).ok_or(InventoryError::OutOfBounds)?.insert_item(to, item, database)
+    }
+}
+
+/// Returns the weight of a stack, including the contents of a container.
+fn item_weight(item: &InventoryItem, database: &ItemDatabase) -> f32 {
+    let weight = database.get_item(item.get_item_id()).map_or(0.0, |d| d.get_weight());
+    let contents = item.get_contents().map_or(0.0, |c| c.get_weight(database));
+    weight * item.get_count() as f32 + contents
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item_database::ItemDefinition;
+
+    fn database() -> ItemDatabase {
+        let mut database = ItemDatabase::default();
+        database.add_item(ItemDefinition::new("helmet", "Helmet", "Items/helmet.png").with_tag("armor.helmet"));
+        database.add_item(ItemDefinition::new("bread", "Bread", "Items/bread.png").with_tag("food"));
+        database
+    }
+
+    /// A page with a helmet slot followed by an unfiltered slot.
+    fn equipment() -> Inventory {
+        let page = InventoryPage::new("Equipment", 1, 2).with_slot_filter(0, 0, "armor.helmet".parse().unwrap());
+        Inventory::from_pages(vec![page])
+    }
+
+    #[test]
+    fn insert_into_filtered_slot_is_rejected() {
+        let database = database();
+        let mut inventory = equipment();
+        let head = SlotPosition::new(0, 0, 0);
+
+        let result = inventory.insert_item(head, InventoryItem::new("bread", 1), &database);
+        assert_eq!(result, Err(InventoryError::FilterRejected));
+        assert!(inventory.get_item(head).is_none());
+        assert_eq!(inventory.insert_item(head, InventoryItem::new("helmet", 1), &database), Ok(()));
+    }
+
+    #[test]
+    fn add_skips_rejecting_slots() {
+        let database = database();
+        let mut inventory = equipment();
+
+        assert_eq!(inventory.add_item(InventoryItem::new("bread", 1), &database), Ok(SlotPosition::new(0, 0, 1)));
+        assert_eq!(inventory.add_item(InventoryItem::new("bread", 1), &database), Err(InventoryError::FilterRejected));
+        assert_eq!(inventory.count_item("bread"), 1);
+        assert_eq!(inventory.add_item(InventoryItem::new("helmet", 1), &database), Ok(SlotPosition::new(0, 0, 0)));
+        assert_eq!(inventory.add_item(InventoryItem::new("helmet", 1), &database), Err(InventoryError::InventoryFull));
+    }
+
+    #[test]
+    fn broken_items_are_rejected_by_filtered_slots() {
+        let database = database();
+        let mut inventory = equipment();
+        let mut helmet = InventoryItem::new("helmet", 1).with_durability(10);
+        helmet.set_broken();
+
+        assert_eq!(inventory.add_item(helmet, &database), Ok(SlotPosition::new(0, 0, 1)));
+    }
+
+    #[test]
+    fn transfer_into_rejecting_slot_changes_nothing() {
+        let database = database();
+        let mut backpack = Inventory::new(1, 1);
+        let pos = SlotPosition::new(0, 0, 0);
+        backpack.insert_item(pos, InventoryItem::new("bread", 1), &database).unwrap();
+        let mut target = equipment();
+
+        assert_eq!(backpack.transfer_item(pos, &mut target, pos, &database), Err(InventoryError::FilterRejected));
+        assert!(backpack.get_item(pos).is_some());
+        assert!(target.get_nested_items().is_empty());
+    }
+
+    #[test]
+    fn move_to_page_without_accepting_slot_changes_nothing() {
+        let database = database();
+        let mut inventory = Inventory::from_pages(vec![
+            InventoryPage::new("Bag", 1, 1),
+            InventoryPage::new("Equipment", 1, 1).with_slot_filter(0, 0, "armor.helmet".parse().unwrap()),
+        ]);
+        let pos = SlotPosition::new(0, 0, 0);
+        inventory.insert_item(pos, InventoryItem::new("bread", 1), &database).unwrap();
+
+        assert_eq!(inventory.move_item_to_page(pos, 1, &database), Err(InventoryError::FilterRejected));
+        assert!(inventory.get_item(pos).is_some());
+    }
+
+    #[test]
+    fn move_into_rejecting_slot_changes_nothing() {
+        let database = database();
+        let mut inventory = equipment();
+        let (head, bag) = (SlotPosition::new(0, 0, 0), SlotPosition::new(0, 0, 1));
+        inventory.insert_item(bag, InventoryItem::new("bread", 1), &database).unwrap();
+
+        assert_eq!(inventory.move_item(bag, head, &database), Err(InventoryError::FilterRejected));
+        assert!(inventory.get_item(head).is_none());
+
+        // Swapping would put the bread into the helmet slot as well.
+        inventory.replace_slot(head, Some(InventoryItem::new("helmet", 1))).unwrap();
+        assert_eq!(inventory.move_item(head, bag, &database), Err(InventoryError::FilterRejected));
+        assert_eq!(inventory.get_item(head).unwrap().get_item_id(), "helmet");
+    }
+
+    #[test]
+    fn resize_ejects_items_no_slot_accepts() {
+        let database = database();
+        let page = InventoryPage::new("Equipment", 2, 2).with_slot_filter(0, 0, "armor.helmet".parse().unwrap());
+        let mut inventory = Inventory::from_pages(vec![page]);
+        inventory.insert_item(SlotPosition::new(0, 1, 0), InventoryItem::new("bread", 1), &database).unwrap();
+        inventory.insert_item(SlotPosition::new(0, 1, 1), InventoryItem::new("helmet", 1), &database).unwrap();
+
+        let report = inventory.resize_inventory(1, 1, &database);
+        assert_eq!(report.get_relocated(), &[(SlotPosition::new(0, 1, 1), SlotPosition::new(0, 0, 0))]);
+        assert_eq!(report.get_ejected().len(), 1);
+        assert_eq!(report.get_ejected()[0].1.get_item_id(), "bread");
+    }
+
+    #[test]
+    fn nested_move_into_rejecting_slot_changes_nothing() {
+        let database = database();
+        let mut inventory = Inventory::new(1, 2);
+        let (pouch, bread) = (SlotPosition::new(0, 0, 0), SlotPosition::new(0, 0, 1));
+        let contents = equipment();
+        inventory.insert_item(pouch, InventoryItem::new("pouch", 1).with_contents(contents), &database).unwrap();
+        inventory.insert_item(bread, InventoryItem::new("bread", 1), &database).unwrap();
+
+        let result = inventory.move_nested_item(&[], bread, &[pouch], SlotPosition::new(0, 0, 0), &database);
+        assert_eq!(result, Err(InventoryError::FilterRejected));
+        assert!(inventory.get_item(bread).is_some());
+        assert!(inventory.move_nested_item(&[], bread, &[pouch], SlotPosition::new(0, 0, 1), &database).is_ok());
+    }
+
+    #[test]
+    fn over_weight_items_are_rejected() {
+        let mut database = database();
+        database.add_item(ItemDefinition::new("anvil", "Anvil", "Items/anvil.png").with_weight(40.0));
+        let anvil = || InventoryItem::new("anvil", 1);
+        let mut backpack = Inventory::new(1, 3).with_max_weight(50.0);
+        backpack.add_item(anvil(), &database).unwrap();
+
+        assert_eq!(backpack.add_item(anvil(), &database), Err(InventoryError::OverWeight));
+        let mut chest = Inventory::new(1, 1);
+        chest.add_item(anvil(), &database).unwrap();
+        let pos = SlotPosition::new(0, 0, 0);
+        let result = chest.transfer_item(pos, &mut backpack, SlotPosition::new(0, 0, 1), &database);
+        assert_eq!(result, Err(InventoryError::OverWeight));
+        assert_eq!(chest.count_item("anvil"), 1);
+        assert_eq!(backpack.count_item("anvil"), 1);
+    }
+
+    #[test]
+    fn nested_move_over_container_weight_changes_nothing() {
+        let mut database = database();
+        database.add_item(ItemDefinition::new("anvil", "Anvil", "Items/anvil.png").with_weight(40.0));
+        let mut inventory = Inventory::new(1, 2);
+        let (pouch, anvil) = (SlotPosition::new(0, 0, 0), SlotPosition::new(0, 0, 1));
+        let contents = Inventory::new(1, 1).with_max_weight(10.0);
+        inventory.insert_item(pouch, InventoryItem::new("pouch", 1).with_contents(contents), &database).unwrap();
+        inventory.insert_item(anvil, InventoryItem::new("anvil", 1), &database).unwrap();
+
+        let result = inventory.move_nested_item(&[], anvil, &[pouch], SlotPosition::new(0, 0, 0), &database);
+        assert_eq!(result, Err(InventoryError::OverWeight));
+        assert!(inventory.get_item(anvil).is_some());
+    }
+
+    #[test]
+    fn precise_errors() {
+        let database = database();
+        let mut inventory = Inventory::new(1, 3);
+        let (a, b, c) = (SlotPosition::new(0, 0, 0), SlotPosition::new(0, 0, 1), SlotPosition::new(0, 0, 2));
+        inventory.insert_item(a, InventoryItem::new("sword", 1).with_durability(10), &database).unwrap();
+        inventory.insert_item(b, InventoryItem::new("bread", 1), &database).unwrap();
+
+        assert_eq!(inventory.move_item(a, a, &database), Err(InventoryError::SameSlot));
+        assert_eq!(inventory.merge_stacks(a, b, 10), Err(InventoryError::NotStackable));
+        let materials = vec![("iron_ingot".to_string(), 1)];
+        assert_eq!(inventory.repair_item(a, &materials), Err(InventoryError::NotDamaged));
+        inventory.consume_durability(a, 5).unwrap();
+        assert_eq!(inventory.repair_item(a, &materials), Err(InventoryError::MissingMaterials));
+        assert_eq!(inventory.get_item(a).unwrap().get_durability().unwrap().get_current(), 5);
+
+        let pouch = InventoryItem::new("pouch", 1).with_contents(Inventory::new(1, 1));
+        inventory.insert_item(c, pouch, &database).unwrap();
+        let result = inventory.move_nested_item(&[], c, &[c], SlotPosition::new(0, 0, 0), &database);
+        assert_eq!(result, Err(InventoryError::InsideItself));
+    }
+}
@@ -1,12 +1,74 @@
 use bevy::prelude::*;
-use rand::Rng;
+
+use crate::inventory::SlotPosition;
+
+/// Decides, which of the background tiles a slot may show, depending on its position in the grid.
+///
+/// The groups of `Checkerboard` and `Framed` are indices into the tiles of the `InventoryBackgroundTiles`. A slot picks
+/// one of the tiles of its group by the hash of its position. An empty group stands for all tiles.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum BackgroundTilePattern {
+    /// Every slot picks from all tiles.
+    #[default]
+    Random,
+    /// Slots alternate between two groups of tiles. The slot in the top left corner uses the first group.
+    Checkerboard(Vec<usize>, Vec<usize>),
+    /// The slots in the corners, along the edges and inside the grid pick from their own group, e.g. to draw a frame.
+    Framed {
+        corner: Vec<usize>,
+        edge: Vec<usize>,
+        inner: Vec<usize>,
+    },
+}
+
+/// Where the images of the tiles come from.
+#[derive(Clone, Debug)]
+enum TileSource {
+    Images(Vec<String>), // Paths to the images
+    Atlas {
+        image: String, // Path to the sprite sheet
+        layout: Handle<TextureAtlasLayout>,
+        indices: Vec<usize>,
+    },
+}
 
 /// Represents a collection of tiles, that can be used as background images
-/// in the inventory. The inventory system then assigns random
-/// tiles to the inventory slots.
-#[derive(Resource)]
+/// in the inventory. The inventory system assigns the tiles to the inventory
+/// slots by a hash of their position, so a grid always looks the same.
+///
+/// The tiles are either a list of images or sections of a sprite sheet. Tiles of a sprite sheet are stretched over the
+/// slot, while images are nine-sliced with the slicer of the `InventoryStyle`.
+///
+/// The resource is used by all inventories. Inserting it as a component on the entity of an `Inventory` or `Vendor`
+/// gives that inventory its own tiles, which are also used by the windows of its containers.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let tiles = InventoryBackgroundTiles::new(vec!["a.png".to_string(), "b.png".to_string()]).with_seed(7);
+/// let pos = SlotPosition::new(0, 2, 3);
+/// assert_eq!(tiles.get_tile_index(pos, 6, 8), tiles.get_tile_index(pos, 6, 8));
+///
+/// // Light and dark tiles like a checkerboard, a frame of tile 2 around tiles 0 and 1.
+/// let tiles = InventoryBackgroundTiles::new(vec!["light.png".into(), "dark.png".into(), "frame.png".into()]);
+/// let checkerboard = tiles.clone().with_pattern(BackgroundTilePattern::Checkerboard(vec![0], vec![1]));
+/// assert_eq!(checkerboard.get_tile_index(SlotPosition::new(0, 1, 1), 6, 8), Some(0));
+/// assert_eq!(checkerboard.get_tile_index(SlotPosition::new(0, 1, 2), 6, 8), Some(1));
+///
+/// let framed = tiles.with_pattern(BackgroundTilePattern::Framed {
+///     corner: vec![2],
+///     edge: vec![2],
+///     inner: vec![0, 1],
+/// });
+/// assert_eq!(framed.get_tile_index(SlotPosition::new(0, 5, 3), 6, 8), Some(2));
+/// assert_ne!(framed.get_tile_index(SlotPosition::new(0, 4, 3), 6, 8), Some(2));
+/// ```
+#[derive(Resource, Component, Clone, Debug)]
 pub struct InventoryBackgroundTiles {
-    background_icons_small: Vec<String>,
+    source: TileSource,
+    seed: u64,
+    pattern: BackgroundTilePattern,
 }
 
 impl Default for InventoryBackgroundTiles {
@@ -15,31 +77,137 @@ impl Default for InventoryBackgroundTiles {
     /// # Example
     ///
     /// ```
-    /// use bevy_inventory_system::prelude::InventoryBackgroundTiles;
+    /// use bevy_inventory_system::prelude::*;
     /// let test = InventoryBackgroundTiles::default();
-    /// let random_tile = test.get_random_tile_small();
-    /// println!("Random tile: {}", random_tile);
+    /// let (tile, atlas) = test.get_tile(SlotPosition::new(0, 0, 0), 6, 8).unwrap();
+    /// assert!(tile.starts_with("Apocalypse/HUD/Sprites/HUD/"));
+    /// assert!(atlas.is_none());
     /// ```
     fn default() -> Self {
-        Self {
-            background_icons_small: vec![
-                "Apocalypse/HUD/Sprites/HUD/SPR_Background_Square_Tile_Grunge_Sml_01.png"
-                    .to_string(),
-                "Apocalypse/HUD/Sprites/HUD/SPR_Background_Square_Tile_Grunge_Sml_02.png"
-                    .to_string(),
-                "Apocalypse/HUD/Sprites/HUD/SPR_Background_Square_Tile_Grunge_Sml_LeftRight.png"
-                    .to_string(),
-            ],
-        }
+        Self::new(vec![
+            "Apocalypse/HUD/Sprites/HUD/SPR_Background_Square_Tile_Grunge_Sml_01.png"
+                .to_string(),
+            "Apocalypse/HUD/Sprites/HUD/SPR_Background_Square_Tile_Grunge_Sml_02.png"
+                .to_string(),
+            "Apocalypse/HUD/Sprites/HUD/SPR_Background_Square_Tile_Grunge_Sml_LeftRight.png"
+                .to_string(),
+        ])
     }
 }
 
+/// Mixes the bits of `value`, see the SplitMix64 generator.
+fn mix(value: u64) -> u64 {
+    let mut z = value.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
 impl InventoryBackgroundTiles {
-    /// This method returns a random small tile.
-    pub fn get_random_tile_small(&self) -> &String {
-        let mut rng = rand::thread_rng();
-        let i = rng.gen_range(0..self.background_icons_small.len());
+    /// Creates a new set of background tiles from a list of asset paths.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_inventory_system::prelude::*;
+    /// let tiles = InventoryBackgroundTiles::new(vec!["tile.png".to_string()]);
+    /// assert_eq!(tiles.get_tile(SlotPosition::new(0, 1, 2), 6, 8).unwrap().0, "tile.png");
+    /// ```
+    pub fn new(background_icons_small: Vec<String>) -> Self {
+        Self {
+            source: TileSource::Images(background_icons_small),
+            seed: 0,
+            pattern: BackgroundTilePattern::default(),
+        }
+    }
+
+    /// Creates a set of background tiles from the sections `indices` of a sprite sheet with the given layout.
+    pub fn from_atlas(image: impl Into<String>, layout: Handle<TextureAtlasLayout>, indices: Vec<usize>) -> Self {
+        Self {
+            source: TileSource::Atlas {
+                image: image.into(),
+                layout,
+                indices,
+            },
+            seed: 0,
+            pattern: BackgroundTilePattern::default(),
+        }
+    }
+
+    /// Sets the seed of the hash, that picks the tiles. Different seeds give different, but stable looks.
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    pub fn with_pattern(mut self, pattern: BackgroundTilePattern) -> Self {
+        self.pattern = pattern;
+        self
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_pattern(&self) -> &BackgroundTilePattern {
+        &self.pattern
+    }
+
+    pub fn get_tile_count(&self) -> usize {
+        match &self.source {
+            TileSource::Images(paths) => paths.len(),
+            TileSource::Atlas { indices, .. } => indices.len(),
+        }
+    }
+
+    /// Returns the index of the tile of the slot at `pos` in a grid of `rows` and `columns`, or `None`, if there are
+    /// no tiles. Tile indices of the pattern, that are out of range, are skipped.
+    pub fn get_tile_index(&self, pos: SlotPosition, rows: usize, columns: usize) -> Option<usize> {
+        let count = self.get_tile_count();
+        let group: &[usize] = match &self.pattern {
+            BackgroundTilePattern::Random => &[],
+            BackgroundTilePattern::Checkerboard(even, odd) => {
+                if (pos.x + pos.y).is_multiple_of(2) {
+                    even
+                } else {
+                    odd
+                }
+            }
+            BackgroundTilePattern::Framed { corner, edge, inner } => {
+                let vertical = pos.x == 0 || pos.x + 1 >= rows;
+                let horizontal = pos.y == 0 || pos.y + 1 >= columns;
+                match (vertical, horizontal) {
+                    (true, true) => corner,
+                    (true, false) | (false, true) => edge,
+                    (false, false) => inner,
+                }
+            }
+        };
+
+        let group: Vec<usize> = group.iter().copied().filter(|&i| i < count).collect();
+        let choices = if group.is_empty() { count } else { group.len() };
+        if choices == 0 {
+            return None;
+        }
+
+        let hash = [pos.page, pos.x, pos.y].into_iter().fold(mix(self.seed), |h, v| mix(h ^ v as u64));
+        let choice = (hash % choices as u64) as usize;
+        Some(if group.is_empty() { choice } else { group[choice] })
+    }
 
-        &self.background_icons_small[i]
+    /// Returns the image of the tile of the slot at `pos` in a grid of `rows` and `columns` and, if the tiles are
+    /// part of a sprite sheet, the section of the sheet. Returns `None`, if there are no tiles.
+    pub fn get_tile(&self, pos: SlotPosition, rows: usize, columns: usize) -> Option<(&str, Option<TextureAtlas>)> {
+        let index = self.get_tile_index(pos, rows, columns)?;
+        Some(match &self.source {
+            TileSource::Images(paths) => (paths[index].as_str(), None),
+            TileSource::Atlas { image, layout, indices } => {
+                let section = TextureAtlas {
+                    layout: layout.clone(),
+                    index: indices[index],
+                };
+                (image.as_str(), Some(section))
+            }
+        })
     }
 }
@@ -1,12 +1,18 @@
 use bevy::prelude::*;
 
+use crate::inventory::SlotPosition;
+use crate::inventory_item::ItemInstanceId;
+use crate::trade::TradeSide;
+
 /// Represents an IconComponent struct.
 ///
 /// This struct is used to store information about an on-screen icon and its corresponding in-memory item.
-/// It contains the `item_id` field, which is an `IVec2` representing the position of the item in a 2D array.
+/// It contains the `item_id` field, which is an `IVec2` representing the position of the item in a 2D array, and the
+/// `page` of the inventory the array belongs to.
 #[derive(Component, Debug)]
 pub struct IconComponent {
     item_id: IVec2, // Items are managed by a 2d array. This maps an on screen icon to an in memory Item.
+    page: usize,
 }
 
 impl IconComponent {
@@ -32,9 +38,28 @@ impl IconComponent {
     pub fn new(x: i32, y: i32) -> Self {
         IconComponent {
             item_id: IVec2 { x, y },
+            page: 0,
         }
     }
 
+    /// Sets the inventory page the icon belongs to.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_inventory_system::prelude::IconComponent;
+    /// let icon = IconComponent::new(1, 2).with_page(3);
+    /// assert_eq!(icon.get_page(), 3);
+    /// ```
+    pub fn with_page(mut self, page: usize) -> Self {
+        self.page = page;
+        self
+    }
+
+    pub fn get_page(&self) -> usize {
+        self.page
+    }
+
     /// Returns the grid position of the `IconComponent`.
     ///
     /// This method retrieves the `item_id` field of the `IconComponent`, which represents the position of the item in a
@@ -55,22 +80,52 @@ impl IconComponent {
     pub fn get_grid_position(&self) -> IVec2 {
         self.item_id
     }
+
+    /// Maps the icon to another item. This is used, when icons are recycled by a virtualized grid.
+    pub fn set_grid_position(&mut self, value: IVec2) {
+        self.item_id = value;
+    }
+}
+
+/// Marks a scroll panel, that only spawns icons for its visible rows.
+#[derive(Component, Debug)]
+pub struct VirtualGridComponent;
+
+/// Marks a row of icons in a virtualized grid.
+///
+/// A virtualized grid spawns a pool of rows. Row `pool_row` of the pool shows every logical row that is equal to
+/// `pool_row` modulo the size of the pool, so that only the rows that scroll out of view need to be remapped.
+#[derive(Component, Debug)]
+pub struct VirtualRowComponent {
+    pool_row: usize,
+}
+
+impl VirtualRowComponent {
+    pub fn new(pool_row: usize) -> Self {
+        Self { pool_row }
+    }
+
+    pub fn get_pool_row(&self) -> usize {
+        self.pool_row
+    }
 }
 
 /// Represents a ScrollPanel struct.
 ///
-/// This struct is used to define the position of a scroll panel in a graphical user interface.
-/// It contains the `x` and `y` coordinates of the scroll panel.
+/// This struct is used to define the scroll position of a scroll panel in a graphical user interface.
+/// It contains the horizontal offset `x` and the vertical offset `y` of the content in pixels, as well as the
+/// `target` offset the panel is smoothly scrolling towards. The parent of a scroll panel is its viewport.
 #[derive(Component, Debug)]
 pub struct ScrollPanel {
     pub x: f32,
     pub y: f32,
+    pub target: Vec2,
 }
 
 /// Implements the `Default` trait for the `ScrollPanel` struct.
 ///
-/// This implementation sets the default values for the `x` and `y` coordinates of a scroll panel.
-/// The default values are both set to 0.0.
+/// This implementation sets the default values for the `x` and `y` offsets and the `target` of a scroll panel.
+/// The default values are all set to 0.0.
 ///
 /// # Example
 ///
@@ -79,12 +134,289 @@ pub struct ScrollPanel {
 /// let scroll_panel = ScrollPanel::default();
 /// assert_eq!(scroll_panel.x, 0.0);
 /// assert_eq!(scroll_panel.y, 0.0);
+/// assert_eq!(scroll_panel.target.x, 0.0);
 /// ```
 ///
 /// This will create a new `ScrollPanel` instance with the default values for the `x` and `y` coordinates.
-
 impl Default for ScrollPanel {
     fn default() -> Self {
-        Self { x: 0.0, y: 0.0 }
+        Self {
+            x: 0.0,
+            y: 0.0,
+            target: Vec2::ZERO,
+        }
+    }
+}
+
+/// The direction a scrollbar scrolls its panel in.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ScrollAxis {
+    Horizontal,
+    Vertical,
+}
+
+/// Marks the track of a scrollbar.
+///
+/// The track belongs to the scroll panel stored in `panel` and contains a single `ScrollbarThumbComponent`.
+#[derive(Component, Debug)]
+pub struct ScrollbarComponent {
+    panel: Entity,
+    axis: ScrollAxis,
+}
+
+impl ScrollbarComponent {
+    pub fn new(panel: Entity, axis: ScrollAxis) -> Self {
+        Self { panel, axis }
+    }
+
+    pub fn get_panel(&self) -> Entity {
+        self.panel
+    }
+
+    pub fn get_axis(&self) -> ScrollAxis {
+        self.axis
+    }
+}
+
+/// Marks the draggable thumb of a scrollbar.
+///
+/// While the thumb is dragged, `drag_start` holds the cursor position and the scroll offset at the time the drag
+/// started.
+#[derive(Component, Debug)]
+pub struct ScrollbarThumbComponent {
+    panel: Entity,
+    axis: ScrollAxis,
+    drag_start: Option<(f32, f32)>,
+}
+
+impl ScrollbarThumbComponent {
+    pub fn new(panel: Entity, axis: ScrollAxis) -> Self {
+        Self {
+            panel,
+            axis,
+            drag_start: None,
+        }
+    }
+
+    pub fn get_panel(&self) -> Entity {
+        self.panel
+    }
+
+    pub fn get_axis(&self) -> ScrollAxis {
+        self.axis
+    }
+
+    pub fn get_drag_start(&self) -> Option<(f32, f32)> {
+        self.drag_start
+    }
+
+    pub fn set_drag_start(&mut self, value: Option<(f32, f32)>) {
+        self.drag_start = value;
+    }
+}
+
+/// Represents the tab button of an inventory page.
+#[derive(Component, Debug)]
+pub struct InventoryTabComponent {
+    page: usize,
+}
+
+impl InventoryTabComponent {
+    pub fn new(page: usize) -> Self {
+        Self { page }
+    }
+
+    pub fn get_page(&self) -> usize {
+        self.page
+    }
+}
+
+/// An action offered by an entry of the context menu of an inventory slot.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContextMenuAction {
+    /// Opens the inventory of the container item with the given instance id in its own window.
+    OpenContainer(ItemInstanceId),
+    /// Repairs the item at the given position of the shown inventory.
+    Repair(SlotPosition),
+    /// Sells the item at the given position of the shown inventory to the shown vendor.
+    Sell(SlotPosition),
+}
+
+/// Represents an entry of the context menu of an inventory slot.
+#[derive(Component, Debug)]
+pub struct ContextMenuEntryComponent {
+    action: ContextMenuAction,
+}
+
+impl ContextMenuEntryComponent {
+    pub fn new(action: ContextMenuAction) -> Self {
+        Self { action }
+    }
+
+    pub fn get_action(&self) -> ContextMenuAction {
+        self.action
+    }
+}
+
+/// Represents a window showing the inventory of a container item, like a pouch.
+///
+/// The container is identified by its instance id, so the window stays open, when the container is moved inside the
+/// shown inventory. The window is closed, once the container is no longer part of it.
+#[derive(Component, Debug)]
+pub struct ContainerWindowComponent {
+    container: ItemInstanceId,
+}
+
+impl ContainerWindowComponent {
+    pub fn new(container: ItemInstanceId) -> Self {
+        Self { container }
+    }
+
+    pub fn get_container(&self) -> ItemInstanceId {
+        self.container
+    }
+}
+
+/// Represents a slot of a container window.
+#[derive(Component, Debug)]
+pub struct ContainerSlotComponent {
+    window: Entity,
+    position: SlotPosition,
+}
+
+impl ContainerSlotComponent {
+    pub fn new(window: Entity, position: SlotPosition) -> Self {
+        Self { window, position }
+    }
+
+    pub fn get_window(&self) -> Entity {
+        self.window
+    }
+
+    pub fn get_position(&self) -> SlotPosition {
+        self.position
+    }
+}
+
+/// Represents the button closing a container window.
+#[derive(Component, Debug)]
+pub struct ContainerCloseComponent {
+    window: Entity,
+}
+
+impl ContainerCloseComponent {
+    pub fn new(window: Entity) -> Self {
+        Self { window }
+    }
+
+    pub fn get_window(&self) -> Entity {
+        self.window
+    }
+}
+
+/// Represents the window showing the stock and buyback list of a vendor.
+#[derive(Component, Debug)]
+pub struct VendorWindowComponent {
+    vendor: Entity,
+}
+
+impl VendorWindowComponent {
+    pub fn new(vendor: Entity) -> Self {
+        Self { vendor }
+    }
+
+    pub fn get_vendor(&self) -> Entity {
+        self.vendor
+    }
+}
+
+/// Represents a slot of the stock of a vendor. Pressing it buys the item.
+#[derive(Component, Debug)]
+pub struct VendorSlotComponent {
+    position: SlotPosition,
+}
+
+impl VendorSlotComponent {
+    pub fn new(position: SlotPosition) -> Self {
+        Self { position }
+    }
+
+    pub fn get_position(&self) -> SlotPosition {
+        self.position
+    }
+}
+
+/// Represents an entry of the buyback list of a vendor. Pressing it buys the item back.
+#[derive(Component, Debug)]
+pub struct BuybackSlotComponent {
+    index: usize,
+}
+
+impl BuybackSlotComponent {
+    pub fn new(index: usize) -> Self {
+        Self { index }
+    }
+
+    pub fn get_index(&self) -> usize {
+        self.index
+    }
+}
+
+/// Represents the window showing the offers of a trade session.
+#[derive(Component, Debug)]
+pub struct TradeWindowComponent {
+    session: Entity,
+}
+
+impl TradeWindowComponent {
+    pub fn new(session: Entity) -> Self {
+        Self { session }
+    }
+
+    pub fn get_session(&self) -> Entity {
+        self.session
+    }
+}
+
+/// Represents a slot of the offer of a side of a trade session. Pressing a slot of the own offer withdraws the item.
+#[derive(Component, Debug)]
+pub struct TradeSlotComponent {
+    side: TradeSide,
+    position: SlotPosition,
+}
+
+impl TradeSlotComponent {
+    pub fn new(side: TradeSide, position: SlotPosition) -> Self {
+        Self { side, position }
+    }
+
+    pub fn get_side(&self) -> TradeSide {
+        self.side
+    }
+
+    pub fn get_position(&self) -> SlotPosition {
+        self.position
+    }
+}
+
+/// Represents the error toast shown above the inventory ui. It is removed, once its timer finished.
+#[derive(Component, Debug)]
+pub struct ErrorToastComponent {
+    timer: Timer,
+}
+
+impl ErrorToastComponent {
+    pub fn new(seconds: f32) -> Self {
+        Self {
+            timer: Timer::from_seconds(seconds, TimerMode::Once),
+        }
+    }
+
+    pub fn get_timer(&self) -> &Timer {
+        &self.timer
+    }
+
+    pub fn get_timer_mut(&mut self) -> &mut Timer {
+        &mut self.timer
     }
 }
@@ -0,0 +1,95 @@
+use std::borrow::Cow;
+use std::fmt;
+
+/// The reason an operation on an inventory failed.
+///
+/// Every mutating operation of `Inventory`, `InventoryTransaction`, `TradeSession` and `Vendor` returns this error and
+/// changes nothing, if it fails. Failures of actions of the player in the inventory ui are shown in an error toast,
+/// using the `Display` text of the error.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let database = ItemDatabase::default();
+/// let mut inventory = Inventory::new(1, 1);
+/// let pos = SlotPosition::new(0, 0, 0);
+///
+/// assert_eq!(inventory.insert_item(pos, InventoryItem::new("axe", 1), &database), Ok(()));
+/// assert_eq!(inventory.insert_item(pos, InventoryItem::new("saw", 1), &database), Err(InventoryError::Occupied));
+/// assert_eq!(inventory.take_item(SlotPosition::new(0, 5, 5)), Err(InventoryError::OutOfBounds));
+/// assert_eq!(inventory.move_item(pos, pos, &database), Err(InventoryError::SameSlot));
+/// assert_eq!(InventoryError::Occupied.to_string(), "The slot is occupied");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum InventoryError {
+    /// The slot or page does not exist, or the slot is disabled.
+    OutOfBounds,
+    /// The entity has no inventory.
+    MissingInventory,
+    /// The target slot already holds an item.
+    Occupied,
+    /// The target stack can not take any more items.
+    StackFull,
+    /// There is no free slot left.
+    InventoryFull,
+    /// The item is not accepted, e.g. by the filter of the slot.
+    FilterRejected,
+    /// The items would exceed the maximum weight of the inventory or container.
+    OverWeight,
+    /// An item covering several cells would overlap another item. Reserved for multi-cell items: every item covers a
+    /// single cell for now, so no operation returns it yet.
+    ShapeCollision,
+    /// There is no item at the position, or there are not enough items.
+    ItemNotFound,
+    /// The source and the target of a move are the same slot.
+    SameSlot,
+    /// The items can not be stacked, e.g. because their ids differ.
+    NotStackable,
+    /// A container would be put inside itself.
+    InsideItself,
+    /// The item to repair has full durability.
+    NotDamaged,
+    /// There are not enough materials to repair the item.
+    MissingMaterials,
+    /// The wallet can not pay the price or hold the payment.
+    CannotAfford,
+    /// Not every party of a trade confirmed the offers.
+    NotConfirmed,
+    /// The item has no price, so it can not be bought from or sold to a vendor.
+    NoPrice,
+    /// The stack can not be split, e.g. because it is a container.
+    CannotSplit,
+    /// A rule of the `InventoryRules` vetoed the operation, with the reason given by the rule.
+    Vetoed(Cow<'static, str>),
+}
+
+impl fmt::Display for InventoryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let text = match self {
+            InventoryError::OutOfBounds => "The slot does not exist",
+            InventoryError::MissingInventory => "The inventory does not exist",
+            InventoryError::Occupied => "The slot is occupied",
+            InventoryError::StackFull => "The stack is full",
+            InventoryError::InventoryFull => "The inventory is full",
+            InventoryError::FilterRejected => "The item is not allowed here",
+            InventoryError::OverWeight => "The items are too heavy",
+            InventoryError::ShapeCollision => "The item does not fit there",
+            InventoryError::ItemNotFound => "The item was not found",
+            InventoryError::SameSlot => "The item is already there",
+            InventoryError::NotStackable => "The items can not be stacked",
+            InventoryError::InsideItself => "The container can not be put inside itself",
+            InventoryError::NotDamaged => "The item is not damaged",
+            InventoryError::MissingMaterials => "Not enough materials",
+            InventoryError::CannotAfford => "Not enough money",
+            InventoryError::NotConfirmed => "The trade is not confirmed",
+            InventoryError::NoPrice => "The item can not be traded",
+            InventoryError::CannotSplit => "The stack can not be split",
+            InventoryError::Vetoed(reason) => reason.as_ref(),
+        };
+
+        f.write_str(text)
+    }
+}
+
+impl std::error::Error for InventoryError {}
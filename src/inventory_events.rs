@@ -1,10 +1,17 @@
 use bevy::prelude::*;
 
+use crate::inventory::{ItemLocation, SlotPosition};
+use crate::inventory_error::InventoryError;
+use crate::inventory_item::{InventoryItem, ItemInstanceId};
+use crate::inventory_transaction::{InventoryTransaction, SlotChange};
+use crate::item_database::BrokenItemOutcome;
+
 /// Represents an event when an inventory icon is clicked.
 #[derive(Event, Default)]
 pub struct InventoryIconClicked {
     x: usize,
     y: usize,
+    page: usize,
 }
 
 impl InventoryIconClicked {
@@ -17,12 +24,19 @@ impl InventoryIconClicked {
     /// # Example
     /// ```
     /// use bevy_inventory_system::prelude::InventoryIconClicked;
-    /// let icon_clicked = InventoryIconClicked::new(5, 10);
+    /// let icon_clicked = InventoryIconClicked::new(5, 10).with_page(1);
     /// assert_eq!(icon_clicked.x(), 5);
     /// assert_eq!(icon_clicked.y(), 10);
+    /// assert_eq!(icon_clicked.page(), 1);
     /// ```
     pub fn new(x: usize, y: usize) -> Self {
-        InventoryIconClicked { x, y }
+        InventoryIconClicked { x, y, page: 0 }
+    }
+
+    /// Sets the inventory page of the icon.
+    pub fn with_page(mut self, page: usize) -> Self {
+        self.page = page;
+        self
     }
 
     pub fn x(&self) -> usize {
@@ -31,19 +45,24 @@ impl InventoryIconClicked {
     pub fn y(&self) -> usize {
         self.y
     }
+    pub fn page(&self) -> usize {
+        self.page
+    }
 }
 
 /// An event, when the mouse enters an inventory icon.
 ///
-/// The event contains the x and y coordinates of the inventory icon.
+/// The event contains the x and y coordinates and the page of the inventory icon.
 ///
 /// # Fields
 /// - `x`: The x coordinate of the icon.
 /// - `y`: The y coordinate of the icon.
+/// - `page`: The inventory page of the icon.
 #[derive(Event, Default)]
 pub struct InventoryIconMouseEnter {
     x: usize,
     y: usize,
+    page: usize,
 }
 
 impl InventoryIconMouseEnter {
@@ -61,7 +80,13 @@ impl InventoryIconMouseEnter {
     /// assert_eq!(mouse_enter_event.y(), 7);
     /// ```
     pub fn new(x: usize, y: usize) -> Self {
-        InventoryIconMouseEnter { x, y }
+        InventoryIconMouseEnter { x, y, page: 0 }
+    }
+
+    /// Sets the inventory page of the icon.
+    pub fn with_page(mut self, page: usize) -> Self {
+        self.page = page;
+        self
     }
 
     pub fn x(&self) -> usize {
@@ -70,19 +95,24 @@ impl InventoryIconMouseEnter {
     pub fn y(&self) -> usize {
         self.y
     }
+    pub fn page(&self) -> usize {
+        self.page
+    }
 }
 
 /// Represents an event when the mouse exits an inventory icon.
 ///
-/// The event contains the x and y coordinates of the icon.
+/// The event contains the x and y coordinates and the page of the icon.
 ///
 /// # Fields
 /// - `x`: The x coordinate of the icon.
 /// - `y`: The y coordinate of the icon.
+/// - `page`: The inventory page of the icon.
 #[derive(Event, Default)]
 pub struct InventoryIconMouseExit {
     x: usize,
     y: usize,
+    page: usize,
 }
 
 impl InventoryIconMouseExit {
@@ -100,7 +130,13 @@ impl InventoryIconMouseExit {
     /// assert_eq!(mouse_exit_event.y(), 8);
     /// ```
     pub fn new(x: usize, y: usize) -> Self {
-        InventoryIconMouseExit { x, y }
+        InventoryIconMouseExit { x, y, page: 0 }
+    }
+
+    /// Sets the inventory page of the icon.
+    pub fn with_page(mut self, page: usize) -> Self {
+        self.page = page;
+        self
     }
 
     pub fn x(&self) -> usize {
@@ -109,6 +145,9 @@ impl InventoryIconMouseExit {
     pub fn y(&self) -> usize {
         self.y
     }
+    pub fn page(&self) -> usize {
+        self.page
+    }
 }
 
 /// Represents an event to reset inventory icons.
@@ -119,3 +158,815 @@ pub struct ResetInventoryIcons;
 pub struct ToggleInventory;
 
 
+
+/// An event that activates a theme registered in `InventoryThemes`.
+///
+/// Open inventory windows are restyled in place.
+///
+/// # Example
+/// ```
+/// use bevy_inventory_system::prelude::SetInventoryTheme;
+/// let event = SetInventoryTheme::new("dark");
+/// assert_eq!(event.name(), "dark");
+/// ```
+#[derive(Event)]
+pub struct SetInventoryTheme {
+    name: String,
+}
+
+impl SetInventoryTheme {
+    pub fn new(name: impl Into<String>) -> Self {
+        SetInventoryTheme { name: name.into() }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+/// An event that scrolls the inventory, so that the icon at the given position becomes visible.
+///
+/// If the position is on another page of the shown inventory, the inventory switches to that page first. The
+/// coordinates use the same convention as `InventoryIconClicked`.
+///
+/// # Example
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let event = ScrollToSlot::new(SlotPosition::new(1, 4, 2));
+/// assert_eq!(event.page(), 1);
+/// assert_eq!(event.x(), 4);
+/// assert_eq!(event.y(), 2);
+/// ```
+#[derive(Event, Default)]
+pub struct ScrollToSlot {
+    position: SlotPosition,
+}
+
+impl ScrollToSlot {
+    pub fn new(position: SlotPosition) -> Self {
+        ScrollToSlot { position }
+    }
+
+    pub fn position(&self) -> SlotPosition {
+        self.position
+    }
+    pub fn page(&self) -> usize {
+        self.position.page
+    }
+    pub fn x(&self) -> usize {
+        self.position.x
+    }
+    pub fn y(&self) -> usize {
+        self.position.y
+    }
+}
+
+/// An event that switches the inventory ui to another page of the shown inventory.
+///
+/// # Example
+/// ```
+/// use bevy_inventory_system::prelude::SetInventoryPage;
+/// let event = SetInventoryPage::new(2);
+/// assert_eq!(event.page(), 2);
+/// ```
+#[derive(Event, Default)]
+pub struct SetInventoryPage {
+    page: usize,
+}
+
+impl SetInventoryPage {
+    pub fn new(page: usize) -> Self {
+        SetInventoryPage { page }
+    }
+
+    pub fn page(&self) -> usize {
+        self.page
+    }
+}
+
+/// An event that rebuilds an open inventory ui, e.g. after the shown page or its grid size changed.
+#[derive(Event, Default)]
+pub struct RebuildInventory;
+
+/// An event that reduces the durability of an item in an inventory.
+///
+/// If the item breaks, the outcome configured in its `ItemDefinition` is applied and an `ItemBroken` event is sent.
+///
+/// # Example
+/// ```
+/// use bevy::prelude::Entity;
+/// use bevy_inventory_system::prelude::*;
+/// let event = ConsumeDurability::new(Entity::PLACEHOLDER, SlotPosition::new(0, 1, 2), 5);
+/// assert_eq!(event.position(), SlotPosition::new(0, 1, 2));
+/// assert_eq!(event.amount(), 5);
+/// ```
+#[derive(Event)]
+pub struct ConsumeDurability {
+    inventory: Entity,
+    position: SlotPosition,
+    amount: u32,
+}
+
+impl ConsumeDurability {
+    pub fn new(inventory: Entity, position: SlotPosition, amount: u32) -> Self {
+        ConsumeDurability {
+            inventory,
+            position,
+            amount,
+        }
+    }
+
+    pub fn inventory(&self) -> Entity {
+        self.inventory
+    }
+    pub fn position(&self) -> SlotPosition {
+        self.position
+    }
+    pub fn amount(&self) -> u32 {
+        self.amount
+    }
+}
+
+/// An event that repairs an item in an inventory, consuming the repair materials of its `ItemDefinition` from the
+/// same inventory.
+#[derive(Event)]
+pub struct RepairItem {
+    inventory: Entity,
+    position: SlotPosition,
+}
+
+impl RepairItem {
+    pub fn new(inventory: Entity, position: SlotPosition) -> Self {
+        RepairItem { inventory, position }
+    }
+
+    pub fn inventory(&self) -> Entity {
+        self.inventory
+    }
+    pub fn position(&self) -> SlotPosition {
+        self.position
+    }
+}
+
+/// An event sent, when the durability of an item has been used up.
+///
+/// `item` is the item as it was, when it broke. `outcome` has already been applied to the inventory.
+#[derive(Event)]
+pub struct ItemBroken {
+    inventory: Entity,
+    position: SlotPosition,
+    item: InventoryItem,
+    outcome: BrokenItemOutcome,
+}
+
+impl ItemBroken {
+    pub fn new(inventory: Entity, position: SlotPosition, item: InventoryItem, outcome: BrokenItemOutcome) -> Self {
+        ItemBroken {
+            inventory,
+            position,
+            item,
+            outcome,
+        }
+    }
+
+    pub fn inventory(&self) -> Entity {
+        self.inventory
+    }
+    pub fn position(&self) -> SlotPosition {
+        self.position
+    }
+    pub fn item(&self) -> &InventoryItem {
+        &self.item
+    }
+    pub fn outcome(&self) -> &BrokenItemOutcome {
+        &self.outcome
+    }
+}
+
+/// An event sent, when the balance of a currency in a `Wallet` changed.
+#[derive(Event, Debug)]
+pub struct CurrencyChanged {
+    wallet: Entity,
+    currency: String,
+    old_balance: u64,
+    new_balance: u64,
+}
+
+impl CurrencyChanged {
+    pub fn new(wallet: Entity, currency: impl Into<String>, old_balance: u64, new_balance: u64) -> Self {
+        CurrencyChanged {
+            wallet,
+            currency: currency.into(),
+            old_balance,
+            new_balance,
+        }
+    }
+
+    /// Returns the entity holding the `Wallet`.
+    pub fn wallet(&self) -> Entity {
+        self.wallet
+    }
+    pub fn currency(&self) -> &str {
+        &self.currency
+    }
+    pub fn get_old_balance(&self) -> u64 {
+        self.old_balance
+    }
+    pub fn get_new_balance(&self) -> u64 {
+        self.new_balance
+    }
+}
+
+/// An event that buys `count` items of the stack at `position` of a vendor's inventory. The items are put into the
+/// inventory of `buyer` and paid from its `Wallet`.
+///
+/// # Example
+/// ```
+/// use bevy::prelude::Entity;
+/// use bevy_inventory_system::prelude::*;
+/// let event = BuyItem::new(Entity::PLACEHOLDER, Entity::PLACEHOLDER, SlotPosition::new(0, 1, 1), 3);
+/// assert_eq!(event.count(), 3);
+/// ```
+#[derive(Event)]
+pub struct BuyItem {
+    vendor: Entity,
+    buyer: Entity,
+    position: SlotPosition,
+    count: u32,
+}
+
+impl BuyItem {
+    pub fn new(vendor: Entity, buyer: Entity, position: SlotPosition, count: u32) -> Self {
+        BuyItem {
+            vendor,
+            buyer,
+            position,
+            count,
+        }
+    }
+
+    pub fn vendor(&self) -> Entity {
+        self.vendor
+    }
+    pub fn buyer(&self) -> Entity {
+        self.buyer
+    }
+    pub fn position(&self) -> SlotPosition {
+        self.position
+    }
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+/// An event that sells the item at `position` of the inventory of `seller` to a vendor. The price is deposited into
+/// the `Wallet` of `seller`.
+#[derive(Event)]
+pub struct SellItem {
+    vendor: Entity,
+    seller: Entity,
+    position: SlotPosition,
+}
+
+impl SellItem {
+    pub fn new(vendor: Entity, seller: Entity, position: SlotPosition) -> Self {
+        SellItem {
+            vendor,
+            seller,
+            position,
+        }
+    }
+
+    pub fn vendor(&self) -> Entity {
+        self.vendor
+    }
+    pub fn seller(&self) -> Entity {
+        self.seller
+    }
+    pub fn position(&self) -> SlotPosition {
+        self.position
+    }
+}
+
+/// An event that buys back the entry at `index` of the buyback list of a vendor.
+#[derive(Event)]
+pub struct BuybackItem {
+    vendor: Entity,
+    buyer: Entity,
+    index: usize,
+}
+
+impl BuybackItem {
+    pub fn new(vendor: Entity, buyer: Entity, index: usize) -> Self {
+        BuybackItem { vendor, buyer, index }
+    }
+
+    pub fn vendor(&self) -> Entity {
+        self.vendor
+    }
+    pub fn buyer(&self) -> Entity {
+        self.buyer
+    }
+    pub fn index(&self) -> usize {
+        self.index
+    }
+}
+
+/// An event sent, when the stock of a vendor has been restocked from its loot table.
+#[derive(Event)]
+pub struct VendorRestocked {
+    vendor: Entity,
+}
+
+impl VendorRestocked {
+    pub fn new(vendor: Entity) -> Self {
+        VendorRestocked { vendor }
+    }
+
+    pub fn vendor(&self) -> Entity {
+        self.vendor
+    }
+}
+
+/// An event that moves the item at `position` of the inventory of `party` into its offer of a trade session.
+#[derive(Event)]
+pub struct OfferTradeItem {
+    session: Entity,
+    party: Entity,
+    position: SlotPosition,
+}
+
+impl OfferTradeItem {
+    pub fn new(session: Entity, party: Entity, position: SlotPosition) -> Self {
+        OfferTradeItem {
+            session,
+            party,
+            position,
+        }
+    }
+
+    pub fn session(&self) -> Entity {
+        self.session
+    }
+    pub fn party(&self) -> Entity {
+        self.party
+    }
+    pub fn position(&self) -> SlotPosition {
+        self.position
+    }
+}
+
+/// An event that moves the item at `position` of the offer of `party` back into its inventory.
+#[derive(Event)]
+pub struct WithdrawTradeItem {
+    session: Entity,
+    party: Entity,
+    position: SlotPosition,
+}
+
+impl WithdrawTradeItem {
+    pub fn new(session: Entity, party: Entity, position: SlotPosition) -> Self {
+        WithdrawTradeItem {
+            session,
+            party,
+            position,
+        }
+    }
+
+    pub fn session(&self) -> Entity {
+        self.session
+    }
+    pub fn party(&self) -> Entity {
+        self.party
+    }
+    pub fn position(&self) -> SlotPosition {
+        self.position
+    }
+}
+
+/// An event that confirms the current offers of a trade session for `party`. The exchange is executed, as soon as
+/// both parties confirmed.
+#[derive(Event)]
+pub struct ConfirmTrade {
+    session: Entity,
+    party: Entity,
+}
+
+impl ConfirmTrade {
+    pub fn new(session: Entity, party: Entity) -> Self {
+        ConfirmTrade { session, party }
+    }
+
+    pub fn session(&self) -> Entity {
+        self.session
+    }
+    pub fn party(&self) -> Entity {
+        self.party
+    }
+}
+
+/// An event that ends a trade session and returns the offered items to their owners.
+#[derive(Event)]
+pub struct CancelTrade {
+    session: Entity,
+}
+
+impl CancelTrade {
+    pub fn new(session: Entity) -> Self {
+        CancelTrade { session }
+    }
+
+    pub fn session(&self) -> Entity {
+        self.session
+    }
+}
+
+/// An event sent, when a trade session ended.
+///
+/// `completed` is true, if the offers were exchanged, and false, if the session was cancelled.
+#[derive(Event)]
+pub struct TradeFinished {
+    session: Entity,
+    completed: bool,
+}
+
+impl TradeFinished {
+    pub fn new(session: Entity, completed: bool) -> Self {
+        TradeFinished { session, completed }
+    }
+
+    pub fn session(&self) -> Entity {
+        self.session
+    }
+    pub fn completed(&self) -> bool {
+        self.completed
+    }
+}
+
+/// An event that applies an `InventoryTransaction` to the inventories of its entities.
+///
+/// If the transaction succeeds, a single `InventoryChanged` event is sent. Otherwise nothing changes and an
+/// `InventoryTransactionFailed` event is sent.
+#[derive(Event)]
+pub struct ApplyInventoryTransaction {
+    transaction: InventoryTransaction,
+}
+
+impl ApplyInventoryTransaction {
+    pub fn new(transaction: InventoryTransaction) -> Self {
+        ApplyInventoryTransaction { transaction }
+    }
+
+    pub fn transaction(&self) -> &InventoryTransaction {
+        &self.transaction
+    }
+}
+
+/// An event sent, when an `InventoryTransaction` failed or was vetoed by the `InventoryRules`. Nothing changed.
+#[derive(Event)]
+pub struct InventoryTransactionFailed {
+    transaction: InventoryTransaction,
+    error: InventoryError,
+}
+
+impl InventoryTransactionFailed {
+    pub fn new(transaction: InventoryTransaction, error: InventoryError) -> Self {
+        InventoryTransactionFailed { transaction, error }
+    }
+
+    pub fn transaction(&self) -> &InventoryTransaction {
+        &self.transaction
+    }
+
+    pub fn error(&self) -> &InventoryError {
+        &self.error
+    }
+}
+
+/// An event sent, when an `InventoryTransaction` has been applied. It lists every slot the transaction changed.
+#[derive(Event)]
+pub struct InventoryChanged {
+    changes: Vec<SlotChange>,
+}
+
+impl InventoryChanged {
+    pub fn new(changes: Vec<SlotChange>) -> Self {
+        InventoryChanged { changes }
+    }
+
+    pub fn changes(&self) -> &[SlotChange] {
+        &self.changes
+    }
+
+    /// Returns true, if a slot of the inventory of `entity` changed.
+    pub fn affects(&self, entity: Entity) -> bool {
+        self.changes.iter().any(|c| c.get_inventory() == entity)
+    }
+}
+
+/// An event that marks the change of the inventory of an entity in the current frame as an action of the player, that
+/// is recorded by its `InventoryHistory`.
+#[derive(Event)]
+pub struct RecordInventoryAction {
+    inventory: Entity,
+}
+
+impl RecordInventoryAction {
+    pub fn new(inventory: Entity) -> Self {
+        RecordInventoryAction { inventory }
+    }
+
+    pub fn inventory(&self) -> Entity {
+        self.inventory
+    }
+}
+
+/// An event that undoes the last recorded action on the inventory of an entity with an `InventoryHistory`.
+#[derive(Event)]
+pub struct UndoInventory {
+    inventory: Entity,
+}
+
+impl UndoInventory {
+    pub fn new(inventory: Entity) -> Self {
+        UndoInventory { inventory }
+    }
+
+    pub fn inventory(&self) -> Entity {
+        self.inventory
+    }
+}
+
+/// An event that redoes the last undone action on the inventory of an entity with an `InventoryHistory`.
+#[derive(Event)]
+pub struct RedoInventory {
+    inventory: Entity,
+}
+
+impl RedoInventory {
+    pub fn new(inventory: Entity) -> Self {
+        RedoInventory { inventory }
+    }
+
+    pub fn inventory(&self) -> Entity {
+        self.inventory
+    }
+}
+
+/// An event that shows an error toast above the inventory ui. It is sent, when an action of the player fails.
+#[derive(Event)]
+pub struct ShowInventoryError {
+    error: InventoryError,
+}
+
+impl ShowInventoryError {
+    pub fn new(error: InventoryError) -> Self {
+        ShowInventoryError { error }
+    }
+
+    pub fn error(&self) -> &InventoryError {
+        &self.error
+    }
+}
+
+/// An event, when an item instance was put into the inventory of an entity.
+///
+/// The change events `ItemAdded`, `ItemRemoved`, `ItemMoved`, `StackCountChanged` and `InventoryCleared` are sent
+/// once per frame for every inventory, that changed since the last frame, no matter if the change was made by the
+/// inventory ui, an event or by calling the methods of `Inventory` directly. Items are told apart by their instance id,
+/// including the contents of containers. An inventory component, that was added or replaced, e.g. when loading a
+/// game, is compared with its previous contents, or with an empty inventory, if there were none.
+///
+/// # Example
+/// ```
+/// use bevy::prelude::Entity;
+/// use bevy_inventory_system::prelude::*;
+/// let item = InventoryItem::new("arrow", 20);
+/// let location = ItemLocation::new(None, SlotPosition::new(0, 1, 2));
+/// let event = ItemAdded::new(Entity::PLACEHOLDER, "arrow", item.get_instance_id(), location, 20);
+/// assert_eq!(event.item_id(), "arrow");
+/// assert_eq!(event.position(), SlotPosition::new(0, 1, 2));
+/// assert_eq!(event.count(), 20);
+/// ```
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct ItemAdded {
+    inventory: Entity,
+    item_id: String,
+    instance_id: ItemInstanceId,
+    location: ItemLocation,
+    count: u32,
+}
+
+impl ItemAdded {
+    pub fn new(
+        inventory: Entity,
+        item_id: impl Into<String>,
+        instance_id: ItemInstanceId,
+        location: ItemLocation,
+        count: u32,
+    ) -> Self {
+        ItemAdded {
+            inventory,
+            item_id: item_id.into(),
+            instance_id,
+            location,
+            count,
+        }
+    }
+
+    pub fn inventory(&self) -> Entity {
+        self.inventory
+    }
+    pub fn item_id(&self) -> &str {
+        &self.item_id
+    }
+    pub fn instance_id(&self) -> ItemInstanceId {
+        self.instance_id
+    }
+    pub fn location(&self) -> ItemLocation {
+        self.location
+    }
+    pub fn position(&self) -> SlotPosition {
+        self.location.position
+    }
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+/// An event, when an item instance left the inventory of an entity. See `ItemAdded`.
+///
+/// The location is the one the item had before it was removed.
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct ItemRemoved {
+    inventory: Entity,
+    item_id: String,
+    instance_id: ItemInstanceId,
+    location: ItemLocation,
+    count: u32,
+}
+
+impl ItemRemoved {
+    pub fn new(
+        inventory: Entity,
+        item_id: impl Into<String>,
+        instance_id: ItemInstanceId,
+        location: ItemLocation,
+        count: u32,
+    ) -> Self {
+        ItemRemoved {
+            inventory,
+            item_id: item_id.into(),
+            instance_id,
+            location,
+            count,
+        }
+    }
+
+    pub fn inventory(&self) -> Entity {
+        self.inventory
+    }
+    pub fn item_id(&self) -> &str {
+        &self.item_id
+    }
+    pub fn instance_id(&self) -> ItemInstanceId {
+        self.instance_id
+    }
+    pub fn location(&self) -> ItemLocation {
+        self.location
+    }
+    pub fn position(&self) -> SlotPosition {
+        self.location.position
+    }
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+/// An event, when an item instance was moved to another slot of the same inventory, possibly into or out of a
+/// container. See `ItemAdded`.
+///
+/// Moving an item to another inventory sends `ItemRemoved` for the old and `ItemAdded` for the new inventory.
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct ItemMoved {
+    inventory: Entity,
+    item_id: String,
+    instance_id: ItemInstanceId,
+    from: ItemLocation,
+    to: ItemLocation,
+    count: u32,
+}
+
+impl ItemMoved {
+    pub fn new(
+        inventory: Entity,
+        item_id: impl Into<String>,
+        instance_id: ItemInstanceId,
+        from: ItemLocation,
+        to: ItemLocation,
+        count: u32,
+    ) -> Self {
+        ItemMoved {
+            inventory,
+            item_id: item_id.into(),
+            instance_id,
+            from,
+            to,
+            count,
+        }
+    }
+
+    pub fn inventory(&self) -> Entity {
+        self.inventory
+    }
+    pub fn item_id(&self) -> &str {
+        &self.item_id
+    }
+    pub fn instance_id(&self) -> ItemInstanceId {
+        self.instance_id
+    }
+    pub fn from(&self) -> ItemLocation {
+        self.from
+    }
+    pub fn to(&self) -> ItemLocation {
+        self.to
+    }
+    /// Returns the number of items in the stack after the move.
+    pub fn count(&self) -> u32 {
+        self.count
+    }
+}
+
+/// An event, when the number of items in a stack of the inventory of an entity changed, e.g. by merging stacks or
+/// consuming items. See `ItemAdded`.
+///
+/// The location is the one of the stack after the change.
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct StackCountChanged {
+    inventory: Entity,
+    item_id: String,
+    instance_id: ItemInstanceId,
+    location: ItemLocation,
+    old_count: u32,
+    new_count: u32,
+}
+
+impl StackCountChanged {
+    pub fn new(
+        inventory: Entity,
+        item_id: impl Into<String>,
+        instance_id: ItemInstanceId,
+        location: ItemLocation,
+        old_count: u32,
+        new_count: u32,
+    ) -> Self {
+        StackCountChanged {
+            inventory,
+            item_id: item_id.into(),
+            instance_id,
+            location,
+            old_count,
+            new_count,
+        }
+    }
+
+    pub fn inventory(&self) -> Entity {
+        self.inventory
+    }
+    pub fn item_id(&self) -> &str {
+        &self.item_id
+    }
+    pub fn instance_id(&self) -> ItemInstanceId {
+        self.instance_id
+    }
+    pub fn location(&self) -> ItemLocation {
+        self.location
+    }
+    pub fn position(&self) -> SlotPosition {
+        self.location.position
+    }
+    pub fn old_count(&self) -> u32 {
+        self.old_count
+    }
+    pub fn new_count(&self) -> u32 {
+        self.new_count
+    }
+}
+
+/// An event, when the last item left the inventory of an entity. See `ItemAdded`.
+///
+/// It is sent after the `ItemRemoved` events of the removed items.
+#[derive(Event, Clone, Debug, PartialEq)]
+pub struct InventoryCleared {
+    inventory: Entity,
+}
+
+impl InventoryCleared {
+    pub fn new(inventory: Entity) -> Self {
+        InventoryCleared { inventory }
+    }
+
+    pub fn inventory(&self) -> Entity {
+        self.inventory
+    }
+}
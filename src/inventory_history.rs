@@ -0,0 +1,202 @@
+use std::collections::VecDeque;
+
+use bevy::prelude::*;
+
+use crate::inventory::{Inventory, SlotPosition};
+use crate::inventory_item::InventoryItem;
+use crate::inventory_transaction::diff_inventories;
+
+/// A recorded action: the changed slots with their items before and after the action.
+#[derive(Clone, Debug, PartialEq)]
+struct InventoryCommand {
+    changes: Vec<(SlotPosition, Option<InventoryItem>, Option<InventoryItem>)>,
+}
+
+impl InventoryCommand {
+    /// Puts the items before (`undo`) or after the action back into their slots. Returns false and changes nothing, if
+    /// a slot no longer holds the item the command expects.
+    fn apply(&self, inventory: &mut Inventory, undo: bool) -> bool {
+        let expected = |(_, old, new): &(SlotPosition, Option<InventoryItem>, Option<InventoryItem>)| {
+            if undo { new.clone() } else { old.clone() }
+        };
+        if !self.changes.iter().all(|c| inventory.get_item(c.0).cloned() == expected(c)) {
+            return false;
+        }
+
+        for (pos, old, new) in &self.changes {
+            let _ = inventory.replace_slot(*pos, if undo { old.clone() } else { new.clone() });
+        }
+
+        true
+    }
+}
+
+/// Returns true, if both inventories have pages of the same size.
+fn same_layout(a: &Inventory, b: &Inventory) -> bool {
+    a.get_page_count() == b.get_page_count()
+        && a.get_pages()
+            .iter()
+            .zip(b.get_pages())
+            .all(|(a, b)| a.get_rows() == b.get_rows() && a.get_columns() == b.get_columns())
+}
+
+/// Records the actions of the player on the inventory of an entity, so they can be undone and redone.
+///
+/// A change of the inventory is recorded as an action, if a `RecordInventoryAction` event was sent for the entity in
+/// the same frame. Dragging and dropping items in the inventory ui sends this event. Any other change, e.g. picking
+/// up an item, clears the history, because the recorded actions may no longer fit the inventory.
+///
+/// `UndoInventory` and `RedoInventory` events undo and redo the actions. While the inventory is shown, Ctrl+Z undoes
+/// and Ctrl+Y or Ctrl+Shift+Z redoes an action on the shown inventory, see `InventorySettings`.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let database = ItemDatabase::default();
+/// let mut history = InventoryHistory::new(20);
+/// let mut inventory = Inventory::new(2, 2);
+/// inventory.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("sword", 1), &database);
+///
+/// let before = inventory.clone();
+/// inventory.move_item(SlotPosition::new(0, 0, 0), SlotPosition::new(0, 1, 1), &database).unwrap();
+/// assert!(history.record(&before, &inventory));
+///
+/// assert!(history.undo(&mut inventory));
+/// assert_eq!(inventory, before);
+/// assert!(history.can_redo());
+///
+/// assert!(history.redo(&mut inventory));
+/// assert!(inventory.get_item(SlotPosition::new(0, 1, 1)).is_some());
+/// ```
+#[derive(Component, Clone, Debug)]
+pub struct InventoryHistory {
+    undo: VecDeque<InventoryCommand>,
+    redo: Vec<InventoryCommand>,
+    limit: usize,
+    snapshot: Option<Inventory>, // The inventory after the last recorded change
+}
+
+impl Default for InventoryHistory {
+    fn default() -> Self {
+        Self::new(50)
+    }
+}
+
+impl InventoryHistory {
+    /// Creates a history keeping up to `limit` actions.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            limit,
+            snapshot: None,
+        }
+    }
+
+    pub fn get_limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Returns the number of actions, that can be undone.
+    pub fn get_undo_count(&self) -> usize {
+        self.undo.len()
+    }
+
+    /// Returns the number of actions, that can be redone.
+    pub fn get_redo_count(&self) -> usize {
+        self.redo.len()
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forgets all recorded actions.
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    /// Records the change from `before` to `after` as an action and forgets the actions, that could be redone.
+    ///
+    /// Returns false and records nothing, if nothing changed or the pages of the inventories differ in size.
+    pub fn record(&mut self, before: &Inventory, after: &Inventory) -> bool {
+        if !same_layout(before, after) {
+            return false;
+        }
+
+        let changes: Vec<_> = diff_inventories(Entity::PLACEHOLDER, before, after)
+            .into_iter()
+            .map(|c| (c.get_position(), c.get_old().cloned(), c.get_new().cloned()))
+            .collect();
+        if changes.is_empty() {
+            return false;
+        }
+
+        self.undo.push_back(InventoryCommand { changes });
+        while self.undo.len() > self.limit {
+            self.undo.pop_front();
+        }
+        self.redo.clear();
+
+        true
+    }
+
+    /// Undoes the last action.
+    ///
+    /// Returns false, if there is nothing to undo or the inventory no longer matches the action. The history is
+    /// cleared in the latter case.
+    pub fn undo(&mut self, inventory: &mut Inventory) -> bool {
+        let Some(command) = self.undo.pop_back() else {
+            return false;
+        };
+
+        if !command.apply(inventory, true) {
+            self.clear();
+            return false;
+        }
+
+        self.redo.push(command);
+        self.snapshot = Some(inventory.clone());
+        true
+    }
+
+    /// Redoes the last undone action.
+    ///
+    /// Returns false, if there is nothing to redo or the inventory no longer matches the action. The history is
+    /// cleared in the latter case.
+    pub fn redo(&mut self, inventory: &mut Inventory) -> bool {
+        let Some(command) = self.redo.pop() else {
+            return false;
+        };
+
+        if !command.apply(inventory, false) {
+            self.clear();
+            return false;
+        }
+
+        self.undo.push_back(command);
+        self.snapshot = Some(inventory.clone());
+        true
+    }
+
+    /// Compares `inventory` with the snapshot taken at the last change. The change is recorded as an action, if
+    /// `is_action` is true, and clears the history otherwise.
+    pub(crate) fn observe(&mut self, inventory: &Inventory, is_action: bool) {
+        match self.snapshot.take() {
+            Some(snapshot) if snapshot == *inventory => {
+                self.snapshot = Some(snapshot);
+                return;
+            }
+            Some(snapshot) if !is_action || !self.record(&snapshot, inventory) => self.clear(),
+            _ => {}
+        }
+
+        self.snapshot = Some(inventory.clone());
+    }
+}
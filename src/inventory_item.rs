@@ -0,0 +1,442 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::inventory::Inventory;
+
+/// The unique id of an item instance.
+///
+/// Ids are taken from a counter shared by the whole program, so the same sequence of created items always gets the
+/// same ids. Loading an id, or creating it with `from_raw`, moves the counter past it, so new items never reuse the id
+/// of a loaded item. Ids stay the same when an item is moved, transferred to another inventory or saved and loaded
+/// again.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let first = ItemInstanceId::new();
+/// assert!(ItemInstanceId::new() > first);
+///
+/// // An id loaded from a save game is never handed out again.
+/// let loaded: ItemInstanceId = ron::from_str("(1000000)").unwrap();
+/// assert!(ItemInstanceId::new() > loaded);
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct ItemInstanceId(u64);
+
+/// The next id handed out by `ItemInstanceId::new`.
+static NEXT_INSTANCE_ID: AtomicU64 = AtomicU64::new(1);
+
+impl ItemInstanceId {
+    /// Creates a new id, that has not been used before.
+    pub fn new() -> Self {
+        Self(NEXT_INSTANCE_ID.fetch_add(1, Ordering::Relaxed))
+    }
+
+    pub fn from_raw(value: u64) -> Self {
+        NEXT_INSTANCE_ID.fetch_max(value.saturating_add(1), Ordering::Relaxed);
+        Self(value)
+    }
+
+    pub fn get_raw(&self) -> u64 {
+        self.0
+    }
+}
+
+impl Default for ItemInstanceId {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'de> Deserialize<'de> for ItemInstanceId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        // Same format as the derived implementation.
+        #[derive(Deserialize)]
+        #[serde(rename = "ItemInstanceId")]
+        struct Raw(u64);
+
+        Raw::deserialize(deserializer).map(|raw| Self::from_raw(raw.0))
+    }
+}
+
+impl fmt::Display for ItemInstanceId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:016x}", self.0)
+    }
+}
+
+/// A single value stored in the `ItemData` of an item instance.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum ItemValue {
+    Bool(bool),
+    Int(i64),
+    Float(f64),
+    Text(String),
+    List(Vec<ItemValue>),
+}
+
+impl From<bool> for ItemValue {
+    fn from(value: bool) -> Self {
+        ItemValue::Bool(value)
+    }
+}
+
+impl From<i64> for ItemValue {
+    fn from(value: i64) -> Self {
+        ItemValue::Int(value)
+    }
+}
+
+impl From<f64> for ItemValue {
+    fn from(value: f64) -> Self {
+        ItemValue::Float(value)
+    }
+}
+
+impl From<&str> for ItemValue {
+    fn from(value: &str) -> Self {
+        ItemValue::Text(value.to_string())
+    }
+}
+
+impl From<String> for ItemValue {
+    fn from(value: String) -> Self {
+        ItemValue::Text(value)
+    }
+}
+
+impl From<Vec<ItemValue>> for ItemValue {
+    fn from(value: Vec<ItemValue>) -> Self {
+        ItemValue::List(value)
+    }
+}
+
+/// Holds the per-instance data of an item, like enchantments or the name of its crafter.
+///
+/// Values are stored by key. Two items only stack, if their data is equal.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let mut data = ItemData::default();
+/// data.set("enchantment", "fire");
+/// data.set("level", 3);
+///
+/// assert_eq!(data.get_text("enchantment"), Some("fire"));
+/// assert_eq!(data.get_int("level"), Some(3));
+/// assert_eq!(data.get_int("enchantment"), None);
+///
+/// data.remove("level");
+/// assert!(!data.contains("level"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ItemData {
+    values: BTreeMap<String, ItemValue>,
+}
+
+impl ItemData {
+    pub fn set(&mut self, key: impl Into<String>, value: impl Into<ItemValue>) {
+        self.values.insert(key.into(), value.into());
+    }
+
+    pub fn get(&self, key: &str) -> Option<&ItemValue> {
+        self.values.get(key)
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<ItemValue> {
+        self.values.remove(key)
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.values.contains_key(key)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn get_bool(&self, key: &str) -> Option<bool> {
+        match self.values.get(key) {
+            Some(ItemValue::Bool(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn get_int(&self, key: &str) -> Option<i64> {
+        match self.values.get(key) {
+            Some(ItemValue::Int(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn get_float(&self, key: &str) -> Option<f64> {
+        match self.values.get(key) {
+            Some(ItemValue::Float(value)) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn get_text(&self, key: &str) -> Option<&str> {
+        match self.values.get(key) {
+            Some(ItemValue::Text(value)) => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Iterates over all keys and values in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&String, &ItemValue)> {
+        self.values.iter()
+    }
+}
+
+/// The durability of an item instance.
+///
+/// An item breaks, once its durability reaches zero.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemDurability {
+    current: u32,
+    max: u32,
+}
+
+impl ItemDurability {
+    /// Creates a durability with the given current and maximum value. `current` is clamped to `max`.
+    pub fn new(current: u32, max: u32) -> Self {
+        Self {
+            current: current.min(max),
+            max,
+        }
+    }
+
+    pub fn get_current(&self) -> u32 {
+        self.current
+    }
+
+    pub fn get_max(&self) -> u32 {
+        self.max
+    }
+
+    /// Returns the current durability as a fraction of the maximum between 0 and 1.
+    pub fn get_fraction(&self) -> f32 {
+        if self.max == 0 {
+            0.0
+        } else {
+            self.current as f32 / self.max as f32
+        }
+    }
+
+    pub fn is_broken(&self) -> bool {
+        self.current == 0
+    }
+
+    pub fn is_damaged(&self) -> bool {
+        self.current < self.max
+    }
+}
+
+/// Represents a stack of items stored in an inventory slot.
+///
+/// The `item_id` refers to an `ItemDefinition` in the `ItemDatabase`. Every stack is an item instance with its own
+/// unique `instance_id` and per-instance `data`.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::InventoryItem;
+/// let mut item = InventoryItem::new("wolf_pelt", 3);
+/// assert_eq!(item.get_item_id(), "wolf_pelt");
+/// assert_eq!(item.get_count(), 3);
+///
+/// item.set_count(5);
+/// assert_eq!(item.get_count(), 5);
+///
+/// // Splitting a stack creates a new instance with the same data.
+/// let half = item.split(2).unwrap();
+/// assert_eq!(item.get_count(), 3);
+/// assert_ne!(half.get_instance_id(), item.get_instance_id());
+/// assert!(half.can_stack_with(&item));
+///
+/// let mut enchanted = InventoryItem::new("wolf_pelt", 1);
+/// enchanted.get_data_mut().set("enchantment", "frost");
+/// assert!(!enchanted.can_stack_with(&item));
+///
+/// // Items with durability break, once it is used up.
+/// let mut pickaxe = InventoryItem::new("pickaxe", 1).with_durability(10);
+/// assert!(!pickaxe.consume_durability(4));
+/// assert_eq!(pickaxe.get_durability().unwrap().get_current(), 6);
+/// assert!(pickaxe.consume_durability(20));
+/// assert!(pickaxe.is_broken());
+///
+/// pickaxe.repair();
+/// assert!(!pickaxe.is_broken());
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct InventoryItem {
+    item_id: String,
+    count: u32,
+    instance_id: ItemInstanceId,
+    data: ItemData,
+    #[serde(default)]
+    durability: Option<ItemDurability>,
+    #[serde(default)]
+    contents: Option<Box<Inventory>>, // Inventory of a container item
+}
+
+impl InventoryItem {
+    /// Creates a new item instance with a new unique id and no data.
+    pub fn new(item_id: impl Into<String>, count: u32) -> Self {
+        Self {
+            item_id: item_id.into(),
+            count,
+            instance_id: ItemInstanceId::new(),
+            data: ItemData::default(),
+            durability: None,
+            contents: None,
+        }
+    }
+
+    /// Sets the per-instance data of the item.
+    pub fn with_data(mut self, data: ItemData) -> Self {
+        self.data = data;
+        self
+    }
+
+    /// Gives the item a durability of `max`, starting undamaged.
+    pub fn with_durability(mut self, max: u32) -> Self {
+        self.durability = Some(ItemDurability::new(max, max));
+        self
+    }
+
+    /// Turns the item into a container holding the given inventory, e.g. a pouch or a backpack.
+    pub fn with_contents(mut self, contents: Inventory) -> Self {
+        self.contents = Some(Box::new(contents));
+        self
+    }
+
+    pub fn get_item_id(&self) -> &str {
+        &self.item_id
+    }
+
+    /// Changes the definition of the item, e.g. when it turns into its broken variant. The instance id and data are
+    /// kept.
+    pub(crate) fn set_item_id(&mut self, value: impl Into<String>) {
+        self.item_id = value.into();
+    }
+
+    pub fn get_count(&self) -> u32 {
+        self.count
+    }
+
+    pub fn set_count(&mut self, value: u32) {
+        self.count = value;
+    }
+
+    pub fn get_instance_id(&self) -> ItemInstanceId {
+        self.instance_id
+    }
+
+    pub fn get_data(&self) -> &ItemData {
+        &self.data
+    }
+
+    pub fn get_data_mut(&mut self) -> &mut ItemData {
+        &mut self.data
+    }
+
+    pub fn get_durability(&self) -> Option<&ItemDurability> {
+        self.durability.as_ref()
+    }
+
+    pub fn set_durability(&mut self, value: Option<ItemDurability>) {
+        self.durability = value;
+    }
+
+    /// Returns the inventory of a container item.
+    pub fn get_contents(&self) -> Option<&Inventory> {
+        self.contents.as_deref()
+    }
+
+    pub fn get_contents_mut(&mut self) -> Option<&mut Inventory> {
+        self.contents.as_deref_mut()
+    }
+
+    /// Returns the inventory of a container item. An empty inventory with the given grid size is created, if the item
+    /// has none yet.
+    pub fn get_or_create_contents(&mut self, rows: usize, columns: usize) -> &mut Inventory {
+        self.contents.get_or_insert_with(|| Box::new(Inventory::new(rows, columns)))
+    }
+
+    /// Returns true, if the item has a durability and it is used up.
+    pub fn is_broken(&self) -> bool {
+        self.durability.is_some_and(|d| d.is_broken())
+    }
+
+    /// Reduces the durability of the item by `amount`. Items without durability are not affected.
+    ///
+    /// Returns true, if the item broke because of this call.
+    pub fn consume_durability(&mut self, amount: u32) -> bool {
+        let Some(durability) = self.durability.as_mut() else {
+            return false;
+        };
+
+        if durability.is_broken() || amount == 0 {
+            return false;
+        }
+
+        durability.current = durability.current.saturating_sub(amount);
+        durability.is_broken()
+    }
+
+    /// Uses up the durability of the item. An item without durability gets a durability of 1, that is used up, so it
+    /// can be repaired like any other broken item.
+    pub(crate) fn set_broken(&mut self) {
+        let max = self.durability.map_or(1, |d| d.max.max(1));
+        self.durability = Some(ItemDurability::new(0, max));
+    }
+
+    /// Restores the full durability of the item.
+    pub fn repair(&mut self) {
+        if let Some(durability) = self.durability.as_mut() {
+            durability.current = durability.max;
+        }
+    }
+
+    /// Returns true, if both items have the same definition, equal data and equal durability. Containers never stack.
+    pub fn can_stack_with(&self, other: &InventoryItem) -> bool {
+        self.item_id == other.item_id
+            && self.data == other.data
+            && self.durability == other.durability
+            && self.contents.is_none()
+            && other.contents.is_none()
+    }
+
+    /// Returns a new instance of `count` items with the same definition, data and durability. The contents of a
+    /// container are not copied.
+    pub fn duplicate(&self, count: u32) -> InventoryItem {
+        InventoryItem {
+            item_id: self.item_id.clone(),
+            count,
+            instance_id: ItemInstanceId::new(),
+            data: self.data.clone(),
+            durability: self.durability,
+            contents: None,
+        }
+    }
+
+    /// Removes `count` items from this stack and returns them as a new instance with the same data.
+    ///
+    /// Returns `None`, if `count` is 0 or not less than the size of the stack, or the item is a container.
+    pub fn split(&mut self, count: u32) -> Option<InventoryItem> {
+        if count == 0 || count >= self.count || self.contents.is_some() {
+            return None;
+        }
+
+        self.count -= count;
+
+        Some(self.duplicate(count))
+    }
+}
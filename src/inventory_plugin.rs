@@ -15,28 +15,176 @@ impl Plugin for InventoryPlugin {
         app.add_event::<InventoryIconMouseExit>();
         app.add_event::<ResetInventoryIcons>();
         app.add_event::<ToggleInventory>();
+        app.add_event::<SetInventoryTheme>();
+        app.add_event::<ScrollToSlot>();
+        app.add_event::<SetInventoryPage>();
+        app.add_event::<RebuildInventory>();
+        app.add_event::<ConsumeDurability>();
+        app.add_event::<RepairItem>();
+        app.add_event::<ItemBroken>();
+        app.add_event::<CurrencyChanged>();
+        app.add_event::<BuyItem>();
+        app.add_event::<SellItem>();
+        app.add_event::<BuybackItem>();
+        app.add_event::<VendorRestocked>();
+        app.add_event::<OfferTradeItem>();
+        app.add_event::<WithdrawTradeItem>();
+        app.add_event::<ConfirmTrade>();
+        app.add_event::<CancelTrade>();
+        app.add_event::<TradeFinished>();
+        app.add_event::<ApplyInventoryTransaction>();
+        app.add_event::<InventoryChanged>();
+        app.add_event::<InventoryTransactionFailed>();
+        app.add_event::<RecordInventoryAction>();
+        app.add_event::<UndoInventory>();
+        app.add_event::<RedoInventory>();
+        app.add_event::<ShowInventoryError>();
+        app.add_event::<ItemAdded>();
+        app.add_event::<ItemRemoved>();
+        app.add_event::<ItemMoved>();
+        app.add_event::<StackCountChanged>();
+        app.add_event::<InventoryCleared>();
 
         // Resources
         app.init_resource::<InventoryStyle>();
         app.init_resource::<InventoryBackgroundTiles>();
         app.init_resource::<InventoryState>();
+        app.init_resource::<InventoryThemes>();
+        app.init_resource::<InventorySettings>();
+        app.init_resource::<ItemDatabase>();
+        app.init_resource::<InventoryRules>();
+        app.init_resource::<InventorySearch>();
+        app.init_resource::<ItemIcons>();
 
         // Systems
-        app.add_systems(OnEnter(InventoryStates::Shown), show_hide_system::show);
-        app.add_systems(OnEnter(InventoryStates::Hidden), show_hide_system::hide);
+        // Leaving `Hidden` spawns the ui, both for `Opening` and for switching to `Shown` directly.
+        app.add_systems(OnExit(InventoryStates::Hidden), show_hide_system::show);
+        app.add_systems(OnEnter(InventoryStates::Shown), transition_system::finish_transition);
+        app.add_systems(
+            OnEnter(InventoryStates::Hidden),
+            (show_hide_system::hide, transition_system::reset_transition),
+        );
 
         app.add_systems(
             Update,
             (
                 button_system::button_system.run_if(in_state(InventoryStates::Shown)),
-                show_hide_system::receive_toggle_inventory_events_shown.run_if(in_state(InventoryStates::Shown)),
-                show_hide_system::receive_toggle_inventory_events_hidden.run_if(in_state(InventoryStates::Hidden)),
-                window_resized_system::on_window_size_changed.run_if(in_state(InventoryStates::Shown)),
+                show_hide_system::receive_toggle_inventory_events,
+                transition_system::animate_transition
+                    .after(slot_contents_system::update_slot_contents)
+                    .run_if(in_state(InventoryStates::Opening).or_else(in_state(InventoryStates::Closing))),
+                window_resized_system::on_window_size_changed.run_if(not(in_state(InventoryStates::Hidden))),
                 reset_icons_system::reset_icons_events.run_if(in_state(InventoryStates::Shown)),
-                mouse_scroll_system::mouse_scroll.run_if(in_state(InventoryStates::Shown)),
+                (
+                    mouse_scroll_system::mouse_scroll,
+                    scrollbar_system::drag_scrollbars,
+                    keyboard_navigation_system::keyboard_navigation,
+                    mouse_scroll_system::scroll_to_slot.after(tab_system::rebuild_inventory),
+                    mouse_scroll_system::smooth_scroll,
+                    virtualization_system::recycle_virtual_rows,
+                    scrollbar_system::update_scrollbars,
+                )
+                    .chain()
+                    .run_if(in_state(InventoryStates::Shown)),
+                theme_system::apply_theme,
+                (
+                    tab_system::tab_buttons,
+                    tab_system::set_inventory_page,
+                    tab_system::rebuild_on_resize,
+                    tab_system::rebuild_inventory,
+                )
+                    .chain(),
+                (
+                    drag_drop_system::start_drag,
+                    drag_drop_system::update_drag,
+                    drag_drop_system::end_drag,
+                )
+                    .chain()
+                    .run_if(in_state(InventoryStates::Shown)),
+                (
+                    container_system::open_context_menu,
+                    container_system::context_menu_actions,
+                    container_system::close_context_menu,
+                    container_system::close_container_windows,
+                )
+                    .chain()
+                    .run_if(in_state(InventoryStates::Shown)),
+                container_system::update_container_windows,
+                durability_system::apply_durability_events,
+                transaction_system::apply_inventory_transactions,
+                slot_contents_system::update_slot_contents,
+                tab_system::update_tabs,
+            ),
+        );
+
+        // Trading
+        app.add_systems(
+            Update,
+            (
+                wallet_system::send_currency_events,
+                wallet_system::update_wallet_footer,
+                vendor_system::vendor_buttons.run_if(in_state(InventoryStates::Shown)),
+                vendor_system::apply_vendor_events.after(vendor_system::vendor_buttons),
+                vendor_system::restock_vendors,
+                vendor_system::update_vendor_window.after(tab_system::rebuild_inventory),
+                trade_system::trade_buttons.run_if(in_state(InventoryStates::Shown)),
+                trade_system::apply_trade_events.after(trade_system::trade_buttons),
+                trade_system::update_trade_window.after(tab_system::rebuild_inventory),
+            ),
+        );
+
+        // Undo and redo
+        app.add_systems(
+            Update,
+            (
+                (
+                    history_system::history_keys.run_if(in_state(InventoryStates::Shown)),
+                    history_system::apply_history_events,
+                )
+                    .chain(),
+                history_system::record_inventory_history
+                    .after(history_system::apply_history_events)
+                    .after(drag_drop_system::end_drag),
             ),
         );
 
+        // Error toasts
+        app.add_systems(
+            Update,
+            (toast_system::show_error_toasts, toast_system::expire_error_toasts)
+                .chain()
+                .after(drag_drop_system::end_drag)
+                .after(vendor_system::apply_vendor_events)
+                .after(trade_system::apply_trade_events)
+                .after(durability_system::apply_durability_events)
+                .after(transaction_system::apply_inventory_transactions)
+                .after(history_system::apply_history_events),
+        );
+
+        // Icon atlases, before every system showing item icons
+        app.add_systems(
+            Update,
+            icon_atlas_system::load_icon_atlases
+                .before(slot_contents_system::update_slot_contents)
+                .before(container_system::update_container_windows)
+                .before(drag_drop_system::start_drag)
+                .before(vendor_system::update_vendor_window)
+                .before(trade_system::update_trade_window),
+        );
+
+        // Rarity colors, after every system setting the colors of slots
+        app.add_systems(
+            Update,
+            rarity_system::apply_rarity_colors
+                .after(button_system::button_system)
+                .after(reset_icons_system::reset_icons_events)
+                .after(slot_contents_system::update_slot_contents)
+                .run_if(in_state(InventoryStates::Shown)),
+        );
+
+        // Change events, after every change made in `Update`
+        app.add_systems(PostUpdate, change_events_system::send_item_change_events);
+
         // Initial state
         app.init_state::<InventoryStates>();
     }
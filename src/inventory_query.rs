@@ -0,0 +1,133 @@
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::inventory::{Inventory, ItemLocation};
+use crate::inventory_error::InventoryError;
+use crate::inventory_rules::InventoryRules;
+use crate::item_database::ItemDatabase;
+use crate::item_filter::ItemFilter;
+
+/// A system parameter to count, find and remove items across the inventories of one or more entities, including the
+/// contents of containers.
+///
+/// The inventories are searched in the given order, each in the order of `Inventory::get_nested_items`. Entities
+/// without an inventory are skipped and entities given twice are searched once.
+///
+/// # Example
+///
+/// ```
+/// use bevy::{ecs::system::SystemState, prelude::*};
+/// use bevy_inventory_system::prelude::*;
+///
+/// let mut world = World::new();
+/// let mut database = ItemDatabase::default();
+/// database.add_item(ItemDefinition::new("wolf_pelt", "Wolf Pelt", "Items/wolf_pelt.png").with_tag("pelt"));
+///
+/// let mut backpack = Inventory::new(2, 2);
+/// backpack.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("wolf_pelt", 2), &database);
+/// let mut saddlebag = Inventory::new(1, 1);
+/// saddlebag.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("wolf_pelt", 2), &database);
+/// world.insert_resource(database);
+/// world.init_resource::<InventoryRules>();
+/// let player = world.spawn(backpack).id();
+/// let horse = world.spawn(saddlebag).id();
+///
+/// let mut state = SystemState::<InventoryQuery>::new(&mut world);
+/// let mut query = state.get_mut(&mut world);
+///
+/// // "Does the player have 3 wolf pelts?"
+/// let pelts = ItemFilter::tag("pelt");
+/// assert!(query.has(&[player, horse], &pelts, 3));
+/// assert!(!query.has(&[player], &pelts, 3));
+///
+/// // Hand them over: the backpack is emptied first.
+/// assert!(query.remove(&[player, horse], &pelts, 3).is_ok());
+/// assert_eq!(query.count(&[player, horse], &pelts), 1);
+/// let left = ItemLocation::new(None, SlotPosition::new(0, 0, 0));
+/// assert_eq!(query.find(&[player, horse], &pelts), vec![(horse, left)]);
+/// assert_eq!(query.remove(&[player], &pelts, 1), Err(InventoryError::ItemNotFound));
+/// ```
+#[derive(SystemParam)]
+pub struct InventoryQuery<'w, 's> {
+    inventories: Query<'w, 's, &'static mut Inventory>,
+    database: Res<'w, ItemDatabase>,
+    rules: Res<'w, InventoryRules>,
+}
+
+/// Returns `entities` without duplicates, keeping the first occurrence.
+fn unique(entities: &[Entity]) -> Vec<Entity> {
+    let mut result = Vec::with_capacity(entities.len());
+    for &entity in entities {
+        if !result.contains(&entity) {
+            result.push(entity);
+        }
+    }
+
+    result
+}
+
+impl InventoryQuery<'_, '_> {
+    /// Returns the number of items matching `filter` in the inventories of `entities`.
+    pub fn count(&self, entities: &[Entity], filter: &ItemFilter) -> u32 {
+        unique(entities)
+            .into_iter()
+            .filter_map(|e| self.inventories.get(e).ok())
+            .map(|inventory| inventory.count_matching(filter, &self.database))
+            .sum()
+    }
+
+    /// Returns true, if there are at least `count` items matching `filter` in the inventories of `entities`.
+    pub fn has(&self, entities: &[Entity], filter: &ItemFilter, count: u32) -> bool {
+        self.count(entities, filter) >= count
+    }
+
+    /// Returns the locations of the stacks matching `filter` together with the entity of their inventory.
+    pub fn find(&self, entities: &[Entity], filter: &ItemFilter) -> Vec<(Entity, ItemLocation)> {
+        unique(entities)
+            .into_iter()
+            .filter_map(|e| self.inventories.get(e).ok().map(|inventory| (e, inventory)))
+            .flat_map(|(e, inventory)| inventory.find_matching(filter, &self.database).into_iter().map(move |l| (e, l)))
+            .collect()
+    }
+
+    /// Removes `count` items matching `filter` from the inventories of `entities`, taking them from the stacks in the
+    /// order of `find`.
+    ///
+    /// Fails and removes nothing, if there are not enough items or one of the `InventoryRules` vetoes a removal.
+    pub fn remove(&mut self, entities: &[Entity], filter: &ItemFilter, count: u32) -> Result<(), InventoryError> {
+        if !self.has(entities, filter, count) {
+            return Err(InventoryError::ItemNotFound);
+        }
+
+        let mut changed = Vec::new();
+        let mut remaining = count;
+        for entity in unique(entities) {
+            let Ok(inventory) = self.inventories.get(entity) else {
+                continue;
+            };
+            if remaining == 0 {
+                break;
+            }
+
+            let mut copy = inventory.clone();
+            let removed = copy.remove_matching(filter, &self.database, remaining);
+            if removed > 0 {
+                self.rules.check_change(entity, inventory, &copy)?;
+                remaining -= removed;
+                changed.push((entity, copy));
+            }
+        }
+
+        for (entity, copy) in changed {
+            if let Ok(mut inventory) = self.inventories.get_mut(entity) {
+                *inventory = copy;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the inventory of `entity`.
+    pub fn get(&self, entity: Entity) -> Option<&Inventory> {
+        self.inventories.get(entity).ok()
+    }
+}
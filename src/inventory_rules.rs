@@ -0,0 +1,368 @@
+use std::borrow::Cow;
+
+use bevy::prelude::*;
+
+use crate::inventory::{Inventory, ItemLocation, SlotPosition};
+use crate::inventory_error::InventoryError;
+use crate::inventory_item::InventoryItem;
+use crate::inventory_transaction::{diff_items, ItemChange};
+
+/// An operation on the inventory of an entity, that is checked by the `InventoryRules` before it happens.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InventoryAction<'a> {
+    /// `count` items are put into the inventory. `item` is the stack at `location` after the operation.
+    Insert {
+        inventory: Entity,
+        location: ItemLocation,
+        item: &'a InventoryItem,
+        count: u32,
+    },
+    /// `count` items are taken out of the inventory. `item` is the stack at `location` before the operation.
+    Remove {
+        inventory: Entity,
+        location: ItemLocation,
+        item: &'a InventoryItem,
+        count: u32,
+    },
+    /// An item is moved to another slot of the same inventory, possibly into or out of a container.
+    Move {
+        inventory: Entity,
+        from: ItemLocation,
+        to: ItemLocation,
+        item: &'a InventoryItem,
+    },
+}
+
+impl InventoryAction<'_> {
+    pub fn get_inventory(&self) -> Entity {
+        match self {
+            InventoryAction::Insert { inventory, .. }
+            | InventoryAction::Remove { inventory, .. }
+            | InventoryAction::Move { inventory, .. } => *inventory,
+        }
+    }
+
+    /// Returns the item of the action.
+    pub fn get_item(&self) -> &InventoryItem {
+        match self {
+            InventoryAction::Insert { item, .. }
+            | InventoryAction::Remove { item, .. }
+            | InventoryAction::Move { item, .. } => item,
+        }
+    }
+}
+
+/// A rule, that can veto operations on inventories. Returning an error cancels the operation, the text is the reason
+/// shown to the player. The reason is either a static text or built at runtime, e.g. to name the item.
+///
+/// Closures taking an `InventoryAction` and returning `Result<(), &'static str>` or `Result<(), String>` implement
+/// this trait.
+pub trait InventoryRule: Send + Sync + 'static {
+    fn check(&self, action: &InventoryAction) -> Result<(), Cow<'static, str>>;
+}
+
+impl<F, R> InventoryRule for F
+where
+    F: Fn(&InventoryAction) -> Result<(), R> + Send + Sync + 'static,
+    R: Into<Cow<'static, str>>,
+{
+    fn check(&self, action: &InventoryAction) -> Result<(), Cow<'static, str>> {
+        self(action).map_err(Into::into)
+    }
+}
+
+/// The registry of rules, that are consulted before items are put into, taken out of or moved inside an inventory.
+///
+/// The rules are checked for the drag and drop of the inventory ui, including moves into and out of containers, and
+/// for the events changing inventories: `ApplyInventoryTransaction`, `BuyItem`, `SellItem`, `BuybackItem`, the trade
+/// events, `ConsumeDurability`, `RepairItem`, `UndoInventory` and `RedoInventory`, as well as for vendor restocks.
+/// Code changing an inventory directly consults them by running the change through `modify` or `modify_pair`. A vetoed
+/// operation changes nothing and fails with `InventoryError::Vetoed`, which the inventory ui shows in an error toast.
+///
+/// Rules only allow or veto an operation, they can not alter it. To change what an operation does, e.g. to halve the
+/// items a player receives, game code changes the inventory itself, again through `modify`.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// use bevy::prelude::*;
+/// let database = ItemDatabase::default();
+///
+/// // Quest items can't be taken out of an inventory.
+/// let rules = InventoryRules::default().with_rule(|action: &InventoryAction| match action {
+///     InventoryAction::Remove { item, .. } if item.get_item_id().starts_with("quest_") => {
+///         Err("This is a quest item")
+///     }
+///     _ => Ok(()),
+/// });
+///
+/// let player = Entity::from_raw(1);
+/// let mut inventory = Inventory::new(2, 2);
+/// let (a, b) = (SlotPosition::new(0, 0, 0), SlotPosition::new(0, 1, 1));
+/// inventory.insert_item(a, InventoryItem::new("quest_letter", 1), &database);
+///
+/// // Moving the item is fine, dropping it is not.
+/// assert!(rules.modify(player, &mut inventory, |inv| inv.move_item(a, b, &database)).is_ok());
+/// let result = rules.modify(player, &mut inventory, |inv| inv.take_item(b));
+/// assert_eq!(result, Err(InventoryError::Vetoed("This is a quest item".into())));
+/// assert!(inventory.get_item(b).is_some());
+///
+/// // Reasons can be built at runtime.
+/// let rules = InventoryRules::default().with_rule(|action: &InventoryAction| match action {
+///     InventoryAction::Insert { item, .. } => Err(format!("The {} does not fit", item.get_item_id())),
+///     _ => Ok(()),
+/// });
+/// let result = rules.modify(player, &mut inventory, |inv| inv.add_item(InventoryItem::new("anvil", 1), &database));
+/// assert_eq!(result.unwrap_err().to_string(), "The anvil does not fit");
+/// ```
+#[derive(Resource, Default)]
+pub struct InventoryRules {
+    rules: Vec<Box<dyn InventoryRule>>,
+}
+
+impl InventoryRules {
+    pub fn with_rule(mut self, rule: impl InventoryRule) -> Self {
+        self.add_rule(rule);
+        self
+    }
+
+    /// Adds a rule, that is checked after the rules added before.
+    pub fn add_rule(&mut self, rule: impl InventoryRule) {
+        self.rules.push(Box::new(rule));
+    }
+
+    pub fn get_rule_count(&self) -> usize {
+        self.rules.len()
+    }
+
+    /// Checks a single action. Fails with the reason of the first rule, that vetoes it.
+    pub fn check(&self, action: &InventoryAction) -> Result<(), InventoryError> {
+        self.rules.iter().try_for_each(|rule| rule.check(action).map_err(InventoryError::Vetoed))
+    }
+
+    /// Checks taking the whole stack at `pos` out of `inventory`, the inventory of `entity`, e.g. to sell or trade it.
+    /// Succeeds, if there is no item.
+    pub fn check_take(&self, entity: Entity, inventory: &Inventory, pos: SlotPosition) -> Result<(), InventoryError> {
+        let Some(item) = inventory.get_item(pos) else {
+            return Ok(());
+        };
+
+        self.check(&InventoryAction::Remove {
+            inventory: entity,
+            location: ItemLocation::new(None, pos),
+            item,
+            count: item.get_count(),
+        })
+    }
+
+    /// Checks every action, that changes the inventory of `entity` from `old` to `new`.
+    ///
+    /// The items are told apart by their instance id. A changed stack size is checked as an insert or a removal of
+    /// the difference.
+    pub fn check_change(&self, entity: Entity, old: &Inventory, new: &Inventory) -> Result<(), InventoryError> {
+        if self.rules.is_empty() {
+            return Ok(());
+        }
+
+        for change in diff_items(Some(old), new) {
+            match change {
+                ItemChange::Added(location, item) => self.check(&InventoryAction::Insert {
+                    inventory: entity,
+                    location,
+                    item,
+                    count: item.get_count(),
+                })?,
+                ItemChange::Removed(location, item) => self.check(&InventoryAction::Remove {
+                    inventory: entity,
+                    location,
+                    item,
+                    count: item.get_count(),
+                })?,
+                ItemChange::Changed(from, to, before, item) => {
+                    if from != to {
+                        self.check(&InventoryAction::Move {
+                            inventory: entity,
+                            from,
+                            to,
+                            item: before,
+                        })?;
+                    }
+
+                    let (old_count, new_count) = (before.get_count(), item.get_count());
+                    if new_count > old_count {
+                        self.check(&InventoryAction::Insert {
+                            inventory: entity,
+                            location: to,
+                            item,
+                            count: new_count - old_count,
+                        })?;
+                    } else if new_count < old_count {
+                        self.check(&InventoryAction::Remove {
+                            inventory: entity,
+                            location: from,
+                            item: before,
+                            count: old_count - new_count,
+                        })?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs `change` on a copy of `inventory`, the inventory of `entity`, and keeps the result, if the change
+    /// succeeded and no rule vetoed it. Otherwise `inventory` does not change.
+    ///
+    /// Without any rules `change` runs on `inventory` itself, so the inventory is not copied. A failing `change` then
+    /// must not leave a partial change behind, which holds for every single operation of `Inventory`, `Vendor` and
+    /// `TradeSession`.
+    pub fn modify<T>(
+        &self,
+        entity: Entity,
+        inventory: &mut Inventory,
+        change: impl FnOnce(&mut Inventory) -> Result<T, InventoryError>,
+    ) -> Result<T, InventoryError> {
+        if self.rules.is_empty() {
+            return change(inventory);
+        }
+
+        let mut copy = inventory.clone();
+        let result = change(&mut copy)?;
+
+        self.check_change(entity, inventory, &copy)?;
+        *inventory = copy;
+        Ok(result)
+    }
+
+    /// Runs `change` on copies of two inventories, each given with its entity, like `modify`. Keeps both results, if
+    /// the change succeeded and no rule vetoed the change of either inventory. Otherwise neither inventory changes.
+    /// Without any rules `change` runs on the inventories themselves, like in `modify`.
+    pub fn modify_pair<T>(
+        &self,
+        (first_entity, first): (Entity, &mut Inventory),
+        (second_entity, second): (Entity, &mut Inventory),
+        change: impl FnOnce(&mut Inventory, &mut Inventory) -> Result<T, InventoryError>,
+    ) -> Result<T, InventoryError> {
+        if self.rules.is_empty() {
+            return change(first, second);
+        }
+
+        let (mut first_copy, mut second_copy) = (first.clone(), second.clone());
+        let result = change(&mut first_copy, &mut second_copy)?;
+
+        self.check_change(first_entity, first, &first_copy)?;
+        self.check_change(second_entity, second, &second_copy)?;
+        *first = first_copy;
+        *second = second_copy;
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item_database::ItemDatabase;
+
+    fn no_removals() -> InventoryRules {
+        InventoryRules::default().with_rule(|action: &InventoryAction| match action {
+            InventoryAction::Remove { .. } => Err("Nothing can be removed"),
+            _ => Ok(()),
+        })
+    }
+
+    fn inventory(id: &str) -> Inventory {
+        let mut inventory = Inventory::new(1, 2);
+        inventory.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new(id, 1), &ItemDatabase::default()).unwrap();
+        inventory
+    }
+
+    #[test]
+    fn vetoed_modify_changes_nothing() {
+        let database = ItemDatabase::default();
+        let mut inventory = inventory("sword");
+        let before = inventory.clone();
+
+        let result = no_removals().modify(Entity::from_raw(1), &mut inventory, |inv| {
+            inv.move_item(SlotPosition::new(0, 0, 0), SlotPosition::new(0, 0, 1), &database)?;
+            inv.take_item(SlotPosition::new(0, 0, 1))
+        });
+        assert_eq!(result, Err(InventoryError::Vetoed("Nothing can be removed".into())));
+        assert_eq!(inventory, before);
+    }
+
+    #[test]
+    fn failed_modify_changes_nothing() {
+        let database = ItemDatabase::default();
+        let mut inventory = inventory("sword");
+        let before = inventory.clone();
+
+        let rules = InventoryRules::default().with_rule(|_: &InventoryAction| Ok::<(), &'static str>(()));
+        let result = rules.modify(Entity::from_raw(1), &mut inventory, |inv| {
+            inv.move_item(SlotPosition::new(0, 0, 0), SlotPosition::new(0, 0, 1), &database)?;
+            inv.take_item(SlotPosition::new(0, 0, 0))
+        });
+        assert_eq!(result, Err(InventoryError::ItemNotFound));
+        assert_eq!(inventory, before);
+    }
+
+    #[test]
+    fn modify_without_rules_changes_the_inventory_itself() {
+        let database = ItemDatabase::default();
+        let mut inventory = inventory("sword");
+
+        let result = InventoryRules::default().modify(Entity::from_raw(1), &mut inventory, |inv| {
+            inv.move_item(SlotPosition::new(0, 0, 0), SlotPosition::new(0, 0, 1), &database)
+        });
+        assert_eq!(result, Ok(()));
+        assert!(inventory.get_item(SlotPosition::new(0, 0, 1)).is_some());
+
+        let result = InventoryRules::default().modify(Entity::from_raw(1), &mut inventory, |inv| {
+            inv.take_item(SlotPosition::new(0, 0, 0))
+        });
+        assert_eq!(result, Err(InventoryError::ItemNotFound));
+        assert!(inventory.get_item(SlotPosition::new(0, 0, 1)).is_some());
+    }
+
+    #[test]
+    fn vetoed_pair_changes_neither_inventory() {
+        let database = ItemDatabase::default();
+        let (player, chest) = (Entity::from_raw(1), Entity::from_raw(2));
+        let rules = InventoryRules::default().with_rule(move |action: &InventoryAction| match action {
+            InventoryAction::Insert { inventory, .. } if *inventory == chest => Err("The chest is locked"),
+            _ => Ok(()),
+        });
+        let (mut backpack, mut storage) = (inventory("sword"), Inventory::new(1, 1));
+        let before = backpack.clone();
+
+        let pos = SlotPosition::new(0, 0, 0);
+        let result = rules.modify_pair((player, &mut backpack), (chest, &mut storage), |from, to| {
+            from.transfer_item(pos, to, pos, &database)
+        });
+        assert_eq!(result, Err(InventoryError::Vetoed("The chest is locked".into())));
+        assert_eq!(backpack, before);
+        assert!(storage.get_item(pos).is_none());
+    }
+
+    #[test]
+    fn moves_into_containers_are_checked() {
+        let database = ItemDatabase::default();
+        let rules = InventoryRules::default().with_rule(|action: &InventoryAction| match action {
+            InventoryAction::Move { to, .. } if to.container.is_some() => Err("The pouch is sealed"),
+            _ => Ok(()),
+        });
+        let mut inventory = Inventory::new(1, 2);
+        let (pouch, coin) = (SlotPosition::new(0, 0, 0), SlotPosition::new(0, 0, 1));
+        let contents = Inventory::new(1, 1);
+        inventory.insert_item(pouch, InventoryItem::new("pouch", 1).with_contents(contents), &database).unwrap();
+        inventory.insert_item(coin, InventoryItem::new("coin", 1), &database).unwrap();
+        let before = inventory.clone();
+
+        let result = rules.modify(Entity::from_raw(1), &mut inventory, |inv| {
+            inv.move_nested_item(&[], coin, &[pouch], SlotPosition::new(0, 0, 0), &database)
+        });
+        assert_eq!(result, Err(InventoryError::Vetoed("The pouch is sealed".into())));
+        assert_eq!(inventory, before);
+    }
+}
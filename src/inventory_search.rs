@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+
+use crate::inventory_item::InventoryItem;
+use crate::item_database::ItemDatabase;
+use crate::item_query::{ItemQuery, ItemQueryError};
+
+/// The search of the inventory ui, e.g. the text of a search box, as an `ItemQuery`.
+///
+/// While a search is set, the icons of items, that do not match it, are drawn in the dimmed icon color of the
+/// `InventoryStyle`. Text, that is not a valid query, keeps the last valid search.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let mut database = ItemDatabase::default();
+/// database.add_item(ItemDefinition::new("bow", "Bow", "Items/bow.png").with_tag("weapon.ranged.bow"));
+///
+/// let mut search = InventorySearch::default();
+/// assert!(search.matches(&InventoryItem::new("bow", 1), &database));
+///
+/// search.set_text("weapon.melee").unwrap();
+/// assert!(!search.matches(&InventoryItem::new("bow", 1), &database));
+///
+/// assert!(search.set_text("weapon &").is_err());
+/// assert_eq!(search.get_query().unwrap().get_text(), "weapon.melee");
+///
+/// search.set_text("").unwrap();
+/// assert!(search.get_query().is_none());
+/// ```
+#[derive(Resource, Default)]
+pub struct InventorySearch {
+    query: Option<ItemQuery>,
+}
+
+impl InventorySearch {
+    /// Parses `text` and searches for it. Empty text clears the search. Fails and keeps the search, if the text is
+    /// not a valid query.
+    pub fn set_text(&mut self, text: &str) -> Result<(), ItemQueryError> {
+        self.query = match text.trim() {
+            "" => None,
+            text => Some(ItemQuery::parse(text)?),
+        };
+        Ok(())
+    }
+
+    pub fn get_query(&self) -> Option<&ItemQuery> {
+        self.query.as_ref()
+    }
+
+    pub fn set_query(&mut self, value: Option<ItemQuery>) {
+        self.query = value;
+    }
+
+    /// Returns true, if no search is set or `item` matches it.
+    pub fn matches(&self, item: &InventoryItem, database: &ItemDatabase) -> bool {
+        match &self.query {
+            Some(query) => query.matches(item, database),
+            None => true,
+        }
+    }
+}
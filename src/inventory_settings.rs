@@ -0,0 +1,218 @@
+use bevy::prelude::*;
+
+/// Defines what happens to the inventory ui when the inventory is hidden.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InventoryHideMode {
+    /// The ui is despawned when hidden and rebuilt when shown again.
+    #[default]
+    Despawn,
+    /// The ui is kept alive and only made invisible. Hidden nodes do not receive any interaction. Reopening the
+    /// inventory is instant and the scroll position and selection are preserved.
+    Hide,
+}
+
+/// The edge of the window an inventory slides in from.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InventoryTransitionEdge {
+    #[default]
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// The animation played when the inventory is shown or hidden.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum InventoryTransition {
+    /// The inventory pops in and out instantly.
+    #[default]
+    None,
+    /// The inventory fades in and out.
+    Fade,
+    /// The inventory slides in from and out to the given edge of the window.
+    Slide(InventoryTransitionEdge),
+    /// The inventory grows from and shrinks to its center.
+    Scale,
+}
+
+/// Holds the behavioural settings of the inventory.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// use bevy::prelude::KeyCode;
+/// let mut settings = InventorySettings::default();
+/// assert_eq!(settings.get_hide_mode(), InventoryHideMode::Despawn);
+///
+/// settings.set_hide_mode(InventoryHideMode::Hide);
+/// assert_eq!(settings.get_hide_mode(), InventoryHideMode::Hide);
+///
+/// assert_eq!(settings.get_transition(), InventoryTransition::None);
+/// assert!(!settings.is_transition_animated());
+/// settings.set_transition(InventoryTransition::Slide(InventoryTransitionEdge::Bottom));
+/// settings.set_transition_duration(0.5);
+/// assert_eq!(settings.get_transition_duration(), 0.5);
+/// assert!(settings.is_transition_animated());
+///
+/// assert_eq!(settings.get_scroll_line_height(), 20.0);
+/// settings.set_scroll_smoothing(0.0); // Scroll instantly
+///
+/// // Only spawn the visible rows of grids with more than 1000 cells.
+/// settings.set_virtualization_threshold(1000);
+/// assert_eq!(settings.get_virtualization_buffer_rows(), 2);
+///
+/// // Undo with Ctrl+Z and redo with Ctrl+Y by default.
+/// assert_eq!(settings.get_undo_key(), Some(KeyCode::KeyZ));
+/// settings.set_redo_key(None); // Only redo with Ctrl+Shift+Z
+///
+/// assert_eq!(settings.get_error_toast_duration(), 2.0);
+/// ```
+#[derive(Resource)]
+pub struct InventorySettings {
+    hide_mode: InventoryHideMode,
+
+    transition: InventoryTransition,
+    transition_duration: f32, // Seconds
+
+    scroll_line_height: f32, // Pixels scrolled per line of the mouse wheel
+    scroll_smoothing: f32,   // Rate at which the scroll position approaches its target. 0.0 scrolls instantly.
+
+    virtualization_threshold: u64,   // Grids with more cells only spawn their visible rows
+    virtualization_buffer_rows: u64, // Rows spawned above and below the visible rows of a virtualized grid
+
+    undo_key: Option<KeyCode>, // Pressed with Ctrl. Ctrl+Shift redoes.
+    redo_key: Option<KeyCode>, // Pressed with Ctrl
+
+    error_toast_duration: f32, // Seconds an error toast is shown
+}
+
+impl Default for InventorySettings {
+    fn default() -> Self {
+        Self {
+            hide_mode: InventoryHideMode::Despawn,
+
+            transition: InventoryTransition::None,
+            transition_duration: 0.25,
+
+            scroll_line_height: 20.0,
+            scroll_smoothing: 15.0,
+
+            virtualization_threshold: 2048,
+            virtualization_buffer_rows: 2,
+
+            undo_key: Some(KeyCode::KeyZ),
+            redo_key: Some(KeyCode::KeyY),
+
+            error_toast_duration: 2.0,
+        }
+    }
+}
+
+impl InventorySettings {
+    // Getter for `hide_mode`
+    pub fn get_hide_mode(&self) -> InventoryHideMode {
+        self.hide_mode
+    }
+
+    // Setter for `hide_mode`
+    pub fn set_hide_mode(&mut self, value: InventoryHideMode) {
+        self.hide_mode = value;
+    }
+
+    // Getter for `transition`
+    pub fn get_transition(&self) -> InventoryTransition {
+        self.transition
+    }
+
+    // Setter for `transition`
+    pub fn set_transition(&mut self, value: InventoryTransition) {
+        self.transition = value;
+    }
+
+    // Getter for `transition_duration`
+    pub fn get_transition_duration(&self) -> f32 {
+        self.transition_duration
+    }
+
+    // Setter for `transition_duration`
+    pub fn set_transition_duration(&mut self, value: f32) {
+        self.transition_duration = value;
+    }
+
+    /// Returns true, if showing and hiding the inventory is animated. Otherwise the inventory switches between
+    /// `Hidden` and `Shown` directly, without passing through `Opening` or `Closing`.
+    pub fn is_transition_animated(&self) -> bool {
+        self.transition != InventoryTransition::None && self.transition_duration > 0.0
+    }
+
+    // Getter for `scroll_line_height`
+    pub fn get_scroll_line_height(&self) -> f32 {
+        self.scroll_line_height
+    }
+
+    // Setter for `scroll_line_height`
+    pub fn set_scroll_line_height(&mut self, value: f32) {
+        self.scroll_line_height = value;
+    }
+
+    // Getter for `scroll_smoothing`
+    pub fn get_scroll_smoothing(&self) -> f32 {
+        self.scroll_smoothing
+    }
+
+    // Setter for `scroll_smoothing`
+    pub fn set_scroll_smoothing(&mut self, value: f32) {
+        self.scroll_smoothing = value;
+    }
+
+    // Getter for `virtualization_threshold`
+    pub fn get_virtualization_threshold(&self) -> u64 {
+        self.virtualization_threshold
+    }
+
+    // Setter for `virtualization_threshold`
+    pub fn set_virtualization_threshold(&mut self, value: u64) {
+        self.virtualization_threshold = value;
+    }
+
+    // Getter for `virtualization_buffer_rows`
+    pub fn get_virtualization_buffer_rows(&self) -> u64 {
+        self.virtualization_buffer_rows
+    }
+
+    // Setter for `virtualization_buffer_rows`
+    pub fn set_virtualization_buffer_rows(&mut self, value: u64) {
+        self.virtualization_buffer_rows = value;
+    }
+
+    // Getter for `undo_key`
+    pub fn get_undo_key(&self) -> Option<KeyCode> {
+        self.undo_key
+    }
+
+    // Setter for `undo_key`
+    pub fn set_undo_key(&mut self, value: Option<KeyCode>) {
+        self.undo_key = value;
+    }
+
+    // Getter for `redo_key`
+    pub fn get_redo_key(&self) -> Option<KeyCode> {
+        self.redo_key
+    }
+
+    // Setter for `redo_key`
+    pub fn set_redo_key(&mut self, value: Option<KeyCode>) {
+        self.redo_key = value;
+    }
+
+    // Getter for `error_toast_duration`
+    pub fn get_error_toast_duration(&self) -> f32 {
+        self.error_toast_duration
+    }
+
+    // Setter for `error_toast_duration`
+    pub fn set_error_toast_duration(&mut self, value: f32) {
+        self.error_toast_duration = value;
+    }
+}
@@ -1,5 +1,8 @@
 use bevy::prelude::*;
 
+use crate::inventory::SlotPosition;
+use crate::inventory_item::ItemInstanceId;
+
 /// Represents the state of an inventory.
 ///
 /// This struct contains the following fields:
@@ -7,6 +10,12 @@ use bevy::prelude::*;
 /// - `top`: The top position of the inventory.
 /// - `selected_icon`: The currently selected icon in the inventory, if any.
 /// - `hovered_icon`: The icon currently being hovered over in the inventory, if any.
+/// - `transition_progress`: How far the inventory is opened, from 0.0 (hidden) to 1.0 (shown).
+/// - `inventory`: The entity holding the `Inventory` shown in the inventory ui, if any.
+/// - `dragged_item`: The position of the item currently being dragged, if any.
+/// - `dragged_container`: The container window the dragged item is taken from, if it is not the shown inventory.
+/// - `vendor`: The entity holding the `Vendor` shown next to the inventory ui, if any.
+/// - `trade`: The entity holding the `TradeSession` shown next to the inventory ui, if any.
 #[derive(Resource)]
 pub struct InventoryState {
     left: f32,
@@ -15,6 +24,14 @@ pub struct InventoryState {
     hovered_icon: Option<IVec2>,
 
     is_shown : bool,
+    transition_progress: f32,
+
+    inventory: Option<Entity>,
+    dragged_item: Option<SlotPosition>,
+    dragged_container: Option<ItemInstanceId>,
+
+    vendor: Option<Entity>,
+    trade: Option<Entity>,
 }
 
 impl Default for InventoryState {
@@ -48,6 +65,16 @@ impl Default for InventoryState {
     /// assert!(inventory_state.is_shown());
     /// inventory_state.set_is_shown(false);
     /// assert!(!inventory_state.is_shown());
+    ///
+    /// assert_eq!(inventory_state.get_transition_progress(), 0.0);
+    /// inventory_state.set_transition_progress(2.0);
+    /// assert_eq!(inventory_state.get_transition_progress(), 1.0);
+    ///
+    /// assert!(inventory_state.get_inventory().is_none());
+    /// assert!(inventory_state.get_dragged_item().is_none());
+    /// assert!(inventory_state.get_dragged_container().is_none());
+    /// assert!(inventory_state.get_vendor().is_none());
+    /// assert!(inventory_state.get_trade().is_none());
     /// ```
     fn default() -> Self {
         Self {
@@ -58,6 +85,14 @@ impl Default for InventoryState {
             hovered_icon: None,
 
             is_shown : false,
+            transition_progress: 0.0,
+
+            inventory: None,
+            dragged_item: None,
+            dragged_container: None,
+
+            vendor: None,
+            trade: None,
         }
     }
 }
@@ -112,4 +147,64 @@ impl InventoryState {
     pub fn set_is_shown(&mut self, value: bool) {
         self.is_shown = value;
     }
+
+    // Getter for `transition_progress`
+    pub fn get_transition_progress(&self) -> f32 {
+        self.transition_progress
+    }
+
+    // Setter for `transition_progress`. The value is clamped to [0.0, 1.0].
+    pub fn set_transition_progress(&mut self, value: f32) {
+        self.transition_progress = value.clamp(0.0, 1.0);
+    }
+
+    // Getter for `inventory`
+    pub fn get_inventory(&self) -> Option<Entity> {
+        self.inventory
+    }
+
+    // Setter for `inventory`
+    pub fn set_inventory(&mut self, value: Option<Entity>) {
+        self.inventory = value;
+    }
+
+    // Getter for `dragged_item`
+    pub fn get_dragged_item(&self) -> Option<SlotPosition> {
+        self.dragged_item
+    }
+
+    // Setter for `dragged_item`
+    pub fn set_dragged_item(&mut self, value: Option<SlotPosition>) {
+        self.dragged_item = value;
+    }
+
+    // Getter for `dragged_container`
+    pub fn get_dragged_container(&self) -> Option<ItemInstanceId> {
+        self.dragged_container
+    }
+
+    // Setter for `dragged_container`
+    pub fn set_dragged_container(&mut self, value: Option<ItemInstanceId>) {
+        self.dragged_container = value;
+    }
+
+    // Getter for `vendor`
+    pub fn get_vendor(&self) -> Option<Entity> {
+        self.vendor
+    }
+
+    // Setter for `vendor`
+    pub fn set_vendor(&mut self, value: Option<Entity>) {
+        self.vendor = value;
+    }
+
+    // Getter for `trade`
+    pub fn get_trade(&self) -> Option<Entity> {
+        self.trade
+    }
+
+    // Setter for `trade`
+    pub fn set_trade(&mut self, value: Option<Entity>) {
+        self.trade = value;
+    }
 }
@@ -1,6 +1,10 @@
-use bevy::prelude::*;
+use bevy::{prelude::*, utils::HashMap};
 
-#[derive(Resource)]
+use crate::inventory_item::InventoryItem;
+use crate::item_database::ItemDatabase;
+use crate::rarity_tier::RarityTier;
+
+#[derive(Resource, Clone, Debug)]
 pub struct InventoryStyle {
     rows: u64,
     columns: u64,
@@ -15,11 +19,37 @@ pub struct InventoryStyle {
     icon_padding: f32,
     border_width: f32,
 
+    // Nine-slice border of the slot images
+    slice_border: f32,
+
     // Background color
     normal_background_color: Color,
     hover_background_color: Color,
     selected_background_color: Color,
 
+    // Scrollbars
+    scrollbar_width: f32,
+    scrollbar_track_color: Color,
+    scrollbar_thumb_color: Color,
+
+    // Durability bar
+    durability_bar_height: f32,
+    durability_full_color: Color,
+    durability_empty_color: Color,
+    durability_broken_color: Color,
+
+    // Background of context menus and container windows
+    panel_background_color: Color,
+
+    // Text of the error toast
+    error_font_color: Color,
+
+    // Tint of item icons not matching the `InventorySearch`
+    dimmed_icon_color: Color,
+
+    // Look of the slots holding items of a rarity, by rarity id
+    rarity_tiers: HashMap<String, RarityTier>,
+
     // Fonts
     default_font: String, // Path to the font file
     default_font_size: f32,
@@ -52,6 +82,20 @@ impl InventoryStyle {
         self.border_width
     }
 
+    pub fn get_slice_border(&self) -> f32 {
+        self.slice_border
+    }
+
+    /// Builds the `TextureSlicer` used to draw the slot background images.
+    pub fn get_texture_slicer(&self) -> TextureSlicer {
+        TextureSlicer {
+            border: BorderRect::square(self.slice_border),
+            center_scale_mode: SliceScaleMode::Stretch,
+            sides_scale_mode: SliceScaleMode::Stretch,
+            max_corner_scale: 1.0,
+        }
+    }
+
     pub fn get_rows(&self) -> u64 {
         self.rows
     }
@@ -90,6 +134,63 @@ impl InventoryStyle {
         &self.normal_border_color
     }
 
+    pub fn get_scrollbar_width(&self) -> f32 {
+        self.scrollbar_width
+    }
+    pub fn get_scrollbar_track_color(&self) -> &Color {
+        &self.scrollbar_track_color
+    }
+    pub fn get_scrollbar_thumb_color(&self) -> &Color {
+        &self.scrollbar_thumb_color
+    }
+
+    pub fn get_panel_background_color(&self) -> &Color {
+        &self.panel_background_color
+    }
+
+    pub fn get_error_font_color(&self) -> &Color {
+        &self.error_font_color
+    }
+
+    pub fn get_dimmed_icon_color(&self) -> &Color {
+        &self.dimmed_icon_color
+    }
+
+    pub fn get_rarity_tier(&self, rarity: &str) -> Option<&RarityTier> {
+        self.rarity_tiers.get(rarity)
+    }
+
+    /// Returns the tier of the rarity of `item`, if its definition has a rarity with a tier.
+    pub fn get_item_rarity_tier(&self, item: &InventoryItem, database: &ItemDatabase) -> Option<&RarityTier> {
+        let rarity = database.get_item(item.get_item_id())?.get_rarity()?;
+        self.get_rarity_tier(rarity)
+    }
+
+    /// Returns the color, that tooltips and item lists use for the name of `item`. Items without a rarity tier use
+    /// the normal font color.
+    pub fn get_item_title_color(&self, item: &InventoryItem, database: &ItemDatabase) -> Color {
+        self.get_item_rarity_tier(item, database)
+            .map_or(self.default_font_color, |t| *t.get_title_color())
+    }
+
+    pub fn get_durability_bar_height(&self) -> f32 {
+        self.durability_bar_height
+    }
+    pub fn get_durability_broken_color(&self) -> &Color {
+        &self.durability_broken_color
+    }
+
+    /// Returns the color of the durability bar for the given fraction of the maximum durability. The color blends from
+    /// the empty to the full color.
+    pub fn get_durability_color(&self, fraction: f32) -> Color {
+        let full = self.durability_full_color.as_rgba_f32();
+        let empty = self.durability_empty_color.as_rgba_f32();
+        let t = fraction.clamp(0.0, 1.0);
+        let mix = |i: usize| empty[i] + (full[i] - empty[i]) * t;
+
+        Color::rgba(mix(0), mix(1), mix(2), mix(3))
+    }
+
     pub fn get_total_height(&self) -> usize {
         self.get_grid_size() * self.rows as usize
     }
@@ -97,6 +198,155 @@ impl InventoryStyle {
     pub fn get_total_width(&self) -> usize {
         self.get_grid_size() * self.columns as usize
     }
+
+    /// Returns the offset of the icon in row `x` and column `y` from the top left corner of the grid.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_inventory_system::prelude::InventoryStyle;
+    /// let style = InventoryStyle::default();
+    /// let offset = style.get_icon_offset(1, 2);
+    /// assert_eq!(offset.x, 2.0 * 96.0 + 5.0);
+    /// assert_eq!(offset.y, 96.0 + 5.0);
+    /// ```
+    pub fn get_icon_offset(&self, x: usize, y: usize) -> Vec2 {
+        Vec2::new(
+            (self.icon_size * y as f32) + 2.0 * self.icon_margin,
+            (self.icon_size * x as f32) + 2.0 * self.icon_margin,
+        )
+    }
+
+    /// Returns the size of the whole grid including its outer margin.
+    pub fn get_content_size(&self) -> Vec2 {
+        Vec2::new(
+            self.icon_size * self.columns as f32,
+            self.icon_size * self.rows as f32,
+        ) + 4.0 * self.icon_margin
+    }
+
+    /// Sets the dimensions of the grid. When an `Inventory` is shown, they follow the size of its current page.
+    pub fn set_grid_size(&mut self, rows: u64, columns: u64) {
+        self.rows = rows;
+        self.columns = columns;
+    }
+
+    pub fn set_normal_border_color(&mut self, value: Color) {
+        self.normal_border_color = value;
+    }
+    pub fn set_hover_border_color(&mut self, value: Color) {
+        self.hover_border_color = value;
+    }
+    pub fn set_selected_border_color(&mut self, value: Color) {
+        self.selected_border_color = value;
+    }
+
+    pub fn set_normal_background_color(&mut self, value: Color) {
+        self.normal_background_color = value;
+    }
+    pub fn set_hover_background_color(&mut self, value: Color) {
+        self.hover_background_color = value;
+    }
+    pub fn set_selected_background_color(&mut self, value: Color) {
+        self.selected_background_color = value;
+    }
+
+    pub fn set_border_width(&mut self, value: f32) {
+        self.border_width = value;
+    }
+    pub fn set_slice_border(&mut self, value: f32) {
+        self.slice_border = value;
+    }
+
+    pub fn set_scrollbar_width(&mut self, value: f32) {
+        self.scrollbar_width = value;
+    }
+    pub fn set_scrollbar_track_color(&mut self, value: Color) {
+        self.scrollbar_track_color = value;
+    }
+    pub fn set_scrollbar_thumb_color(&mut self, value: Color) {
+        self.scrollbar_thumb_color = value;
+    }
+
+    pub fn set_panel_background_color(&mut self, value: Color) {
+        self.panel_background_color = value;
+    }
+
+    pub fn set_error_font_color(&mut self, value: Color) {
+        self.error_font_color = value;
+    }
+
+    pub fn set_dimmed_icon_color(&mut self, value: Color) {
+        self.dimmed_icon_color = value;
+    }
+
+    /// Sets the look of the slots holding items of `rarity`. An existing tier of the rarity is replaced.
+    pub fn set_rarity_tier(&mut self, rarity: impl Into<String>, tier: RarityTier) {
+        self.rarity_tiers.insert(rarity.into(), tier);
+    }
+
+    /// Replaces all rarity tiers, e.g. with tiers loaded from data, see `RarityTier`.
+    pub fn set_rarity_tiers(&mut self, tiers: HashMap<String, RarityTier>) {
+        self.rarity_tiers = tiers;
+    }
+
+    pub fn get_rarity_tiers(&self) -> &HashMap<String, RarityTier> {
+        &self.rarity_tiers
+    }
+
+    pub fn remove_rarity_tier(&mut self, rarity: &str) {
+        self.rarity_tiers.remove(rarity);
+    }
+
+    pub fn set_durability_bar_height(&mut self, value: f32) {
+        self.durability_bar_height = value;
+    }
+    pub fn set_durability_full_color(&mut self, value: Color) {
+        self.durability_full_color = value;
+    }
+    pub fn set_durability_empty_color(&mut self, value: Color) {
+        self.durability_empty_color = value;
+    }
+    pub fn set_durability_broken_color(&mut self, value: Color) {
+        self.durability_broken_color = value;
+    }
+
+    pub fn set_normal_font(&mut self, value: impl Into<String>) {
+        self.default_font = value.into();
+    }
+    pub fn set_normal_font_size(&mut self, value: f32) {
+        self.default_font_size = value;
+    }
+    pub fn set_normal_font_color(&mut self, value: Color) {
+        self.default_font_color = value;
+    }
+
+    /// Copies the layout related values (grid dimensions, icon size, margin, padding, scrollbar width and positioning)
+    /// from `other`.
+    ///
+    /// Themes only change the look of an inventory. When a theme is applied, the layout of the active style is kept
+    /// so that an open inventory window can be restyled in place.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use bevy_inventory_system::prelude::InventoryStyle;
+    /// let active = InventoryStyle::default();
+    /// let mut theme = InventoryStyle::default();
+    /// theme.set_border_width(4.0);
+    /// theme.copy_layout_from(&active);
+    /// assert_eq!(theme.get_rows(), active.get_rows());
+    /// assert_eq!(theme.get_border_with(), 4.0);
+    /// ```
+    pub fn copy_layout_from(&mut self, other: &InventoryStyle) {
+        self.rows = other.rows;
+        self.columns = other.columns;
+        self.icon_size = other.icon_size;
+        self.icon_margin = other.icon_margin;
+        self.icon_padding = other.icon_padding;
+        self.scrollbar_width = other.scrollbar_width;
+        self.positioning = other.positioning;
+    }
 }
 
 impl Default for InventoryStyle {
@@ -111,9 +361,21 @@ impl Default for InventoryStyle {
     /// - `icon_margin`: 2.5
     /// - `icon_padding`: 2.5
     /// - `border_width`: 2.0
+    /// - `slice_border`: 15.0
     /// - `normal_background_color`: RGBA(1.0, 1.0, 1.0, 0.5) (White)
     /// - `hover_background_color`: RGBA(1.0, 1.0, 1.0, 0.75) (White)
     /// - `selected_background_color`: RGBA(1.0, 1.0, 1.0, 0.75) (White)
+    /// - `scrollbar_width`: 10.0
+    /// - `scrollbar_track_color`: RGBA(0.0, 0.0, 0.0, 0.25)
+    /// - `scrollbar_thumb_color`: GRAY
+    /// - `durability_bar_height`: 4.0
+    /// - `durability_full_color`: RGBA(0.2, 0.8, 0.2, 1.0) (Green)
+    /// - `durability_empty_color`: RGBA(0.9, 0.6, 0.1, 1.0) (Orange)
+    /// - `durability_broken_color`: RED
+    /// - `panel_background_color`: RGBA(0.1, 0.1, 0.1, 0.9)
+    /// - `error_font_color`: RGBA(1.0, 0.35, 0.3, 1.0) (Light red)
+    /// - `dimmed_icon_color`: RGBA(1.0, 1.0, 1.0, 0.25)
+    /// - `rarity_tiers`: none
     /// - `default_font`: "Apocalypse/HUD/Fonts/SairaCondensed/SairaCondensed-Regular.ttf"
     /// - `default_font_size`: 24.0
     /// - `default_font_color`: GRAY
@@ -142,10 +404,29 @@ impl Default for InventoryStyle {
             icon_padding: 2.5,
             border_width: 2.0,
 
+            slice_border: 15.0,
+
             normal_background_color: Color::rgba(1.0, 1.0, 1.0, 0.5), // White
             hover_background_color: Color::rgba(1.0, 1.0, 1.0, 0.75), // White
             selected_background_color: Color::rgba(1.0, 1.0, 1.0, 0.75), // White
 
+            scrollbar_width: 10.0,
+            scrollbar_track_color: Color::rgba(0.0, 0.0, 0.0, 0.25),
+            scrollbar_thumb_color: Color::GRAY,
+
+            durability_bar_height: 4.0,
+            durability_full_color: Color::rgba(0.2, 0.8, 0.2, 1.0), // Green
+            durability_empty_color: Color::rgba(0.9, 0.6, 0.1, 1.0), // Orange
+            durability_broken_color: Color::RED,
+
+            panel_background_color: Color::rgba(0.1, 0.1, 0.1, 0.9),
+
+            error_font_color: Color::rgba(1.0, 0.35, 0.3, 1.0),
+
+            dimmed_icon_color: Color::rgba(1.0, 1.0, 1.0, 0.25),
+
+            rarity_tiers: HashMap::new(),
+
             default_font: "Apocalypse/HUD/Fonts/SairaCondensed/SairaCondensed-Regular.ttf"
                 .to_string(),
             default_font_size: 24.0,
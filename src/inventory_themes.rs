@@ -0,0 +1,82 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{inventory_background_tiles::InventoryBackgroundTiles, inventory_style::InventoryStyle};
+
+/// A named look for the inventory.
+///
+/// A theme consists of an `InventoryStyle` and optionally a set of background tiles. If a theme does not provide
+/// background tiles, the currently active tiles are kept when the theme is applied.
+#[derive(Clone, Debug)]
+pub struct InventoryTheme {
+    style: InventoryStyle,
+    background_tiles: Option<InventoryBackgroundTiles>,
+}
+
+impl InventoryTheme {
+    /// Creates a new theme from a style.
+    pub fn new(style: InventoryStyle) -> Self {
+        Self {
+            style,
+            background_tiles: None,
+        }
+    }
+
+    /// Sets the background tiles used by this theme.
+    pub fn with_background_tiles(mut self, tiles: InventoryBackgroundTiles) -> Self {
+        self.background_tiles = Some(tiles);
+        self
+    }
+
+    pub fn get_style(&self) -> &InventoryStyle {
+        &self.style
+    }
+
+    pub fn get_background_tiles(&self) -> Option<&InventoryBackgroundTiles> {
+        self.background_tiles.as_ref()
+    }
+}
+
+/// Holds all themes registered for the inventory.
+///
+/// Themes are usually registered at startup and activated at runtime by sending a `SetInventoryTheme` event.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// use bevy::prelude::*;
+///
+/// let mut dark = InventoryStyle::default();
+/// dark.set_normal_background_color(Color::rgba(0.1, 0.1, 0.1, 0.8));
+///
+/// let mut themes = InventoryThemes::default();
+/// themes.add_theme("dark", InventoryTheme::new(dark));
+/// assert!(themes.get_theme("dark").is_some());
+/// assert!(themes.get_theme("light").is_none());
+/// assert!(themes.get_current_theme().is_none());
+/// ```
+#[derive(Resource, Default)]
+pub struct InventoryThemes {
+    themes: HashMap<String, InventoryTheme>,
+    current_theme: Option<String>,
+}
+
+impl InventoryThemes {
+    /// Registers a theme under the given name. An existing theme with the same name is replaced.
+    pub fn add_theme(&mut self, name: impl Into<String>, theme: InventoryTheme) {
+        self.themes.insert(name.into(), theme);
+    }
+
+    pub fn get_theme(&self, name: &str) -> Option<&InventoryTheme> {
+        self.themes.get(name)
+    }
+
+    /// Returns the name of the theme that was applied last, if any.
+    pub fn get_current_theme(&self) -> Option<&String> {
+        self.current_theme.as_ref()
+    }
+
+    pub(crate) fn set_current_theme(&mut self, name: Option<String>) {
+        self.current_theme = name;
+    }
+}
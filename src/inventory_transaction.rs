@@ -0,0 +1,403 @@
+use bevy::{prelude::*, utils::{HashMap, HashSet}};
+
+use crate::inventory::{Inventory, ItemLocation, SlotPosition};
+use crate::inventory_error::InventoryError;
+use crate::inventory_item::InventoryItem;
+use crate::inventory_rules::InventoryRules;
+use crate::item_database::ItemDatabase;
+
+/// A single step of an `InventoryTransaction`.
+#[derive(Clone, Debug, PartialEq)]
+pub enum InventoryOperation {
+    /// Puts an item into an empty slot, that accepts it.
+    Insert {
+        inventory: Entity,
+        position: SlotPosition,
+        item: InventoryItem,
+    },
+    /// Puts an item into the first free slot, that accepts it.
+    Add { inventory: Entity, item: InventoryItem },
+    /// Removes `count` items from the stack at `position`.
+    Remove {
+        inventory: Entity,
+        position: SlotPosition,
+        count: u32,
+    },
+    /// Removes `count` items with the given id, taking them from the stacks in page, row and column order.
+    RemoveItems {
+        inventory: Entity,
+        item_id: String,
+        count: u32,
+    },
+    /// Moves the item at `from` to `to`, possibly into another inventory. An item at `to` is swapped.
+    Move {
+        from_inventory: Entity,
+        from: SlotPosition,
+        to_inventory: Entity,
+        to: SlotPosition,
+    },
+}
+
+impl InventoryOperation {
+    /// Returns the inventories the operation touches.
+    fn get_inventories(&self) -> [Entity; 2] {
+        match self {
+            InventoryOperation::Insert { inventory, .. }
+            | InventoryOperation::Add { inventory, .. }
+            | InventoryOperation::Remove { inventory, .. }
+            | InventoryOperation::RemoveItems { inventory, .. } => [*inventory, *inventory],
+            InventoryOperation::Move {
+                from_inventory,
+                to_inventory,
+                ..
+            } => [*from_inventory, *to_inventory],
+        }
+    }
+
+    /// Applies the operation to the working copies. The copies may be changed partially, if it fails.
+    fn apply(
+        &self,
+        inventories: &mut HashMap<Entity, Inventory>,
+        database: &ItemDatabase,
+    ) -> Result<(), InventoryError> {
+        fn get<'a>(
+            inventories: &'a mut HashMap<Entity, Inventory>,
+            entity: &Entity,
+        ) -> Result<&'a mut Inventory, InventoryError> {
+            inventories.get_mut(entity).ok_or(InventoryError::MissingInventory)
+        }
+
+        match self {
+            InventoryOperation::Insert {
+                inventory,
+                position,
+                item,
+            } => get(inventories, inventory)?.insert_item(*position, item.clone(), database),
+            InventoryOperation::Add { inventory, item } => {
+                get(inventories, inventory)?.add_item(item.clone(), database).map(|_| ())
+            }
+            InventoryOperation::Remove {
+                inventory,
+                position,
+                count,
+            } => {
+                let inventory = get(inventories, inventory)?;
+                let item = inventory.get_item_mut(*position).ok_or(InventoryError::ItemNotFound)?;
+
+                match item.get_count() {
+                    c if c < *count || *count == 0 => Err(InventoryError::ItemNotFound),
+                    c if c == *count => inventory.take_item(*position).map(|_| ()),
+                    c => {
+                        item.set_count(c - count);
+                        Ok(())
+                    }
+                }
+            }
+            InventoryOperation::RemoveItems {
+                inventory,
+                item_id,
+                count,
+            } => get(inventories, inventory)?.remove_items(item_id, *count),
+            InventoryOperation::Move {
+                from_inventory,
+                from,
+                to_inventory,
+                to,
+            } => {
+                let item = get(inventories, from_inventory)?.take_item(*from)?;
+                let target = get(inventories, to_inventory)?;
+
+                let previous = target.take_item(*to).ok();
+                target.insert_item(*to, item, database)?;
+
+                match previous {
+                    Some(previous) => get(inventories, from_inventory)?.insert_item(*from, previous, database),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// The inventories changed by a transaction together with the changed slots.
+pub(crate) type ResolvedTransaction = (Vec<(Entity, Inventory)>, Vec<SlotChange>);
+
+/// The change of a single slot made by an `InventoryTransaction`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SlotChange {
+    inventory: Entity,
+    position: SlotPosition,
+    old: Option<InventoryItem>,
+    new: Option<InventoryItem>,
+}
+
+impl SlotChange {
+    pub fn get_inventory(&self) -> Entity {
+        self.inventory
+    }
+
+    pub fn get_position(&self) -> SlotPosition {
+        self.position
+    }
+
+    /// Returns the item in the slot before the transaction.
+    pub fn get_old(&self) -> Option<&InventoryItem> {
+        self.old.as_ref()
+    }
+
+    /// Returns the item in the slot after the transaction.
+    pub fn get_new(&self) -> Option<&InventoryItem> {
+        self.new.as_ref()
+    }
+}
+
+/// Returns the slots of the inventory of `entity`, that differ between `old` and `new`. Both have to have the same
+/// pages.
+pub(crate) fn diff_inventories(entity: Entity, old: &Inventory, new: &Inventory) -> Vec<SlotChange> {
+    let mut changes = Vec::new();
+    for (page, contents) in new.get_pages().iter().enumerate() {
+        for x in 0..contents.get_rows() {
+            for y in 0..contents.get_columns() {
+                let position = SlotPosition::new(page, x, y);
+                let (a, b) = (old.get_item(position), new.get_item(position));
+                if a != b {
+                    changes.push(SlotChange {
+                        inventory: entity,
+                        position,
+                        old: a.cloned(),
+                        new: b.cloned(),
+                    });
+                }
+            }
+        }
+    }
+
+    changes
+}
+
+/// The change of a single item instance between two states of an inventory, see `diff_items`.
+pub(crate) enum ItemChange<'a> {
+    Added(ItemLocation, &'a InventoryItem),
+    Removed(ItemLocation, &'a InventoryItem),
+    /// The item with its old and new location and its old and new state.
+    Changed(ItemLocation, ItemLocation, &'a InventoryItem, &'a InventoryItem),
+}
+
+/// Compares the items of `old` and `new` by their instance id, including the contents of containers. A missing `old`
+/// inventory counts as empty.
+///
+/// Removed items are returned first, in the order of `old`, followed by the added and changed items in the order of
+/// `new`. Items, that kept their location and count, are left out.
+pub(crate) fn diff_items<'a>(old: Option<&'a Inventory>, new: &'a Inventory) -> Vec<ItemChange<'a>> {
+    let old_items = old.map(|o| o.get_nested_items()).unwrap_or_default();
+    let new_items = new.get_nested_items();
+    let old: HashMap<_, _> = old_items.iter().map(|(l, i)| (i.get_instance_id(), (*l, *i))).collect();
+    let new: HashSet<_> = new_items.iter().map(|(_, i)| i.get_instance_id()).collect();
+
+    let mut changes: Vec<_> = old_items
+        .iter()
+        .filter(|(_, item)| !new.contains(&item.get_instance_id()))
+        .map(|(location, item)| ItemChange::Removed(*location, item))
+        .collect();
+
+    for (location, item) in new_items {
+        match old.get(&item.get_instance_id()) {
+            None => changes.push(ItemChange::Added(location, item)),
+            Some((from, before)) if *from != location || before.get_count() != item.get_count() => {
+                changes.push(ItemChange::Changed(*from, location, before, item));
+            }
+            Some(_) => {}
+        }
+    }
+
+    changes
+}
+
+/// A batch of inserts, removals and moves across one or more inventories, that is applied completely or not at all.
+///
+/// The operations are run in order on copies of the inventories. Only if every operation succeeds, the copies replace
+/// the inventories and the changed slots are returned. Sending the transaction in an `ApplyInventoryTransaction`
+/// event applies it to the inventories of the entities and sends a single `InventoryChanged` event.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// use bevy::prelude::*;
+///
+/// let database = ItemDatabase::default();
+/// let rules = InventoryRules::default();
+/// let (player, bench) = (Entity::from_raw(1), Entity::from_raw(2));
+/// let mut backpack = Inventory::new(2, 2);
+/// backpack.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("plank", 3), &database);
+/// backpack.insert_item(SlotPosition::new(0, 0, 1), InventoryItem::new("nail", 2), &database);
+/// let mut workbench = Inventory::new(1, 1);
+///
+/// // Craft a chair: both ingredients are consumed and the chair is put onto the workbench.
+/// let craft = InventoryTransaction::new()
+///     .remove_items(player, "plank", 3)
+///     .remove_items(player, "nail", 2)
+///     .insert(bench, SlotPosition::new(0, 0, 0), InventoryItem::new("chair", 1));
+/// let changes = craft.apply(&rules, &database, [(player, &mut backpack), (bench, &mut workbench)]).unwrap();
+/// assert_eq!(changes.len(), 3);
+/// assert_eq!(workbench.count_item("chair"), 1);
+///
+/// // The workbench is occupied now, so nothing is consumed.
+/// backpack.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("plank", 3), &database);
+/// backpack.insert_item(SlotPosition::new(0, 0, 1), InventoryItem::new("nail", 2), &database);
+/// let before = backpack.clone();
+/// let result = craft.apply(&rules, &database, [(player, &mut backpack), (bench, &mut workbench)]);
+/// assert_eq!(result, Err(InventoryError::Occupied));
+/// assert_eq!(backpack, before);
+///
+/// // The registered rules are checked for every changed inventory.
+/// let rules = rules.with_rule(|action: &InventoryAction| match action {
+///     InventoryAction::Remove { item, .. } if item.get_item_id() == "nail" => Err("The nails are glued"),
+///     _ => Ok(()),
+/// });
+/// workbench.take_item(SlotPosition::new(0, 0, 0)).unwrap();
+/// let result = craft.apply(&rules, &database, [(player, &mut backpack), (bench, &mut workbench)]);
+/// assert_eq!(result, Err(InventoryError::Vetoed("The nails are glued".into())));
+/// assert_eq!(backpack, before);
+///
+/// // Every inventory of the transaction has to be given.
+/// let result = craft.apply(&rules, &database, [(player, &mut backpack)]);
+/// assert_eq!(result, Err(InventoryError::MissingInventory));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct InventoryTransaction {
+    operations: Vec<InventoryOperation>,
+}
+
+impl InventoryTransaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an operation to the end of the transaction.
+    pub fn with_operation(mut self, operation: InventoryOperation) -> Self {
+        self.operations.push(operation);
+        self
+    }
+
+    /// Puts `item` into the empty slot `position` of `inventory`.
+    pub fn insert(self, inventory: Entity, position: SlotPosition, item: InventoryItem) -> Self {
+        self.with_operation(InventoryOperation::Insert {
+            inventory,
+            position,
+            item,
+        })
+    }
+
+    /// Puts `item` into the first free slot of `inventory`.
+    pub fn add(self, inventory: Entity, item: InventoryItem) -> Self {
+        self.with_operation(InventoryOperation::Add { inventory, item })
+    }
+
+    /// Removes `count` items from the stack at `position` of `inventory`.
+    pub fn remove(self, inventory: Entity, position: SlotPosition, count: u32) -> Self {
+        self.with_operation(InventoryOperation::Remove {
+            inventory,
+            position,
+            count,
+        })
+    }
+
+    /// Removes `count` items with the given id from `inventory`.
+    pub fn remove_items(self, inventory: Entity, item_id: impl Into<String>, count: u32) -> Self {
+        self.with_operation(InventoryOperation::RemoveItems {
+            inventory,
+            item_id: item_id.into(),
+            count,
+        })
+    }
+
+    /// Moves the item at `from` of `from_inventory` to `to` of `to_inventory`. An item at `to` is swapped.
+    pub fn move_item(self, from_inventory: Entity, from: SlotPosition, to_inventory: Entity, to: SlotPosition) -> Self {
+        self.with_operation(InventoryOperation::Move {
+            from_inventory,
+            from,
+            to_inventory,
+            to,
+        })
+    }
+
+    pub fn get_operations(&self) -> &[InventoryOperation] {
+        &self.operations
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Returns the inventories the transaction touches, in the order they are first used.
+    pub fn get_inventories(&self) -> Vec<Entity> {
+        let mut entities = Vec::new();
+        for entity in self.operations.iter().flat_map(|o| o.get_inventories()) {
+            if !entities.contains(&entity) {
+                entities.push(entity);
+            }
+        }
+
+        entities
+    }
+
+    /// Runs the operations on copies of the inventories returned by `get`.
+    ///
+    /// Returns the changed inventories and the changed slots. Fails, if an inventory is missing, an operation failed
+    /// or one of the `rules` vetoed a change.
+    pub(crate) fn resolve<'a>(
+        &self,
+        rules: &InventoryRules,
+        database: &ItemDatabase,
+        get: impl Fn(Entity) -> Option<&'a Inventory>,
+    ) -> Result<ResolvedTransaction, InventoryError> {
+        let get = |entity| get(entity).ok_or(InventoryError::MissingInventory);
+        let entities = self.get_inventories();
+        let mut copies = HashMap::new();
+        for &entity in &entities {
+            copies.insert(entity, get(entity)?.clone());
+        }
+
+        for operation in &self.operations {
+            operation.apply(&mut copies, database)?;
+        }
+
+        let mut changed = Vec::new();
+        let mut changes = Vec::new();
+        for entity in entities {
+            let new = copies.remove(&entity).ok_or(InventoryError::MissingInventory)?;
+            let old = get(entity)?;
+            let diff = diff_inventories(entity, old, &new);
+            if !diff.is_empty() {
+                rules.check_change(entity, old, &new)?;
+                changes.extend(diff);
+                changed.push((entity, new));
+            }
+        }
+
+        Ok((changed, changes))
+    }
+
+    /// Applies the transaction to `inventories`, pairs of an entity and its inventory.
+    ///
+    /// Returns the changed slots. Fails and changes nothing, if an inventory is missing, an operation failed or one of
+    /// the `rules` vetoes a change of an inventory. The error is the one of the first failed operation.
+    pub fn apply<'a>(
+        &self,
+        rules: &InventoryRules,
+        database: &ItemDatabase,
+        inventories: impl IntoIterator<Item = (Entity, &'a mut Inventory)>,
+    ) -> Result<Vec<SlotChange>, InventoryError> {
+        let mut inventories: HashMap<Entity, &mut Inventory> = inventories.into_iter().collect();
+        let (changed, changes) = self.resolve(rules, database, |e| inventories.get(&e).map(|i| &**i))?;
+
+        for (entity, inventory) in changed {
+            if let Some(target) = inventories.get_mut(&entity) {
+                **target = inventory;
+            }
+        }
+
+        Ok(changes)
+    }
+}
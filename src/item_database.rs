@@ -0,0 +1,410 @@
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::item_query::tag_matches;
+
+/// What happens to an item, when its durability is used up.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub enum BrokenItemOutcome {
+    /// The item is removed from the inventory.
+    Destroy,
+    /// The item turns into the item with the given id, e.g. a broken sword. Its instance id and data are kept.
+    Replace(String),
+    /// The item stays in its slot, but is unusable until it is repaired: it is marked as broken and slots with a
+    /// filter, like equipment slots, do not accept it, see `Inventory::accepts`.
+    #[default]
+    KeepUnusable,
+}
+
+/// A sprite sheet of item icons, that is cut into a grid of equally sized icons.
+///
+/// Atlases are registered in the `ItemDatabase` under an id. Items show an icon of an atlas with
+/// `ItemDefinition::with_icon_atlas`. The icons are numbered row by row, starting with 0 in the top left corner.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// use bevy::prelude::*;
+///
+/// let atlas = IconAtlas::new("Items/weapons.png", Vec2::new(32.0, 32.0), 8, 4).with_padding(Vec2::splat(2.0));
+/// assert_eq!(atlas.get_icon_count(), 32);
+/// assert_eq!(atlas.to_layout().textures[9].min, Vec2::new(34.0, 34.0));
+///
+/// let mut database = ItemDatabase::default();
+/// database.add_icon_atlas("weapons", atlas);
+/// database.add_item(ItemDefinition::new("sword", "Sword", "Items/sword.png").with_icon_atlas("weapons", 9));
+/// assert_eq!(database.get_item("sword").unwrap().get_icon_atlas(), Some(("weapons", 9)));
+/// assert!(database.get_icon_atlas("weapons").is_some());
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IconAtlas {
+    image: String, // Path to the sprite sheet
+    tile_size: Vec2,
+    columns: usize,
+    rows: usize,
+    #[serde(default)]
+    padding: Option<Vec2>,
+    #[serde(default)]
+    offset: Option<Vec2>,
+}
+
+impl IconAtlas {
+    /// Creates an atlas of `columns` times `rows` icons of `tile_size` pixels.
+    pub fn new(image: impl Into<String>, tile_size: Vec2, columns: usize, rows: usize) -> Self {
+        Self {
+            image: image.into(),
+            tile_size,
+            columns,
+            rows,
+            padding: None,
+            offset: None,
+        }
+    }
+
+    /// Sets the space between two icons in pixels.
+    pub fn with_padding(mut self, padding: Vec2) -> Self {
+        self.padding = Some(padding);
+        self
+    }
+
+    /// Sets the position of the first icon in pixels from the top left corner of the sheet.
+    pub fn with_offset(mut self, offset: Vec2) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    pub fn get_image(&self) -> &str {
+        &self.image
+    }
+
+    pub fn get_tile_size(&self) -> Vec2 {
+        self.tile_size
+    }
+
+    pub fn get_columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn get_rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn get_icon_count(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    /// Returns the layout of the icons on the sheet.
+    pub fn to_layout(&self) -> TextureAtlasLayout {
+        TextureAtlasLayout::from_grid(self.tile_size, self.columns, self.rows, self.padding, self.offset)
+    }
+}
+
+/// Describes a kind of item.
+///
+/// Items stored in an inventory refer to their definition by id.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let pelt = ItemDefinition::new("wolf_pelt", "Wolf Pelt", "Items/wolf_pelt.png")
+///     .with_max_stack(20)
+///     .with_tag("pelt");
+/// assert_eq!(pelt.get_id(), "wolf_pelt");
+/// assert_eq!(pelt.get_max_stack(), 20);
+/// assert!(pelt.has_tag("pelt"));
+///
+/// let sword = ItemDefinition::new("sword", "Sword", "Items/sword.png")
+///     .with_broken_outcome(BrokenItemOutcome::Replace("broken_sword".to_string()))
+///     .with_repair_materials(vec![("iron_ingot".to_string(), 2)]);
+/// assert_eq!(sword.get_repair_materials(), &[("iron_ingot".to_string(), 2)]);
+/// ```
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ItemDefinition {
+    id: String,
+    name: String,
+    icon: String, // Path to the icon image
+    #[serde(default)]
+    icon_atlas: Option<(String, usize)>, // (atlas id, icon index)
+    #[serde(default = "default_max_stack")]
+    max_stack: u32,
+    #[serde(default)]
+    broken_outcome: BrokenItemOutcome,
+    #[serde(default)]
+    repair_materials: Vec<(String, u32)>, // (item id, count)
+    #[serde(default)]
+    weight: f32,
+    #[serde(default)]
+    container_size: Option<(usize, usize)>, // (rows, columns)
+    #[serde(default)]
+    price: Option<(String, u64)>, // (currency, amount)
+    #[serde(default)]
+    rarity: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+fn default_max_stack() -> u32 {
+    1
+}
+
+impl ItemDefinition {
+    /// Creates a new definition of an item that does not stack.
+    pub fn new(id: impl Into<String>, name: impl Into<String>, icon: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            icon: icon.into(),
+            icon_atlas: None,
+            max_stack: 1,
+            broken_outcome: BrokenItemOutcome::default(),
+            repair_materials: Vec::new(),
+            weight: 0.0,
+            container_size: None,
+            price: None,
+            rarity: None,
+            tags: Vec::new(),
+        }
+    }
+
+    /// Shows the icon with the given index of an `IconAtlas` of the database instead of the icon image. The icon
+    /// image is still shown, if the atlas is not registered or has no icon with the index.
+    pub fn with_icon_atlas(mut self, atlas: impl Into<String>, index: usize) -> Self {
+        self.icon_atlas = Some((atlas.into(), index));
+        self
+    }
+
+    /// Sets the number of items that fit into a single slot.
+    pub fn with_max_stack(mut self, max_stack: u32) -> Self {
+        self.max_stack = max_stack.max(1);
+        self
+    }
+
+    /// Sets what happens to instances of this item, when their durability is used up.
+    pub fn with_broken_outcome(mut self, outcome: BrokenItemOutcome) -> Self {
+        self.broken_outcome = outcome;
+        self
+    }
+
+    /// Sets the items, given as pairs of item id and count, that are consumed when an instance is repaired.
+    pub fn with_repair_materials(mut self, materials: Vec<(String, u32)>) -> Self {
+        self.repair_materials = materials;
+        self
+    }
+
+    /// Sets the weight of a single item.
+    pub fn with_weight(mut self, weight: f32) -> Self {
+        self.weight = weight;
+        self
+    }
+
+    /// Turns the item into a container, like a pouch, with an inventory of the given grid size.
+    pub fn with_container(mut self, rows: usize, columns: usize) -> Self {
+        self.container_size = Some((rows, columns));
+        self
+    }
+
+    /// Sets the base price of a single item. Vendors multiply it with their own factors.
+    pub fn with_price(mut self, currency: impl Into<String>, amount: u64) -> Self {
+        self.price = Some((currency.into(), amount));
+        self
+    }
+
+    /// Sets the id of the rarity of the item, e.g. "rare".
+    pub fn with_rarity(mut self, rarity: impl Into<String>) -> Self {
+        self.rarity = Some(rarity.into());
+        self
+    }
+
+    /// Adds a tag, e.g. "pelt". Tags group items for filters and queries. They are hierarchical, with the segments
+    /// separated by dots, e.g. "weapon.ranged.bow".
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    pub fn get_id(&self) -> &str {
+        &self.id
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_icon(&self) -> &str {
+        &self.icon
+    }
+
+    /// Returns the id of the icon atlas and the index of the icon in it, if the item uses an atlas.
+    pub fn get_icon_atlas(&self) -> Option<(&str, usize)> {
+        self.icon_atlas.as_ref().map(|(atlas, index)| (atlas.as_str(), *index))
+    }
+
+    pub fn get_max_stack(&self) -> u32 {
+        self.max_stack
+    }
+
+    pub fn get_broken_outcome(&self) -> &BrokenItemOutcome {
+        &self.broken_outcome
+    }
+
+    pub fn get_repair_materials(&self) -> &[(String, u32)] {
+        &self.repair_materials
+    }
+
+    pub fn get_weight(&self) -> f32 {
+        self.weight
+    }
+
+    /// Returns the rows and columns of the inventory of a container item.
+    pub fn get_container_size(&self) -> Option<(usize, usize)> {
+        self.container_size
+    }
+
+    pub fn is_container(&self) -> bool {
+        self.container_size.is_some()
+    }
+
+    pub fn get_rarity(&self) -> Option<&str> {
+        self.rarity.as_deref()
+    }
+
+    pub fn get_tags(&self) -> &[String] {
+        &self.tags
+    }
+
+    /// Returns true, if a tag of the item matches the pattern `tag`. A pattern matches its own tag and all tags below
+    /// it, a `*` segment matches any segment. So "weapon" and "weapon.*.bow" match "weapon.ranged.bow".
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| tag_matches(tag, t))
+    }
+
+    /// Returns the currency and base price of a single item. Items without a price can not be traded with vendors.
+    pub fn get_price(&self) -> Option<(&str, u64)> {
+        self.price.as_ref().map(|(currency, amount)| (currency.as_str(), *amount))
+    }
+}
+
+/// Holds the definitions of all items known to the game.
+///
+/// The database can be loaded from RON with `ItemDatabase::from_ron`. The items are given as a list, all fields but
+/// the id, name and icon are optional, and a missing rarity order keeps the default one.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let mut database = ItemDatabase::default();
+/// database.add_item(ItemDefinition::new("sword", "Sword", "Items/sword.png"));
+/// assert_eq!(database.get_item("sword").unwrap().get_name(), "Sword");
+/// assert!(database.get_item("shield").is_none());
+///
+/// // Rarities are ranked from common to legendary by default.
+/// assert!(database.get_rarity_rank("epic") > database.get_rarity_rank("rare"));
+/// database.set_rarity_order(vec!["junk".to_string(), "treasure".to_string()]);
+/// assert_eq!(database.get_rarity_rank("treasure"), Some(1));
+/// assert_eq!(database.get_rarity_rank("epic"), None);
+/// ```
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let database = ItemDatabase::from_ron(r#"(
+///     items: [
+///         (id: "sword", name: "Sword", icon: "Items/sword.png", rarity: Some("rare"), tags: ["weapon.sword"]),
+///         (
+///             id: "wolf_pelt",
+///             name: "Wolf Pelt",
+///             icon: "Items/pelts.png",
+///             icon_atlas: Some(("pelts", 3)),
+///             max_stack: 20,
+///             price: Some(("gold", 5)),
+///         ),
+///     ],
+///     icon_atlases: {
+///         "pelts": (image: "Items/pelts.png", tile_size: (32.0, 32.0), columns: 4, rows: 2),
+///     },
+/// )"#).unwrap();
+///
+/// assert_eq!(database.get_item("sword").unwrap().get_max_stack(), 1);
+/// assert!(database.get_item("sword").unwrap().has_tag("weapon"));
+/// assert_eq!(database.get_item("wolf_pelt").unwrap().get_price(), Some(("gold", 5)));
+/// assert_eq!(database.get_icon_atlas("pelts").unwrap().get_icon_count(), 8);
+/// assert_eq!(database.get_rarity_rank("rare"), Some(2));
+/// assert!(ItemDatabase::from_ron("(items: [(id: \"sword\")])").is_err());
+/// ```
+#[derive(Resource, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ItemDatabase {
+    #[serde(serialize_with = "serialize_items", deserialize_with = "deserialize_items")]
+    items: HashMap<String, ItemDefinition>,
+    icon_atlases: HashMap<String, IconAtlas>,
+    rarity_order: Vec<String>, // Rarity ids from the lowest to the highest rank
+}
+
+/// Writes the items of the database as a list, as their ids are part of the definitions.
+fn serialize_items<S: Serializer>(items: &HashMap<String, ItemDefinition>, serializer: S) -> Result<S::Ok, S::Error> {
+    let mut definitions: Vec<&ItemDefinition> = items.values().collect();
+    definitions.sort_by(|a, b| a.id.cmp(&b.id));
+    definitions.serialize(serializer)
+}
+
+/// Reads a list of items and keys them by id. A later definition with the same id replaces an earlier one.
+fn deserialize_items<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HashMap<String, ItemDefinition>, D::Error> {
+    let definitions = Vec::<ItemDefinition>::deserialize(deserializer)?;
+    Ok(definitions.into_iter().map(|d| (d.id.clone(), d)).collect())
+}
+
+impl Default for ItemDatabase {
+    fn default() -> Self {
+        Self {
+            items: HashMap::new(),
+            icon_atlases: HashMap::new(),
+            rarity_order: ["common", "uncommon", "rare", "epic", "legendary"].map(String::from).to_vec(),
+        }
+    }
+}
+
+impl ItemDatabase {
+    /// Loads a database from RON, see the example of `ItemDatabase`.
+    pub fn from_ron(text: &str) -> Result<Self, ron::error::SpannedError> {
+        ron::from_str(text)
+    }
+
+    /// Adds a definition. An existing definition with the same id is replaced.
+    pub fn add_item(&mut self, definition: ItemDefinition) {
+        self.items.insert(definition.id.clone(), definition);
+    }
+
+    pub fn get_item(&self, id: &str) -> Option<&ItemDefinition> {
+        self.items.get(id)
+    }
+
+    /// Registers a sprite sheet of item icons. An existing atlas with the same id is replaced.
+    pub fn add_icon_atlas(&mut self, id: impl Into<String>, atlas: IconAtlas) {
+        self.icon_atlases.insert(id.into(), atlas);
+    }
+
+    pub fn get_icon_atlas(&self, id: &str) -> Option<&IconAtlas> {
+        self.icon_atlases.get(id)
+    }
+
+    /// Returns all registered icon atlases by id.
+    pub fn get_icon_atlases(&self) -> impl Iterator<Item = (&str, &IconAtlas)> {
+        self.icon_atlases.iter().map(|(id, atlas)| (id.as_str(), atlas))
+    }
+
+    /// Sets the ids of the rarities from the lowest to the highest rank. Item queries compare rarities by rank.
+    pub fn set_rarity_order(&mut self, rarities: Vec<String>) {
+        self.rarity_order = rarities;
+    }
+
+    pub fn get_rarity_order(&self) -> &[String] {
+        &self.rarity_order
+    }
+
+    /// Returns the rank of a rarity, or `None`, if it is not part of the rarity order.
+    pub fn get_rarity_rank(&self, rarity: &str) -> Option<usize> {
+        self.rarity_order.iter().position(|r| r == rarity)
+    }
+}
@@ -0,0 +1,53 @@
+use crate::inventory_item::InventoryItem;
+use crate::item_database::ItemDatabase;
+use crate::item_query::{ItemQuery, ItemQueryError};
+
+/// Selects items by their id, by a tag of their `ItemDefinition` or by an `ItemQuery`.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let mut database = ItemDatabase::default();
+/// database.add_item(ItemDefinition::new("wolf_pelt", "Wolf Pelt", "Items/wolf_pelt.png").with_tag("pelt"));
+///
+/// let pelt = InventoryItem::new("wolf_pelt", 3);
+/// assert!(ItemFilter::id("wolf_pelt").matches(&pelt, &database));
+/// assert!(ItemFilter::tag("pelt").matches(&pelt, &database));
+/// assert!(!ItemFilter::tag("weapon").matches(&pelt, &database));
+/// assert!(ItemFilter::query("pelt & !id=bear_pelt").unwrap().matches(&pelt, &database));
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub enum ItemFilter {
+    /// Items with the given item id.
+    Id(String),
+    /// Items, whose definition has a tag matching the given pattern, see `ItemDefinition::has_tag`. Items without a
+    /// definition have no tags.
+    Tag(String),
+    /// Items matching the query.
+    Query(ItemQuery),
+}
+
+impl ItemFilter {
+    pub fn id(item_id: impl Into<String>) -> Self {
+        ItemFilter::Id(item_id.into())
+    }
+
+    pub fn tag(tag: impl Into<String>) -> Self {
+        ItemFilter::Tag(tag.into())
+    }
+
+    /// Parses `text` as an `ItemQuery`.
+    pub fn query(text: &str) -> Result<Self, ItemQueryError> {
+        ItemQuery::parse(text).map(ItemFilter::Query)
+    }
+
+    /// Returns true, if `item` is selected by the filter.
+    pub fn matches(&self, item: &InventoryItem, database: &ItemDatabase) -> bool {
+        match self {
+            ItemFilter::Id(id) => item.get_item_id() == id,
+            ItemFilter::Tag(tag) => database.get_item(item.get_item_id()).is_some_and(|d| d.has_tag(tag)),
+            ItemFilter::Query(query) => query.matches(item, database),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::item_database::{IconAtlas, ItemDatabase, ItemDefinition};
+
+/// The texture atlas layouts of the `IconAtlas`es of the `ItemDatabase`.
+///
+/// The layouts are created by `load_icon_atlases`, whenever the database changed, and are used to show the icons of
+/// items in slots, on the dragged item and in the vendor and trade windows.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// use bevy::prelude::*;
+///
+/// let mut database = ItemDatabase::default();
+/// database.add_icon_atlas("potions", IconAtlas::new("Items/potions.png", Vec2::splat(32.0), 4, 1));
+/// let potion = ItemDefinition::new("potion", "Potion", "Items/potion.png").with_icon_atlas("potions", 2);
+///
+/// let mut layouts = Assets::<TextureAtlasLayout>::default();
+/// let mut icons = ItemIcons::default();
+/// icons.update(&database, &mut layouts);
+///
+/// let (image, atlas) = icons.get_icon(&potion);
+/// assert_eq!(image, "Items/potions.png");
+/// assert_eq!(atlas.unwrap().index, 2);
+///
+/// // Icons outside of the atlas fall back to the icon image.
+/// let elixir = ItemDefinition::new("elixir", "Elixir", "Items/elixir.png").with_icon_atlas("potions", 4);
+/// let (image, atlas) = icons.get_icon(&elixir);
+/// assert_eq!(image, "Items/elixir.png");
+/// assert!(atlas.is_none());
+/// ```
+#[derive(Resource, Default)]
+pub struct ItemIcons {
+    layouts: HashMap<String, (IconAtlas, Handle<TextureAtlasLayout>)>,
+}
+
+impl ItemIcons {
+    /// Creates the layouts of new or changed atlases of `database` and drops the layouts of removed ones.
+    pub fn update(&mut self, database: &ItemDatabase, layouts: &mut Assets<TextureAtlasLayout>) {
+        self.layouts.retain(|id, _| database.get_icon_atlas(id).is_some());
+
+        for (id, atlas) in database.get_icon_atlases() {
+            if self.layouts.get(id).is_some_and(|(known, _)| known == atlas) {
+                continue;
+            }
+
+            let handle = layouts.add(atlas.to_layout());
+            self.layouts.insert(id.to_string(), (atlas.clone(), handle));
+        }
+    }
+
+    /// Returns the path of the image to show for `definition` and, if it is an icon of an atlas, the section of the
+    /// atlas.
+    pub fn get_icon<'a>(&'a self, definition: &'a ItemDefinition) -> (&'a str, Option<TextureAtlas>) {
+        let icon = definition.get_icon_atlas().and_then(|(id, index)| {
+            let (atlas, layout) = self.layouts.get(id)?;
+            (index < atlas.get_icon_count()).then(|| {
+                let section = TextureAtlas {
+                    layout: layout.clone(),
+                    index,
+                };
+                (atlas.get_image(), Some(section))
+            })
+        });
+
+        icon.unwrap_or((definition.get_icon(), None))
+    }
+}
@@ -0,0 +1,299 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::inventory_item::InventoryItem;
+use crate::item_database::{ItemDatabase, ItemDefinition};
+
+/// Returns true, if the hierarchical `tag` matches `pattern`.
+///
+/// Tags are made of segments separated by dots, e.g. `weapon.ranged.bow`. A pattern matches a tag, if its segments
+/// equal the leading segments of the tag, where a `*` segment matches any single segment. So `weapon` and
+/// `weapon.*` both match `weapon.ranged.bow`, but only `weapon` matches `weapon` itself.
+pub(crate) fn tag_matches(pattern: &str, tag: &str) -> bool {
+    let mut tag = tag.split('.');
+    pattern.split('.').all(|p| tag.next().is_some_and(|t| p == "*" || p == t))
+}
+
+/// A comparison of a query term like `rarity>=rare`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    fn compare(self, a: usize, b: usize) -> bool {
+        match self {
+            Comparison::Equal => a == b,
+            Comparison::NotEqual => a != b,
+            Comparison::Less => a < b,
+            Comparison::LessOrEqual => a <= b,
+            Comparison::Greater => a > b,
+            Comparison::GreaterOrEqual => a >= b,
+        }
+    }
+}
+
+/// The parsed form of a query.
+#[derive(Clone, Debug, PartialEq)]
+enum Term {
+    Tag(String),
+    Id(String, bool), // (item id, equal)
+    Rarity(Comparison, String),
+    Not(Box<Term>),
+    And(Vec<Term>),
+    Or(Vec<Term>),
+}
+
+impl Term {
+    fn matches(&self, item: &InventoryItem, definition: Option<&ItemDefinition>, database: &ItemDatabase) -> bool {
+        match self {
+            Term::Tag(pattern) => definition.is_some_and(|d| d.has_tag(pattern)),
+            Term::Id(id, equal) => (item.get_item_id() == id) == *equal,
+            Term::Rarity(comparison, rarity) => {
+                let rank = definition.and_then(|d| d.get_rarity()).and_then(|r| database.get_rarity_rank(r));
+                match (rank, database.get_rarity_rank(rarity)) {
+                    (Some(a), Some(b)) => comparison.compare(a, b),
+                    _ => *comparison == Comparison::NotEqual,
+                }
+            }
+            Term::Not(term) => !term.matches(item, definition, database),
+            Term::And(terms) => terms.iter().all(|t| t.matches(item, definition, database)),
+            Term::Or(terms) => terms.iter().any(|t| t.matches(item, definition, database)),
+        }
+    }
+}
+
+/// The reason a query could not be parsed, together with the byte offset in the query text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ItemQueryError {
+    position: usize,
+    message: &'static str,
+}
+
+impl ItemQueryError {
+    pub fn get_position(&self) -> usize {
+        self.position
+    }
+
+    pub fn get_message(&self) -> &str {
+        self.message
+    }
+}
+
+impl fmt::Display for ItemQueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.message, self.position)
+    }
+}
+
+impl std::error::Error for ItemQueryError {}
+
+/// A recursive descent parser over the query text.
+struct Parser<'a> {
+    text: &'a str,
+    position: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, message: &'static str) -> ItemQueryError {
+        ItemQueryError {
+            position: self.position,
+            message,
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = &self.text[self.position..];
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Consumes `token`, if the text continues with it.
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.text[self.position..].starts_with(token) {
+            self.position += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn word(&mut self) -> Result<&'a str, ItemQueryError> {
+        self.skip_whitespace();
+        let rest = &self.text[self.position..];
+        let length = rest
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | '*')))
+            .unwrap_or(rest.len());
+        if length == 0 {
+            return Err(self.error("Expected a tag or a term"));
+        }
+
+        self.position += length;
+        Ok(&rest[..length])
+    }
+
+    fn or(&mut self) -> Result<Term, ItemQueryError> {
+        let mut terms = vec![self.and()?];
+        while self.eat("|") {
+            terms.push(self.and()?);
+        }
+
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Term::Or(terms) })
+    }
+
+    fn and(&mut self) -> Result<Term, ItemQueryError> {
+        let mut terms = vec![self.unary()?];
+        while self.eat("&") {
+            terms.push(self.unary()?);
+        }
+
+        Ok(if terms.len() == 1 { terms.remove(0) } else { Term::And(terms) })
+    }
+
+    fn unary(&mut self) -> Result<Term, ItemQueryError> {
+        if self.eat("!") {
+            return Ok(Term::Not(Box::new(self.unary()?)));
+        }
+
+        if self.eat("(") {
+            let term = self.or()?;
+            if !self.eat(")") {
+                return Err(self.error("Expected ')'"));
+            }
+            return Ok(term);
+        }
+
+        let start = self.position;
+        let word = self.word()?;
+        let comparison = [
+            ("!=", Comparison::NotEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("<=", Comparison::LessOrEqual),
+            ("=", Comparison::Equal),
+            (">", Comparison::Greater),
+            ("<", Comparison::Less),
+        ]
+        .into_iter()
+        .find(|(token, _)| self.eat(token));
+
+        let Some((_, comparison)) = comparison else {
+            return Ok(Term::Tag(word.to_string()));
+        };
+
+        let value = self.word()?.to_string();
+        match (word, comparison) {
+            ("rarity", _) => Ok(Term::Rarity(comparison, value)),
+            ("id", Comparison::Equal) => Ok(Term::Id(value, true)),
+            ("id", Comparison::NotEqual) => Ok(Term::Id(value, false)),
+            ("id", _) => Err(self.error("Item ids can only be compared with '=' and '!='")),
+            _ => Err(ItemQueryError {
+                position: start,
+                message: "Unknown key, expected 'rarity' or 'id'",
+            }),
+        }
+    }
+}
+
+/// A query matching items by the tags, id and rarity of their definition, e.g. `weapon.* & !cursed & rarity>=rare`.
+///
+/// A query is parsed once and can then be matched against many items. It is made of these terms:
+/// - A tag pattern like `weapon.ranged` or `weapon.*` matches items with a tag below it, see `ItemDefinition::has_tag`.
+/// - `id=arrow` and `id!=arrow` compare the item id.
+/// - `rarity=rare`, `rarity!=rare`, `rarity<rare`, `rarity<=rare`, `rarity>rare` and `rarity>=rare` compare the
+///   rarity by its rank in the rarity order of the `ItemDatabase`. Items without a known rarity only match `!=`.
+/// - `!term` negates a term, `a & b` requires both and `a | b` either term. `&` binds stronger than `|`, parentheses
+///   group terms.
+///
+/// Queries are used by `ItemFilter::Query`, slot filters of an `InventoryPage` and the `InventorySearch`. They are
+/// saved as their text.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let mut database = ItemDatabase::default();
+/// let longbow = ItemDefinition::new("longbow", "Longbow", "Items/longbow.png");
+/// database.add_item(longbow.with_tag("weapon.ranged.bow").with_rarity("epic"));
+/// let hexblade = ItemDefinition::new("hexblade", "Hexblade", "Items/hexblade.png");
+/// database.add_item(hexblade.with_tag("weapon.melee").with_tag("cursed").with_rarity("legendary"));
+/// database.add_item(ItemDefinition::new("club", "Club", "Items/club.png").with_tag("weapon.melee"));
+///
+/// let query: ItemQuery = "weapon.* & !cursed & rarity>=rare".parse().unwrap();
+/// assert!(query.matches(&InventoryItem::new("longbow", 1), &database));
+/// assert!(!query.matches(&InventoryItem::new("hexblade", 1), &database));
+/// assert!(!query.matches(&InventoryItem::new("club", 1), &database));
+///
+/// let query = ItemQuery::parse("weapon.ranged | id=club").unwrap();
+/// assert!(query.matches(&InventoryItem::new("club", 1), &database));
+/// assert_eq!(query.get_text(), "weapon.ranged | id=club");
+///
+/// assert!(ItemQuery::parse("weapon & (cursed").is_err());
+/// assert_eq!(ItemQuery::parse("level>5").unwrap_err().get_position(), 0);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ItemQuery {
+    text: String,
+    term: Term,
+}
+
+impl ItemQuery {
+    /// Parses a query. Fails, if the text is empty or not a valid query.
+    pub fn parse(text: &str) -> Result<Self, ItemQueryError> {
+        let mut parser = Parser { text, position: 0 };
+        let term = parser.or()?;
+
+        parser.skip_whitespace();
+        if parser.position < text.len() {
+            return Err(parser.error("Unexpected character"));
+        }
+
+        Ok(Self {
+            text: text.to_string(),
+            term,
+        })
+    }
+
+    /// Returns the text the query was parsed from.
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    /// Returns true, if `item` matches the query. Terms on tags and rarity never match items without a definition.
+    pub fn matches(&self, item: &InventoryItem, database: &ItemDatabase) -> bool {
+        self.term.matches(item, database.get_item(item.get_item_id()), database)
+    }
+}
+
+impl PartialEq for ItemQuery {
+    fn eq(&self, other: &Self) -> bool {
+        self.term == other.term
+    }
+}
+
+impl FromStr for ItemQuery {
+    type Err = ItemQueryError;
+
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        Self::parse(text)
+    }
+}
+
+impl Serialize for ItemQuery {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.text)
+    }
+}
+
+impl<'de> Deserialize<'de> for ItemQuery {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        Self::parse(&text).map_err(serde::de::Error::custom)
+    }
+}
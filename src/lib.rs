@@ -4,25 +4,110 @@ mod systems {
     pub mod show_hide_system;
     pub mod mouse_scroll_system;
     pub mod reset_icons_system;
+    pub mod theme_system;
+    pub mod transition_system;
+    pub mod scrollbar_system;
+    pub mod keyboard_navigation_system;
+    pub mod virtualization_system;
+    pub mod slot_contents_system;
+    pub mod tab_system;
+    pub mod drag_drop_system;
+    pub mod durability_system;
+    pub mod container_system;
+    pub mod wallet_system;
+    pub mod vendor_system;
+    pub mod trade_system;
+    pub mod transaction_system;
+    pub mod history_system;
+    pub mod toast_system;
+    pub mod change_events_system;
+    pub mod rarity_system;
+    pub mod icon_atlas_system;
 }
 
+mod inventory;
 mod inventory_components;
 mod inventory_background_tiles;
 mod inventory_plugin;
+mod inventory_settings;
 mod inventory_state;
 mod inventory_style;
+mod inventory_themes;
 mod marker;
 mod inventory_events;
+mod inventory_item;
+mod item_database;
 mod states;
+mod wallet;
+mod vendor;
+mod loot_table;
+mod trade;
+mod inventory_transaction;
+mod inventory_history;
+mod inventory_error;
+mod inventory_rules;
+mod item_filter;
+mod item_query;
+mod inventory_search;
+mod inventory_query;
+mod rarity_tier;
+mod item_icons;
 
 pub mod prelude {
     pub use crate::inventory_plugin::InventoryPlugin;
 
+    // Inventory
+    pub use crate::inventory::Inventory;
+    pub use crate::inventory::InventoryPage;
+    pub use crate::inventory::SlotPosition;
+    pub use crate::inventory::ItemLocation;
+    pub use crate::inventory::ResizeReport;
+    pub use crate::inventory_error::InventoryError;
+    pub use crate::inventory_item::{InventoryItem, ItemData, ItemDurability, ItemInstanceId, ItemValue};
+    pub use crate::item_database::{BrokenItemOutcome, IconAtlas, ItemDefinition};
+    pub use crate::wallet::{CurrencyChange, Wallet, WalletTransaction};
+    pub use crate::vendor::{BuybackEntry, Vendor};
+    pub use crate::loot_table::{LootEntry, LootTable, RestockRules, VendorRestock};
+    pub use crate::trade::{TradeSession, TradeSide};
+    pub use crate::inventory_transaction::{InventoryOperation, InventoryTransaction, SlotChange};
+    pub use crate::inventory_history::InventoryHistory;
+    pub use crate::inventory_rules::{InventoryAction, InventoryRule, InventoryRules};
+    pub use crate::item_filter::ItemFilter;
+    pub use crate::item_query::{ItemQuery, ItemQueryError};
+    pub use crate::inventory_query::InventoryQuery;
+
     // Components
     pub use crate::inventory_components::IconComponent;
     pub use crate::marker::UiCameraComponent;
     pub use crate::marker::UiRootComponent;
+    pub use crate::marker::IconLabelComponent;
+    pub use crate::marker::ItemIconComponent;
+    pub use crate::marker::ItemCountComponent;
+    pub use crate::marker::DragGhostComponent;
+    pub use crate::marker::UiViewportComponent;
+    pub use crate::marker::DurabilityBarComponent;
+    pub use crate::marker::ContextMenuComponent;
+    pub use crate::marker::WalletFooterComponent;
+    pub use crate::marker::TradeConfirmComponent;
+    pub use crate::marker::TradeCancelComponent;
+    pub use crate::inventory_components::InventoryTabComponent;
     pub use crate::inventory_components::ScrollPanel;
+    pub use crate::inventory_components::ScrollAxis;
+    pub use crate::inventory_components::ScrollbarComponent;
+    pub use crate::inventory_components::ScrollbarThumbComponent;
+    pub use crate::inventory_components::VirtualGridComponent;
+    pub use crate::inventory_components::VirtualRowComponent;
+    pub use crate::inventory_components::ContextMenuAction;
+    pub use crate::inventory_components::ContextMenuEntryComponent;
+    pub use crate::inventory_components::ContainerWindowComponent;
+    pub use crate::inventory_components::ContainerSlotComponent;
+    pub use crate::inventory_components::ContainerCloseComponent;
+    pub use crate::inventory_components::VendorWindowComponent;
+    pub use crate::inventory_components::VendorSlotComponent;
+    pub use crate::inventory_components::BuybackSlotComponent;
+    pub use crate::inventory_components::TradeWindowComponent;
+    pub use crate::inventory_components::TradeSlotComponent;
+    pub use crate::inventory_components::ErrorToastComponent;
 
     // Events
     pub use crate::inventory_events::InventoryIconClicked;
@@ -30,11 +115,50 @@ pub mod prelude {
     pub use crate::inventory_events::InventoryIconMouseExit;
     pub use crate::inventory_events::ResetInventoryIcons;
     pub use crate::inventory_events::ToggleInventory;
+    pub use crate::inventory_events::SetInventoryTheme;
+    pub use crate::inventory_events::ScrollToSlot;
+    pub use crate::inventory_events::SetInventoryPage;
+    pub use crate::inventory_events::RebuildInventory;
+    pub use crate::inventory_events::ConsumeDurability;
+    pub use crate::inventory_events::RepairItem;
+    pub use crate::inventory_events::ItemBroken;
+    pub use crate::inventory_events::CurrencyChanged;
+    pub use crate::inventory_events::BuyItem;
+    pub use crate::inventory_events::SellItem;
+    pub use crate::inventory_events::BuybackItem;
+    pub use crate::inventory_events::VendorRestocked;
+    pub use crate::inventory_events::OfferTradeItem;
+    pub use crate::inventory_events::WithdrawTradeItem;
+    pub use crate::inventory_events::ConfirmTrade;
+    pub use crate::inventory_events::CancelTrade;
+    pub use crate::inventory_events::TradeFinished;
+    pub use crate::inventory_events::ApplyInventoryTransaction;
+    pub use crate::inventory_events::InventoryChanged;
+    pub use crate::inventory_events::InventoryTransactionFailed;
+    pub use crate::inventory_events::RecordInventoryAction;
+    pub use crate::inventory_events::UndoInventory;
+    pub use crate::inventory_events::RedoInventory;
+    pub use crate::inventory_events::ShowInventoryError;
+    pub use crate::inventory_events::ItemAdded;
+    pub use crate::inventory_events::ItemRemoved;
+    pub use crate::inventory_events::ItemMoved;
+    pub use crate::inventory_events::StackCountChanged;
+    pub use crate::inventory_events::InventoryCleared;
 
     // Resources
-    pub use crate::inventory_background_tiles::InventoryBackgroundTiles;
+    pub use crate::inventory_background_tiles::{BackgroundTilePattern, InventoryBackgroundTiles};
     pub use crate::inventory_state::InventoryState;
+    pub use crate::inventory_search::InventorySearch;
+    pub use crate::inventory_settings::InventorySettings;
+    pub use crate::inventory_settings::InventoryHideMode;
+    pub use crate::inventory_settings::InventoryTransition;
+    pub use crate::inventory_settings::InventoryTransitionEdge;
     pub use crate::inventory_style::InventoryStyle;
+    pub use crate::inventory_themes::InventoryThemes;
+    pub use crate::inventory_themes::InventoryTheme;
+    pub use crate::rarity_tier::RarityTier;
+    pub use crate::item_database::ItemDatabase;
+    pub use crate::item_icons::ItemIcons;
 
     // States
     pub use crate::states::InventoryStates;
@@ -0,0 +1,390 @@
+use bevy::{prelude::*, utils::HashMap};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::inventory::{Inventory, SlotPosition};
+use crate::inventory_item::InventoryItem;
+use crate::item_database::ItemDatabase;
+
+/// An entry of a `LootTable`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LootEntry {
+    item_id: String,
+    weight: u32,
+    min_count: u32,
+    max_count: u32,
+}
+
+impl LootEntry {
+    /// Creates an entry dropping between `min_count` and `max_count` items.
+    pub fn new(item_id: impl Into<String>, weight: u32, min_count: u32, max_count: u32) -> Self {
+        Self {
+            item_id: item_id.into(),
+            weight,
+            min_count: min_count.max(1),
+            max_count: max_count.max(min_count.max(1)),
+        }
+    }
+
+    pub fn get_item_id(&self) -> &str {
+        &self.item_id
+    }
+
+    pub fn get_weight(&self) -> u32 {
+        self.weight
+    }
+
+    pub fn get_min_count(&self) -> u32 {
+        self.min_count
+    }
+
+    pub fn get_max_count(&self) -> u32 {
+        self.max_count
+    }
+}
+
+/// A weighted list of items, e.g. the goods a vendor restocks.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct LootTable {
+    entries: Vec<LootEntry>,
+}
+
+impl LootTable {
+    pub fn new(entries: Vec<LootEntry>) -> Self {
+        Self { entries }
+    }
+
+    pub fn with_entry(mut self, entry: LootEntry) -> Self {
+        self.entries.push(entry);
+        self
+    }
+
+    pub fn get_entries(&self) -> &[LootEntry] {
+        &self.entries
+    }
+
+    /// Picks an entry. The weight of every entry is multiplied with `factor` of the entry. Returns `None`, if all
+    /// weights are zero.
+    pub fn roll(&self, rng: &mut impl Rng, factor: impl Fn(&LootEntry) -> f32) -> Option<&LootEntry> {
+        let weights: Vec<f32> = self.entries.iter().map(|e| e.weight as f32 * factor(e).max(0.0)).collect();
+        let total: f32 = weights.iter().sum();
+        if total <= 0.0 {
+            return None;
+        }
+
+        let mut pick = rng.gen_range(0.0..total);
+        for (entry, weight) in self.entries.iter().zip(weights) {
+            if pick < weight {
+                return Some(entry);
+            }
+            pick -= weight;
+        }
+
+        self.entries.iter().rev().find(|e| e.weight > 0)
+    }
+}
+
+/// Rules for restocking a vendor.
+///
+/// - `interval`: Seconds of game time between two restocks.
+/// - `rolls`: The number of entries picked from the loot table per restock.
+/// - `max_stock`: The maximum number of items of a kind the vendor keeps. Items without an own limit use
+///   `default_max_stock`.
+/// - `rarity_weights`: Factors applied to the weights of entries by the rarity of their items. Items without a rarity
+///   or with an unlisted rarity use a factor of 1.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RestockRules {
+    interval: f32,
+    rolls: u32,
+    default_max_stock: u32,
+    max_stock: HashMap<String, u32>,
+    rarity_weights: HashMap<String, f32>,
+}
+
+impl RestockRules {
+    pub fn new(interval: f32) -> Self {
+        Self {
+            interval: interval.max(0.001),
+            rolls: 3,
+            default_max_stock: 10,
+            max_stock: HashMap::new(),
+            rarity_weights: HashMap::new(),
+        }
+    }
+
+    pub fn with_rolls(mut self, rolls: u32) -> Self {
+        self.rolls = rolls;
+        self
+    }
+
+    pub fn with_default_max_stock(mut self, max: u32) -> Self {
+        self.default_max_stock = max;
+        self
+    }
+
+    pub fn with_max_stock(mut self, item_id: impl Into<String>, max: u32) -> Self {
+        self.max_stock.insert(item_id.into(), max);
+        self
+    }
+
+    pub fn with_rarity_weight(mut self, rarity: impl Into<String>, weight: f32) -> Self {
+        self.rarity_weights.insert(rarity.into(), weight);
+        self
+    }
+
+    pub fn get_interval(&self) -> f32 {
+        self.interval
+    }
+
+    pub fn get_rolls(&self) -> u32 {
+        self.rolls
+    }
+
+    pub fn get_max_stock(&self, item_id: &str) -> u32 {
+        self.max_stock.get(item_id).copied().unwrap_or(self.default_max_stock)
+    }
+
+    pub fn get_rarity_weight(&self, rarity: &str) -> f32 {
+        self.rarity_weights.get(rarity).copied().unwrap_or(1.0)
+    }
+}
+
+/// Restocks the inventory of a vendor from a loot table on a schedule of game time.
+///
+/// Every restock is rolled with a ChaCha8 generator seeded from the seed of the vendor and the number of the restock,
+/// so a vendor with the same seed always restocks the same goods, also across platforms and builds. The seed, the
+/// number of restocks and the time since the last restock are saved together with the loot table and the rules, so a
+/// loaded vendor continues its schedule and restocks the same goods it would have without saving.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let mut database = ItemDatabase::default();
+/// database.add_item(ItemDefinition::new("arrow", "Arrow", "Items/arrow.png").with_max_stack(20));
+/// database.add_item(ItemDefinition::new("bow", "Bow", "Items/bow.png").with_rarity("rare"));
+///
+/// let table = LootTable::default()
+///     .with_entry(LootEntry::new("arrow", 10, 5, 20))
+///     .with_entry(LootEntry::new("bow", 1, 1, 1));
+/// let rules = RestockRules::new(60.0).with_max_stock("bow", 1).with_rarity_weight("rare", 0.5);
+///
+/// let mut first = VendorRestock::new(table.clone(), rules.clone(), 42);
+/// let mut second = VendorRestock::new(table, rules, 42);
+/// let (mut a, mut b) = (Inventory::new(4, 4), Inventory::new(4, 4));
+///
+/// // Nothing happens before the interval elapsed.
+/// assert_eq!(first.tick(30.0, &mut a, &database), 0);
+/// assert_eq!(first.tick(30.0, &mut a, &database), 1);
+/// second.restock(&mut b, &database);
+///
+/// // The same seed restocks the same goods.
+/// assert_eq!(a.count_item("arrow"), b.count_item("arrow"));
+/// assert!(a.count_item("bow") <= 1);
+///
+/// // A loaded schedule continues where the saved one stopped.
+/// first.tick(45.0, &mut a, &database);
+/// let mut loaded: VendorRestock = ron::from_str(&ron::to_string(&first).unwrap()).unwrap();
+/// assert_eq!(loaded.get_restocks(), 1);
+/// assert_eq!(loaded.get_time_left(), 15.0);
+/// let (mut c, mut d) = (a.clone(), a.clone());
+/// loaded.tick(15.0, &mut c, &database);
+/// first.tick(15.0, &mut d, &database);
+/// assert_eq!(c.count_item("arrow"), d.count_item("arrow"));
+/// ```
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
+pub struct VendorRestock {
+    table: LootTable,
+    rules: RestockRules,
+    seed: u64,
+    elapsed: f32,
+    restocks: u64,
+}
+
+impl VendorRestock {
+    pub fn new(table: LootTable, rules: RestockRules, seed: u64) -> Self {
+        Self {
+            table,
+            rules,
+            seed,
+            elapsed: 0.0,
+            restocks: 0,
+        }
+    }
+
+    pub fn get_table(&self) -> &LootTable {
+        &self.table
+    }
+
+    pub fn get_rules(&self) -> &RestockRules {
+        &self.rules
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the number of restocks done so far.
+    pub fn get_restocks(&self) -> u64 {
+        self.restocks
+    }
+
+    /// Returns the seconds of game time until the next restock.
+    pub fn get_time_left(&self) -> f32 {
+        (self.rules.interval - self.elapsed).max(0.0)
+    }
+
+    /// Advances the schedule by `seconds` of game time and restocks `stock` for every elapsed interval. Returns the
+    /// number of restocks done.
+    pub fn tick(&mut self, seconds: f32, stock: &mut Inventory, database: &ItemDatabase) -> u32 {
+        let count = self.advance(seconds);
+        for _ in 0..count {
+            self.restock(stock, database);
+        }
+
+        count
+    }
+
+    /// Advances the schedule by `seconds` of game time without restocking. Returns the number of elapsed intervals,
+    /// i.e. the number of restocks, that are due.
+    pub fn advance(&mut self, seconds: f32) -> u32 {
+        self.elapsed += seconds;
+
+        let mut count = 0;
+        while self.elapsed >= self.rules.interval {
+            self.elapsed -= self.rules.interval;
+            count += 1;
+        }
+
+        count
+    }
+
+    /// Restocks `stock` immediately, without waiting for the schedule.
+    ///
+    /// Picks `rolls` entries from the loot table and adds their items, as long as the maximum stock of the item is not
+    /// reached. The items top up the stacks of the item in stock first, the rest is added as new stacks split by the
+    /// maximum stack size of the item, as long as there are free slots.
+    pub fn restock(&mut self, stock: &mut Inventory, database: &ItemDatabase) {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed ^ self.restocks.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+        self.restocks += 1;
+
+        for _ in 0..self.rules.rolls {
+            let rules = &self.rules;
+            let Some(entry) = self.table.roll(&mut rng, |e| {
+                database
+                    .get_item(e.get_item_id())
+                    .and_then(|d| d.get_rarity())
+                    .map_or(1.0, |r| rules.get_rarity_weight(r))
+            }) else {
+                return;
+            };
+
+            let rolled = rng.gen_range(entry.min_count..=entry.max_count);
+            let room = rules.get_max_stock(&entry.item_id).saturating_sub(stock.count_item(&entry.item_id));
+            let max_stack = database.get_item(&entry.item_id).map_or(1, |d| d.get_max_stack());
+
+            let mut remaining = top_up(stock, &entry.item_id, rolled.min(room), max_stack, database);
+            while remaining > 0 {
+                let count = remaining.min(max_stack);
+                if stock.add_item(InventoryItem::new(entry.item_id.clone(), count), database).is_err() {
+                    return;
+                }
+                remaining -= count;
+            }
+        }
+    }
+}
+
+/// Adds up to `count` items to the stacks of `item_id` in `stock`, that are not full. Items exceeding the maximum
+/// weight of the inventory are dropped. Returns the number of items left, that still have to be added as new stacks.
+fn top_up(stock: &mut Inventory, item_id: &str, mut count: u32, max_stack: u32, database: &ItemDatabase) -> u32 {
+    let fresh = InventoryItem::new(item_id, 0);
+    if let Some(max_weight) = stock.get_max_weight() {
+        let weight = database.get_item(item_id).map_or(0.0, |d| d.get_weight());
+        if weight > 0.0 {
+            let fitting = ((max_weight - stock.get_weight(database)) / weight).floor().max(0.0);
+            count = count.min(fitting as u32);
+        }
+    }
+
+    let stacks: Vec<SlotPosition> = stock
+        .get_pages()
+        .iter()
+        .enumerate()
+        .flat_map(|(page, p)| {
+            p.iter()
+                .filter(|(_, item)| item.can_stack_with(&fresh))
+                .map(move |((x, y), _)| SlotPosition::new(page, x, y))
+        })
+        .collect();
+
+    for pos in stacks {
+        let Some(stack) = stock.get_item_mut(pos) else {
+            continue;
+        };
+        let added = count.min(max_stack.saturating_sub(stack.get_count()));
+        stack.set_count(stack.get_count() + added);
+        count -= added;
+    }
+
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::item_database::ItemDefinition;
+
+    fn database() -> ItemDatabase {
+        let mut database = ItemDatabase::default();
+        database.add_item(ItemDefinition::new("arrow", "Arrow", "Items/arrow.png").with_max_stack(20));
+        database.add_item(ItemDefinition::new("bow", "Bow", "Items/bow.png"));
+        database
+    }
+
+    fn restock(seed: u64) -> VendorRestock {
+        let table = LootTable::default()
+            .with_entry(LootEntry::new("arrow", 3, 5, 15))
+            .with_entry(LootEntry::new("bow", 1, 1, 1));
+        VendorRestock::new(table, RestockRules::new(60.0).with_default_max_stock(50), seed)
+    }
+
+    fn stacks(stock: &Inventory) -> Vec<(SlotPosition, String, u32)> {
+        let page = stock.get_page(0).unwrap();
+        page.iter()
+            .map(|((x, y), item)| (SlotPosition::new(0, x, y), item.get_item_id().to_string(), item.get_count()))
+            .collect()
+    }
+
+    #[test]
+    fn seeded_restock_output_is_fixed() {
+        let database = database();
+        let mut stock = Inventory::new(2, 4);
+        let mut restock = restock(42);
+        let stack = |y, id: &str, count| (SlotPosition::new(0, 0, y), id.to_string(), count);
+
+        restock.restock(&mut stock, &database);
+        assert_eq!(stacks(&stock), vec![stack(0, "arrow", 20), stack(1, "arrow", 7), stack(2, "bow", 1)]);
+
+        // The second restock tops up the arrows to 20 before adding a new stack and stops at the max stock of 50.
+        restock.restock(&mut stock, &database);
+        assert_eq!(
+            stacks(&stock),
+            vec![stack(0, "arrow", 20), stack(1, "arrow", 20), stack(2, "bow", 1), stack(3, "arrow", 10)]
+        );
+    }
+
+    #[test]
+    fn restock_tops_up_existing_stacks() {
+        let database = database();
+        let mut stock = Inventory::new(1, 1);
+        stock.add_item(InventoryItem::new("arrow", 1), &database).unwrap();
+        let table = LootTable::default().with_entry(LootEntry::new("arrow", 1, 5, 5));
+        let mut restock = VendorRestock::new(table, RestockRules::new(60.0).with_rolls(1), 7);
+
+        restock.restock(&mut stock, &database);
+        assert_eq!(stock.count_item("arrow"), 6);
+        assert_eq!(stacks(&stock).len(), 1);
+    }
+}
@@ -7,3 +7,43 @@ pub struct UiRootComponent;
 /// Marks the camera that renders the ui.
 #[derive(Component, Debug, Clone)]
 pub struct UiCameraComponent;
+
+/// Marks the text label of an inventory icon.
+#[derive(Component, Debug, Clone)]
+pub struct IconLabelComponent;
+
+/// Marks the image showing the item stored in an inventory slot.
+#[derive(Component, Debug, Clone)]
+pub struct ItemIconComponent;
+
+/// Marks the text showing the number of items stored in an inventory slot.
+#[derive(Component, Debug, Clone)]
+pub struct ItemCountComponent;
+
+/// Marks the image following the mouse cursor while an item is dragged.
+#[derive(Component, Debug, Clone)]
+pub struct DragGhostComponent;
+
+/// Marks the node that clips the scroll panel of the inventory ui.
+#[derive(Component, Debug, Clone)]
+pub struct UiViewportComponent;
+
+/// Marks the bar showing the durability of the item stored in an inventory slot.
+#[derive(Component, Debug, Clone)]
+pub struct DurabilityBarComponent;
+
+/// Marks the context menu of an inventory slot.
+#[derive(Component, Debug, Clone)]
+pub struct ContextMenuComponent;
+
+/// Marks the text in the footer of the inventory ui listing the balances of the wallet.
+#[derive(Component, Debug, Clone)]
+pub struct WalletFooterComponent;
+
+/// Marks the button of the trade window confirming the offers.
+#[derive(Component, Debug, Clone)]
+pub struct TradeConfirmComponent;
+
+/// Marks the button of the trade window cancelling the trade.
+#[derive(Component, Debug, Clone)]
+pub struct TradeCancelComponent;
@@ -0,0 +1,118 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// The look of the slots holding items of a rarity, e.g. a golden border for legendary items.
+///
+/// Tiers are registered in the `InventoryStyle` under the id of their rarity, see `ItemDefinition::with_rarity`. A
+/// slot shows the border and background color of the tier of its item, while it is neither hovered nor selected. A
+/// tier with a glow pulses its border between the border and the glow color.
+///
+/// Tiers can be loaded from data, e.g. a RON file mapping rarity ids to tiers, and registered with
+/// `InventoryStyle::set_rarity_tiers`. The glow and the title color are optional there.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// use bevy::prelude::*;
+///
+/// let gold = Color::rgba(1.0, 0.65, 0.1, 1.0);
+/// let tier = RarityTier::new(gold, Color::rgba(1.0, 0.8, 0.4, 0.6)).with_glow(Color::WHITE, 0.5);
+///
+/// // Pulsing with half a pulse per second, the border is brightest after one second.
+/// assert_eq!(tier.get_border_color_at(0.0), gold);
+/// assert!(tier.get_border_color_at(1.0).g() > 0.99);
+/// assert!(tier.is_animated());
+/// assert_eq!(tier.get_title_color(), &gold);
+///
+/// let mut style = InventoryStyle::default();
+/// style.set_rarity_tier("legendary", tier);
+/// assert!(style.get_rarity_tier("legendary").is_some());
+/// assert!(style.get_rarity_tier("common").is_none());
+///
+/// // Load the tiers from data.
+/// let tiers: bevy::utils::HashMap<String, RarityTier> = ron::from_str(r#"{
+///     "rare": (
+///         border_color: Rgba(red: 0.2, green: 0.4, blue: 1.0, alpha: 1.0),
+///         background_color: Rgba(red: 0.1, green: 0.2, blue: 0.5, alpha: 0.6),
+///     ),
+/// }"#).unwrap();
+/// style.set_rarity_tiers(tiers);
+/// let rare = style.get_rarity_tier("rare").unwrap();
+/// assert_eq!(rare.get_title_color(), rare.get_border_color());
+/// assert!(style.get_rarity_tier("legendary").is_none());
+/// ```
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct RarityTier {
+    border_color: Color,
+    background_color: Color,
+    #[serde(default)]
+    glow: Option<(Color, f32)>, // (color, pulses per second)
+    #[serde(default)]
+    title_color: Option<Color>, // The border color, if not set
+}
+
+impl RarityTier {
+    /// Creates a tier without a glow. The title color is the border color.
+    pub fn new(border_color: Color, background_color: Color) -> Self {
+        Self {
+            border_color,
+            background_color,
+            glow: None,
+            title_color: None,
+        }
+    }
+
+    /// Lets the border pulse towards `color` `speed` times per second. With a speed of 0 the border glows steadily.
+    pub fn with_glow(mut self, color: Color, speed: f32) -> Self {
+        self.glow = Some((color, speed));
+        self
+    }
+
+    /// Sets the color, that tooltips and item lists use for the names of items of this tier.
+    pub fn with_title_color(mut self, color: Color) -> Self {
+        self.title_color = Some(color);
+        self
+    }
+
+    pub fn get_border_color(&self) -> &Color {
+        &self.border_color
+    }
+
+    pub fn get_background_color(&self) -> &Color {
+        &self.background_color
+    }
+
+    /// Returns the glow color and the pulses per second, if the tier glows.
+    pub fn get_glow(&self) -> Option<(Color, f32)> {
+        self.glow
+    }
+
+    /// Returns the color for the names of items of this tier, which is the border color, unless one was set.
+    pub fn get_title_color(&self) -> &Color {
+        self.title_color.as_ref().unwrap_or(&self.border_color)
+    }
+
+    /// Returns true, if the border color changes over time.
+    pub fn is_animated(&self) -> bool {
+        self.glow.is_some_and(|(_, speed)| speed != 0.0)
+    }
+
+    /// Returns the border color `seconds` after the start of the pulse.
+    pub fn get_border_color_at(&self, seconds: f32) -> Color {
+        let Some((glow, speed)) = self.glow else {
+            return self.border_color;
+        };
+
+        if speed == 0.0 {
+            return glow;
+        }
+
+        let t = (1.0 - (seconds * speed * std::f32::consts::TAU).cos()) / 2.0;
+        let from = self.border_color.as_rgba_f32();
+        let to = glow.as_rgba_f32();
+        let mix = |i: usize| from[i] + (to[i] - from[i]) * t;
+
+        Color::rgba(mix(0), mix(1), mix(2), mix(3))
+    }
+}
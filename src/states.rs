@@ -1,8 +1,14 @@
 use bevy::prelude::*;
 
+/// The states of the inventory ui.
+///
+/// `Opening` and `Closing` are active while the show and hide transitions configured in `InventorySettings` are
+/// running. Game code can use them to block input until a transition has completed.
 #[derive(States, Default, Debug, Clone, PartialEq, Eq, Hash)]
 pub enum InventoryStates {
+    Opening,
     Shown,
+    Closing,
     #[default]
     Hidden,
-}
\ No newline at end of file
+}
@@ -53,7 +53,7 @@ pub fn button_system(
                 *color = inv_style.get_selected_background_color().clone().into();
                 border_color.0 = inv_style.get_selected_border_color().clone();
 
-                let event = InventoryIconClicked::new(v.x as usize, v.y as usize);
+                let event = InventoryIconClicked::new(v.x as usize, v.y as usize).with_page(icon.get_page());
                 clicks.send(event);
 
                 inv_state.set_selected_icon(Some(v));
@@ -79,12 +79,12 @@ pub fn button_system(
                         continue;
                     }
 
-                    let evt = InventoryIconMouseExit::new(v.x as usize, v.y as usize);
+                    let evt = InventoryIconMouseExit::new(v.x as usize, v.y as usize).with_page(icon.get_page());
                     unhovered.send(evt);
                 }
 
                 // Mouse entered
-                let evt2 = InventoryIconMouseEnter::new(v.x as usize, v.y as usize);
+                let evt2 = InventoryIconMouseEnter::new(v.x as usize, v.y as usize).with_page(icon.get_page());
                 hovered.send(evt2);
 
                 inv_state.set_hovered_icon(Some(v));
@@ -97,7 +97,7 @@ pub fn button_system(
                 // Mous exited
                 if let Some(e) = inv_state.get_hovered_icon() {
                     if e.x == v.x && e.y == v.y {
-                        let evt = InventoryIconMouseExit::new(v.x as usize, v.y as usize);
+                        let evt = InventoryIconMouseExit::new(v.x as usize, v.y as usize).with_page(icon.get_page());
                         unhovered.send(evt);
 
                         inv_state.set_hovered_icon(None);
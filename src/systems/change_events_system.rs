@@ -0,0 +1,75 @@
+use bevy::{prelude::*, utils::HashMap};
+use crate::prelude::*;
+use crate::inventory_transaction::{diff_items, ItemChange};
+
+/// Sends `ItemAdded`, `ItemRemoved`, `ItemMoved`, `StackCountChanged` and `InventoryCleared` events for every
+/// inventory, that changed since the last run.
+///
+/// The system keeps a copy of every inventory and compares the items of the changed ones by their instance id with
+/// the copy. It runs in `PostUpdate`, so it sees the changes of all systems in `Update`.
+#[allow(clippy::complexity)]
+pub fn send_item_change_events(
+    mut snapshots: Local<HashMap<Entity, Inventory>>,
+    inventories: Query<(Entity, Ref<Inventory>)>,
+    mut removed_inventories: RemovedComponents<Inventory>,
+    mut added: EventWriter<ItemAdded>,
+    mut removed: EventWriter<ItemRemoved>,
+    mut moved: EventWriter<ItemMoved>,
+    mut counts: EventWriter<StackCountChanged>,
+    mut cleared: EventWriter<InventoryCleared>,
+) {
+    for entity in removed_inventories.read() {
+        if !inventories.contains(entity) {
+            snapshots.remove(&entity);
+        }
+    }
+
+    for (entity, inventory) in &inventories {
+        if !inventory.is_changed() {
+            continue;
+        }
+        let snapshot = snapshots.insert(entity, inventory.clone());
+        if snapshot.as_ref() == Some(&*inventory) {
+            continue;
+        }
+
+        let changes = diff_items(snapshot.as_ref(), &inventory);
+        for change in &changes {
+            match *change {
+                ItemChange::Removed(location, item) => {
+                    removed.send(ItemRemoved::new(
+                        entity,
+                        item.get_item_id(),
+                        item.get_instance_id(),
+                        location,
+                        item.get_count(),
+                    ));
+                }
+                ItemChange::Added(location, item) => {
+                    added.send(ItemAdded::new(
+                        entity,
+                        item.get_item_id(),
+                        item.get_instance_id(),
+                        location,
+                        item.get_count(),
+                    ));
+                }
+                ItemChange::Changed(from, to, before, item) => {
+                    let (id, count) = (item.get_instance_id(), item.get_count());
+                    if from != to {
+                        moved.send(ItemMoved::new(entity, item.get_item_id(), id, from, to, count));
+                    }
+                    let old_count = before.get_count();
+                    if old_count != count {
+                        counts.send(StackCountChanged::new(entity, item.get_item_id(), id, to, old_count, count));
+                    }
+                }
+            }
+        }
+
+        let emptied = changes.iter().any(|c| matches!(c, ItemChange::Removed(..)));
+        if emptied && inventory.get_nested_items().is_empty() {
+            cleared.send(InventoryCleared::new(entity));
+        }
+    }
+}
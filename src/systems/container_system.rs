@@ -0,0 +1,492 @@
+use bevy::{prelude::*, ui::CalculatedClip, utils::HashMap, window::PrimaryWindow};
+use crate::prelude::*;
+use crate::systems::drag_drop_system::contains_cursor;
+use crate::systems::show_hide_system::insert_background_tile;
+use crate::systems::slot_contents_system::show_slot_item;
+
+/// Opens the context menu of a slot, when it is right clicked.
+///
+/// The menu is headed by the name of the item in its title color and offers to open container items, to repair
+/// damaged items of the shown inventory and to sell items to the shown vendor. No menu is opened, if there is nothing
+/// to offer.
+#[allow(clippy::complexity)]
+pub fn open_context_menu(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    inv_state: Res<InventoryState>,
+    inv_style: Res<InventoryStyle>,
+    database: Res<ItemDatabase>,
+    assets: Res<AssetServer>,
+    inventories: Query<&Inventory>,
+    icons: Query<(&IconComponent, &Node, &GlobalTransform, Option<&CalculatedClip>)>,
+    slots: Query<(&ContainerSlotComponent, &Node, &GlobalTransform)>,
+    containers: Query<&ContainerWindowComponent>,
+    roots: Query<(Entity, &Node, &GlobalTransform), With<UiRootComponent>>,
+    menus: Query<Entity, With<ContextMenuComponent>>,
+) {
+    if !mouse.just_pressed(MouseButton::Right) {
+        return;
+    }
+
+    for e in &menus {
+        commands.entity(e).despawn_recursive();
+    }
+
+    let Some(cursor) = windows.get_single().ok().and_then(|w| w.cursor_position()) else {
+        return;
+    };
+
+    let Some(inventory) = inv_state.get_inventory().and_then(|e| inventories.get(e).ok()) else {
+        return;
+    };
+
+    // The clicked slot and the path to the container it belongs to.
+    let clicked = slots
+        .iter()
+        .filter(|(_, node, transform)| contains_cursor(node, transform, None, cursor))
+        .find_map(|(slot, _, _)| {
+            let window = containers.get(slot.get_window()).ok()?;
+            Some((inventory.find_item_path(window.get_container())?, slot.get_position()))
+        })
+        .or_else(|| {
+            icons
+                .iter()
+                .find(|(_, node, transform, clip)| contains_cursor(node, transform, *clip, cursor))
+                .map(|(icon, _, _, _)| {
+                    let v = icon.get_grid_position();
+                    (Vec::new(), SlotPosition::new(icon.get_page(), v.x as usize, v.y as usize))
+                })
+        });
+
+    let Some((path, pos)) = clicked else {
+        return;
+    };
+
+    let Some(item) = inventory.get_container(&path).and_then(|c| c.get_item(pos)) else {
+        return;
+    };
+
+    let definition = database.get_item(item.get_item_id());
+
+    let mut entries = Vec::new();
+    if item.get_contents().is_some() || definition.is_some_and(|d| d.is_container()) {
+        entries.push(("Open", ContextMenuAction::OpenContainer(item.get_instance_id())));
+    }
+    if path.is_empty() && item.get_durability().is_some_and(|d| d.is_damaged()) {
+        entries.push(("Repair", ContextMenuAction::Repair(pos)));
+    }
+    if path.is_empty() && inv_state.get_vendor().is_some() && definition.is_some_and(|d| d.get_price().is_some()) {
+        entries.push(("Sell", ContextMenuAction::Sell(pos)));
+    }
+
+    if entries.is_empty() {
+        return;
+    }
+
+    let Some((root, node, transform)) = roots.iter().next() else {
+        return;
+    };
+
+    let position = cursor - node.logical_rect(transform).min;
+    let font = assets.load(inv_style.get_normal_font().clone());
+    let padding = Val::Px(inv_style.get_icon_padding() * 2.0);
+    let title = definition.map_or(item.get_item_id(), |d| d.get_name());
+    let title_style = TextStyle {
+        font: font.clone(),
+        font_size: inv_style.get_normal_font_size(),
+        color: inv_style.get_item_title_color(item, &database),
+    };
+
+    let menu = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px(position.x),
+                    top: Val::Px(position.y),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(padding),
+                    ..default()
+                },
+                background_color: (*inv_style.get_panel_background_color()).into(),
+                z_index: ZIndex::Global(i32::MAX - 1),
+                ..default()
+            },
+            ContextMenuComponent,
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(title, title_style).with_style(Style {
+                    padding: UiRect::all(padding),
+                    ..default()
+                }),
+                IconLabelComponent,
+            ));
+
+            for (label, action) in entries {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                padding: UiRect::all(padding),
+                                ..default()
+                            },
+                            background_color: Color::NONE.into(),
+                            ..default()
+                        },
+                        ContextMenuEntryComponent::new(action),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(
+                                label,
+                                TextStyle {
+                                    font: font.clone(),
+                                    font_size: inv_style.get_normal_font_size(),
+                                    color: *inv_style.get_normal_font_color(),
+                                },
+                            ),
+                            IconLabelComponent,
+                        ));
+                    });
+            }
+        })
+        .id();
+
+    commands.entity(root).add_child(menu);
+}
+
+/// Executes the action of a pressed context menu entry and closes the menu.
+///
+/// Opening a container, that has no inventory yet, creates an empty inventory with the grid size of its definition.
+/// A container, that is already open, is not opened twice.
+#[allow(clippy::complexity)]
+pub fn context_menu_actions(
+    mut commands: Commands,
+    inv_state: Res<InventoryState>,
+    inv_style: Res<InventoryStyle>,
+    backgrounds: Res<InventoryBackgroundTiles>,
+    database: Res<ItemDatabase>,
+    assets: Res<AssetServer>,
+    mut inventories: Query<(&mut Inventory, Option<&InventoryBackgroundTiles>)>,
+    entries: Query<(&Interaction, &ContextMenuEntryComponent), Changed<Interaction>>,
+    menus: Query<Entity, With<ContextMenuComponent>>,
+    containers: Query<&ContainerWindowComponent>,
+    roots: Query<Entity, With<UiRootComponent>>,
+    mut repairs: EventWriter<RepairItem>,
+    mut sales: EventWriter<SellItem>,
+) {
+    let Some(action) = entries
+        .iter()
+        .find(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, entry)| entry.get_action())
+    else {
+        return;
+    };
+
+    for e in &menus {
+        commands.entity(e).despawn_recursive();
+    }
+
+    let Some(entity) = inv_state.get_inventory() else {
+        return;
+    };
+
+    match action {
+        ContextMenuAction::Repair(pos) => {
+            repairs.send(RepairItem::new(entity, pos));
+        }
+        ContextMenuAction::Sell(pos) => {
+            if let Some(vendor) = inv_state.get_vendor() {
+                sales.send(SellItem::new(vendor, entity, pos));
+            }
+        }
+        ContextMenuAction::OpenContainer(id) => {
+            if containers.iter().any(|w| w.get_container() == id) {
+                return;
+            }
+
+            let (Ok((inventory, _)), Some(root)) = (inventories.get(entity), roots.iter().next()) else {
+                return;
+            };
+
+            let Some(path) = inventory.find_item_path(id) else {
+                return;
+            };
+            let (pos, parent) = path.split_last().unwrap();
+            let Some(item) = inventory.get_container(parent).and_then(|c| c.get_item(*pos)) else {
+                return;
+            };
+
+            // Only an item without contents yet changes the inventory, when its window is opened.
+            let definition = database.get_item(item.get_item_id());
+            let size = definition.and_then(|d| d.get_container_size());
+            if let (None, Some((rows, columns))) = (item.get_contents(), size) {
+                let Ok((mut inventory, _)) = inventories.get_mut(entity) else {
+                    return;
+                };
+                if let Some(item) = inventory.get_container_mut(parent).and_then(|c| c.get_item_mut(*pos)) {
+                    item.get_or_create_contents(rows, columns);
+                }
+            }
+
+            let Ok((inventory, tiles)) = inventories.get(entity) else {
+                return;
+            };
+            let Some(item) = inventory.get_container(parent).and_then(|c| c.get_item(*pos)) else {
+                return;
+            };
+            let title = definition.map_or(item.get_item_id().to_string(), |d| d.get_name().to_string());
+            let title_color = inv_style.get_item_title_color(item, &database);
+
+            if let Some(contents) = item.get_contents() {
+                let index = containers.iter().count();
+                let window = spawn_container_window(
+                    &mut commands,
+                    &inv_style,
+                    tiles.unwrap_or(&backgrounds),
+                    &assets,
+                    id,
+                    (&title, title_color),
+                    contents,
+                    index,
+                );
+                commands.entity(root).add_child(window);
+            }
+        }
+    }
+}
+
+/// Closes the context menu, when the mouse is pressed anywhere else.
+pub fn close_context_menu(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    entries: Query<&Interaction, With<ContextMenuEntryComponent>>,
+    menus: Query<Entity, With<ContextMenuComponent>>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) || entries.iter().any(|i| *i == Interaction::Pressed) {
+        return;
+    }
+
+    for e in &menus {
+        commands.entity(e).despawn_recursive();
+    }
+}
+
+/// Closes a container window, when its close button is pressed.
+pub fn close_container_windows(
+    mut commands: Commands,
+    buttons: Query<(&Interaction, &ContainerCloseComponent), Changed<Interaction>>,
+) {
+    for (interaction, button) in &buttons {
+        if *interaction == Interaction::Pressed {
+            commands.entity(button.get_window()).despawn_recursive();
+        }
+    }
+}
+
+/// Shows the items of the open containers in the slots of their windows.
+///
+/// A window is closed, once its container is no longer part of the shown inventory.
+#[allow(clippy::complexity)]
+pub fn update_container_windows(
+    mut commands: Commands,
+    inv_state: Res<InventoryState>,
+    database: Res<ItemDatabase>,
+    icons: Res<ItemIcons>,
+    assets: Res<AssetServer>,
+    inventories: Query<Ref<Inventory>>,
+    windows: Query<(Entity, &ContainerWindowComponent)>,
+    mut slots: Query<(Ref<ContainerSlotComponent>, &Children, &mut Visibility), Without<ItemIconComponent>>,
+    mut images: Query<(&mut UiImage, &mut Visibility, Option<&mut TextureAtlas>), With<ItemIconComponent>>,
+    mut counts: Query<&mut Text, With<ItemCountComponent>>,
+) {
+    let Some(inventory) = inv_state.get_inventory().and_then(|e| inventories.get(e).ok()) else {
+        return;
+    };
+
+    let force = inventory.is_changed() || database.is_changed() || icons.is_changed();
+
+    let mut paths = HashMap::new();
+    for (e, window) in &windows {
+        match inventory.find_item_path(window.get_container()) {
+            Some(path) => {
+                paths.insert(e, path);
+            }
+            None => commands.entity(e).despawn_recursive(),
+        }
+    }
+
+    for (slot, children, mut slot_visibility) in &mut slots {
+        if !force && !slot.is_added() {
+            continue;
+        }
+
+        let Some(contents) = paths.get(&slot.get_window()).and_then(|p| inventory.get_container(p)) else {
+            continue;
+        };
+
+        let visibility = if contents.is_cell_enabled(slot.get_position()) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *slot_visibility != visibility {
+            *slot_visibility = visibility;
+        }
+
+        let item = contents.get_item(slot.get_position());
+        show_slot_item(&mut commands, item, &database, &icons, &assets, children, &mut images, &mut counts);
+    }
+}
+
+/// Spawns a window showing the current page of the inventory of a container item. The title is shown in the given
+/// color, see `InventoryStyle::get_item_title_color`. The window is placed next to the inventory ui and has to be
+/// added as a child of its root.
+#[allow(clippy::complexity)]
+fn spawn_container_window(
+    commands: &mut Commands,
+    inv_style: &InventoryStyle,
+    backgrounds: &InventoryBackgroundTiles,
+    assets: &AssetServer,
+    container: ItemInstanceId,
+    (title, title_color): (&str, Color),
+    contents: &Inventory,
+    index: usize,
+) -> Entity {
+    let font = assets.load(inv_style.get_normal_font().clone());
+    let text_style = TextStyle {
+        font,
+        font_size: inv_style.get_normal_font_size(),
+        color: *inv_style.get_normal_font_color(),
+    };
+
+    let margin = inv_style.get_icon_margin();
+    let padding = inv_style.get_icon_padding();
+    let size = inv_style.get_icon_size() - (margin + padding);
+
+    let page = contents.get_current_page();
+    let (rows, columns) = contents
+        .get_page(page)
+        .map_or((0, 0), |p| (p.get_rows(), p.get_columns()));
+
+    let window = commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Percent(100.0),
+                    top: Val::Px(index as f32 * inv_style.get_normal_font_size() * 2.0),
+                    margin: UiRect::left(Val::Px(margin * 2.0)),
+                    padding: UiRect::all(Val::Px(padding * 2.0)),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: (*inv_style.get_panel_background_color()).into(),
+                ..default()
+            },
+            ContainerWindowComponent::new(container),
+        ))
+        .id();
+
+    commands.entity(window).with_children(|parent| {
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    justify_content: JustifyContent::SpaceBetween,
+                    margin: UiRect::bottom(Val::Px(margin * 2.0)),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                let title_style = TextStyle {
+                    color: title_color,
+                    ..text_style.clone()
+                };
+                parent.spawn((TextBundle::from_section(title, title_style), IconLabelComponent));
+
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                margin: UiRect::left(Val::Px(margin * 4.0)),
+                                ..default()
+                            },
+                            background_color: Color::NONE.into(),
+                            ..default()
+                        },
+                        ContainerCloseComponent::new(window),
+                    ))
+                    .with_children(|parent| {
+                        parent.spawn((TextBundle::from_section("X", text_style.clone()), IconLabelComponent));
+                    });
+            });
+
+        parent
+            .spawn(NodeBundle {
+                style: Style {
+                    display: Display::Grid,
+                    grid_template_columns: RepeatedGridTrack::px(columns as u16, size),
+                    grid_template_rows: RepeatedGridTrack::px(rows as u16, size),
+                    row_gap: Val::Px(margin),
+                    column_gap: Val::Px(margin),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|parent| {
+                for x in 0..rows {
+                    for y in 0..columns {
+                        let pos = SlotPosition::new(page, x, y);
+                        let mut slot = parent.spawn((
+                            ButtonBundle {
+                                style: Style {
+                                    border: UiRect::all(Val::Px(inv_style.get_border_with())),
+                                    ..default()
+                                },
+                                background_color: (*inv_style.get_normal_background_color()).into(),
+                                border_color: (*inv_style.get_normal_border_color()).into(),
+                                ..default()
+                            },
+                            ContainerSlotComponent::new(window, pos),
+                        ));
+                        insert_background_tile(&mut slot, inv_style, backgrounds, assets, pos, (rows, columns));
+
+                        slot
+                            .with_children(|parent| {
+                                // The item stored in the slot. It is shown by `update_container_windows`.
+                                parent.spawn((
+                                    ImageBundle {
+                                        style: Style {
+                                            position_type: PositionType::Absolute,
+                                            top: Val::Px(padding),
+                                            left: Val::Px(padding),
+                                            right: Val::Px(padding),
+                                            bottom: Val::Px(padding),
+                                            ..default()
+                                        },
+                                        visibility: Visibility::Hidden,
+                                        ..default()
+                                    },
+                                    ItemIconComponent,
+                                ));
+
+                                parent.spawn((
+                                    TextBundle::from_section("", text_style.clone()).with_style(Style {
+                                        position_type: PositionType::Absolute,
+                                        top: Val::Px(padding),
+                                        right: Val::Px(padding * 2.0),
+                                        ..default()
+                                    }),
+                                    ItemCountComponent,
+                                ));
+                            });
+                    }
+                }
+            });
+    });
+
+    window
+}
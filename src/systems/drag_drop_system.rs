@@ -0,0 +1,312 @@
+use bevy::{prelude::*, ui::CalculatedClip, window::PrimaryWindow};
+use crate::prelude::*;
+
+/// Returns true, if the cursor is over the visible part of a node.
+pub(crate) fn contains_cursor(node: &Node, transform: &GlobalTransform, clip: Option<&CalculatedClip>, cursor: Vec2) -> bool {
+    let mut rect = node.logical_rect(transform);
+    if let Some(clip) = clip {
+        rect = rect.intersect(clip.clip);
+    }
+
+    rect.contains(cursor)
+}
+
+/// Starts dragging an item, when its icon in the inventory or in a container window is pressed.
+///
+/// An image of the item follows the mouse cursor while it is dragged.
+#[allow(clippy::complexity)]
+pub fn start_drag(
+    mut commands: Commands,
+    mut inv_state: ResMut<InventoryState>,
+    inv_style: Res<InventoryStyle>,
+    database: Res<ItemDatabase>,
+    item_icons: Res<ItemIcons>,
+    assets: Res<AssetServer>,
+    inventories: Query<&Inventory>,
+    icons: Query<(&Interaction, &IconComponent), Changed<Interaction>>,
+    slots: Query<(&Interaction, &ContainerSlotComponent), Changed<Interaction>>,
+    windows: Query<&ContainerWindowComponent>,
+    roots: Query<Entity, With<UiRootComponent>>,
+) {
+    if inv_state.get_dragged_item().is_some() {
+        return;
+    }
+
+    let Some(inventory) = inv_state.get_inventory().and_then(|e| inventories.get(e).ok()) else {
+        return;
+    };
+
+    // The pressed slot together with the container window it belongs to.
+    let pressed = icons
+        .iter()
+        .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+        .map(|(_, icon)| {
+            let v = icon.get_grid_position();
+            (None, SlotPosition::new(icon.get_page(), v.x as usize, v.y as usize))
+        })
+        .chain(
+            slots
+                .iter()
+                .filter(|(interaction, _)| **interaction == Interaction::Pressed)
+                .filter_map(|(_, slot)| {
+                    let window = windows.get(slot.get_window()).ok()?;
+                    Some((Some(window.get_container()), slot.get_position()))
+                }),
+        )
+        .next();
+
+    let Some((container, pos)) = pressed else {
+        return;
+    };
+
+    let path = match container {
+        Some(id) => inventory.find_item_path(id),
+        None => Some(Vec::new()),
+    };
+    let Some(item) = path.and_then(|p| inventory.get_container(&p)?.get_item(pos)) else {
+        return;
+    };
+
+    inv_state.set_dragged_item(Some(pos));
+    inv_state.set_dragged_container(container);
+
+    let Some(definition) = database.get_item(item.get_item_id()) else {
+        return;
+    };
+
+    let size = Val::Px(inv_style.get_icon_size() * 0.8);
+    let (icon, atlas) = item_icons.get_icon(definition);
+    let mut ghost = commands.spawn((
+        ImageBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                width: size,
+                height: size,
+                ..default()
+            },
+            image: assets.load(icon.to_string()).into(),
+            z_index: ZIndex::Global(i32::MAX),
+            visibility: Visibility::Hidden,
+            ..default()
+        },
+        DragGhostComponent,
+    ));
+    if let Some(atlas) = atlas {
+        ghost.insert(atlas);
+    }
+    let ghost = ghost.id();
+
+    if let Some(root) = roots.iter().next() {
+        commands.entity(root).add_child(ghost);
+    }
+}
+
+/// Moves the image of the dragged item with the mouse cursor.
+pub fn update_drag(
+    windows: Query<&Window, With<PrimaryWindow>>,
+    roots: Query<(&Node, &GlobalTransform), With<UiRootComponent>>,
+    mut ghosts: Query<(&mut Style, &mut Visibility, &Node), With<DragGhostComponent>>,
+) {
+    let Some(cursor) = windows.get_single().ok().and_then(|w| w.cursor_position()) else {
+        return;
+    };
+
+    let Some((root, transform)) = roots.iter().next() else {
+        return;
+    };
+
+    let origin = root.logical_rect(transform).min;
+    for (mut style, mut visibility, node) in &mut ghosts {
+        let position = cursor - origin - node.size() * 0.5;
+        style.left = Val::Px(position.x);
+        style.top = Val::Px(position.y);
+
+        // The ghost is shown, once it has been laid out at the cursor.
+        if node.size() != Vec2::ZERO {
+            *visibility = Visibility::Inherited;
+        }
+    }
+}
+
+/// Drops the dragged item, when the left mouse button is released.
+///
+/// If the item is dropped onto a slot, it is moved there. If the target slot holds a stack of the same item with equal
+/// data, as many items as fit are added to it. Any other item in the target slot is swapped with the dragged item.
+/// If the item is dropped onto the tab of another page, it is moved to the first free slot of that page. The slot
+/// filters of the target slots have to accept the items.
+///
+/// Items can be dropped into and taken out of the slots of container windows. Between different containers, items are
+/// only moved into empty slots and a container can not be put inside itself. Items of the shown inventory dropped onto
+/// the vendor window are sold to the vendor, items dropped onto the trade window are offered in the trade.
+///
+/// Moves are checked by the `InventoryRules`. Moves inside the shown inventory are recorded as actions, that can be
+/// undone. A failed or vetoed move is shown in an error toast.
+#[allow(clippy::complexity)]
+pub fn end_drag(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut inv_state: ResMut<InventoryState>,
+    (database, rules): (Res<ItemDatabase>, Res<InventoryRules>),
+    mut inventories: Query<&mut Inventory>,
+    icons: Query<(&IconComponent, &Node, &GlobalTransform, Option<&CalculatedClip>)>,
+    slots: Query<(&ContainerSlotComponent, &Node, &GlobalTransform)>,
+    containers: Query<&ContainerWindowComponent>,
+    tabs: Query<(&InventoryTabComponent, &Node, &GlobalTransform)>,
+    trading: Query<
+        (Option<&VendorWindowComponent>, Option<&TradeWindowComponent>, &Node, &GlobalTransform),
+        Or<(With<VendorWindowComponent>, With<TradeWindowComponent>)>,
+    >,
+    ghosts: Query<Entity, With<DragGhostComponent>>,
+    mut reset: EventWriter<ResetInventoryIcons>,
+    mut sales: EventWriter<SellItem>,
+    mut offers: EventWriter<OfferTradeItem>,
+    (mut actions, mut errors): (EventWriter<RecordInventoryAction>, EventWriter<ShowInventoryError>),
+) {
+    if !mouse.just_released(MouseButton::Left) {
+        return;
+    }
+
+    let Some(from) = inv_state.get_dragged_item() else {
+        return;
+    };
+    let source = inv_state.get_dragged_container();
+    inv_state.set_dragged_item(None);
+    inv_state.set_dragged_container(None);
+
+    for e in &ghosts {
+        commands.entity(e).despawn_recursive();
+    }
+
+    let Some(cursor) = windows.get_single().ok().and_then(|w| w.cursor_position()) else {
+        return;
+    };
+
+    let Some(entity) = inv_state.get_inventory() else {
+        return;
+    };
+    let Ok(mut inventory) = inventories.get_mut(entity) else {
+        return;
+    };
+
+    let source_path = match source {
+        Some(id) => inventory.find_item_path(id),
+        None => Some(Vec::new()),
+    };
+    let Some(source_path) = source_path else {
+        return;
+    };
+
+    for (slot, node, transform) in &slots {
+        if !contains_cursor(node, transform, None, cursor) {
+            continue;
+        }
+
+        let Some(target_path) =
+            containers.get(slot.get_window()).ok().and_then(|w| inventory.find_item_path(w.get_container()))
+        else {
+            return;
+        };
+
+        let to = slot.get_position();
+        let dropped = rules.modify(entity, &mut inventory, |inv| {
+            drop_item(inv, &database, &source_path, from, &target_path, to)
+        });
+        match dropped {
+            Ok(()) => {
+                actions.send(RecordInventoryAction::new(entity));
+            }
+            Err(InventoryError::SameSlot) => {}
+            Err(error) => {
+                errors.send(ShowInventoryError::new(error));
+            }
+        }
+        return;
+    }
+
+    for (icon, node, transform, clip) in &icons {
+        if !contains_cursor(node, transform, clip, cursor) {
+            continue;
+        }
+
+        let v = icon.get_grid_position();
+        let to = SlotPosition::new(icon.get_page(), v.x as usize, v.y as usize);
+        match rules.modify(entity, &mut inventory, |inv| drop_item(inv, &database, &source_path, from, &[], to)) {
+            Ok(()) => {
+                actions.send(RecordInventoryAction::new(entity));
+                inv_state.set_selected_icon(Some(v));
+                reset.send(ResetInventoryIcons);
+            }
+            Err(InventoryError::SameSlot) => {}
+            Err(error) => {
+                errors.send(ShowInventoryError::new(error));
+            }
+        }
+        return;
+    }
+
+    if !source_path.is_empty() {
+        return;
+    }
+
+    for (vendor, trade, node, transform) in &trading {
+        if !contains_cursor(node, transform, None, cursor) {
+            continue;
+        }
+
+        if let Some(window) = vendor {
+            sales.send(SellItem::new(window.get_vendor(), entity, from));
+        } else if let Some(window) = trade {
+            offers.send(OfferTradeItem::new(window.get_session(), entity, from));
+        }
+        return;
+    }
+
+    for (tab, node, transform) in &tabs {
+        if contains_cursor(node, transform, None, cursor) {
+            let page = tab.get_page();
+            let moved = rules.modify(entity, &mut inventory, |inv| inv.move_item_to_page(from, page, &database));
+            match moved {
+                Ok(to) if to != from => {
+                    actions.send(RecordInventoryAction::new(entity));
+                }
+                Ok(_) => {}
+                Err(error) => {
+                    errors.send(ShowInventoryError::new(error));
+                }
+            }
+            return;
+        }
+    }
+}
+
+/// Moves the item at `from` inside the container at `source_path` onto the slot `to` inside the container at
+/// `target_path`. Fails, if the item could not be moved or a slot filter does not accept it.
+fn drop_item(
+    inventory: &mut Inventory,
+    database: &ItemDatabase,
+    source_path: &[SlotPosition],
+    from: SlotPosition,
+    target_path: &[SlotPosition],
+    to: SlotPosition,
+) -> Result<(), InventoryError> {
+    if source_path != target_path {
+        return inventory.move_nested_item(source_path, from, target_path, to, database);
+    }
+
+    let container = inventory.get_container_mut(target_path).ok_or(InventoryError::OutOfBounds)?;
+    let stacks = match (container.get_item(from), container.get_item(to)) {
+        (Some(a), Some(b)) => a.can_stack_with(b),
+        _ => false,
+    };
+
+    if stacks {
+        let max_stack = container
+            .get_item(to)
+            .and_then(|item| database.get_item(item.get_item_id()))
+            .map_or(1, |d| d.get_max_stack());
+        container.merge_stacks(from, to, max_stack).or_else(|_| container.move_item(from, to, database))
+    } else {
+        container.move_item(from, to, database)
+    }
+}
@@ -0,0 +1,67 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+
+/// Applies `ConsumeDurability` and `RepairItem` events to the targeted inventories.
+///
+/// When an item breaks, the outcome configured in its `ItemDefinition` is applied and an `ItemBroken` event is sent.
+/// Items without a definition, or whose outcome is vetoed by the `InventoryRules`, are kept unusable. Repairs check the
+/// `InventoryRules` for the consumed materials. A failed repair of an item of the shown inventory is shown in an error
+/// toast.
+#[allow(clippy::complexity)]
+pub fn apply_durability_events(
+    mut consume_events: EventReader<ConsumeDurability>,
+    mut repair_events: EventReader<RepairItem>,
+    inv_state: Res<InventoryState>,
+    database: Res<ItemDatabase>,
+    rules: Res<InventoryRules>,
+    mut inventories: Query<&mut Inventory>,
+    mut broken: EventWriter<ItemBroken>,
+    mut errors: EventWriter<ShowInventoryError>,
+) {
+    for event in consume_events.read() {
+        let Ok(mut inventory) = inventories.get_mut(event.inventory()) else {
+            continue;
+        };
+        let pos = event.position();
+
+        if inventory.consume_durability(pos, event.amount()) != Ok(true) {
+            continue;
+        }
+
+        let mut outcome = inventory
+            .get_item(pos)
+            .and_then(|item| database.get_item(item.get_item_id()))
+            .map(|definition| definition.get_broken_outcome().clone())
+            .unwrap_or_default();
+
+        let mut result = rules.modify(event.inventory(), &mut inventory, |inv| inv.break_item(pos, &outcome));
+        if matches!(result, Err(InventoryError::Vetoed(_))) {
+            outcome = BrokenItemOutcome::KeepUnusable;
+            result = rules.modify(event.inventory(), &mut inventory, |inv| inv.break_item(pos, &outcome));
+        }
+
+        if let Ok(item) = result {
+            broken.send(ItemBroken::new(event.inventory(), pos, item, outcome));
+        }
+    }
+
+    for event in repair_events.read() {
+        let Ok(mut inventory) = inventories.get_mut(event.inventory()) else {
+            continue;
+        };
+        let pos = event.position();
+
+        let materials = inventory
+            .get_item(pos)
+            .and_then(|item| database.get_item(item.get_item_id()))
+            .map(|definition| definition.get_repair_materials().to_vec())
+            .unwrap_or_default();
+
+        let repaired = rules.modify(event.inventory(), &mut inventory, |inv| inv.repair_item(pos, &materials));
+        if let Err(error) = repaired {
+            if inv_state.get_inventory() == Some(event.inventory()) {
+                errors.send(ShowInventoryError::new(error));
+            }
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+
+/// Sends `UndoInventory` and `RedoInventory` events for the shown inventory, when the keys set in `InventorySettings`
+/// are pressed together with Ctrl. Ctrl+Shift with the undo key redoes as well.
+pub fn history_keys(
+    keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<InventorySettings>,
+    inv_state: Res<InventoryState>,
+    mut undo_events: EventWriter<UndoInventory>,
+    mut redo_events: EventWriter<RedoInventory>,
+) {
+    let Some(inventory) = inv_state.get_inventory() else {
+        return;
+    };
+    if inv_state.get_dragged_item().is_some() || !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+
+    let shift = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let undo = settings.get_undo_key().is_some_and(|k| keys.just_pressed(k));
+    let redo = settings.get_redo_key().is_some_and(|k| keys.just_pressed(k));
+
+    if undo && !shift {
+        undo_events.send(UndoInventory::new(inventory));
+    } else if redo || undo {
+        redo_events.send(RedoInventory::new(inventory));
+    }
+}
+
+/// Applies `UndoInventory` and `RedoInventory` events.
+///
+/// An action vetoed by the `InventoryRules` is not undone or redone and stays in the history. The veto is shown in an
+/// error toast, if it concerns the shown inventory.
+#[allow(clippy::complexity)]
+pub fn apply_history_events(
+    mut undo_events: EventReader<UndoInventory>,
+    mut redo_events: EventReader<RedoInventory>,
+    inv_state: Res<InventoryState>,
+    rules: Res<InventoryRules>,
+    mut histories: Query<(&mut Inventory, &mut InventoryHistory)>,
+    mut errors: EventWriter<ShowInventoryError>,
+) {
+    let undos = undo_events.read().map(|e| (e.inventory(), true));
+    let redos = redo_events.read().map(|e| (e.inventory(), false));
+
+    for (entity, undo) in undos.chain(redos) {
+        let Ok((mut inventory, mut history)) = histories.get_mut(entity) else {
+            continue;
+        };
+
+        // Undo on copies, so a vetoed action stays in the history.
+        let (mut new_inventory, mut new_history) = (inventory.clone(), history.clone());
+        let done = if undo {
+            new_history.undo(&mut new_inventory)
+        } else {
+            new_history.redo(&mut new_inventory)
+        };
+
+        if !done {
+            *history = new_history;
+            continue;
+        }
+
+        match rules.check_change(entity, &inventory, &new_inventory) {
+            Ok(()) => {
+                *inventory = new_inventory;
+                *history = new_history;
+            }
+            Err(error) if inv_state.get_inventory() == Some(entity) => {
+                errors.send(ShowInventoryError::new(error));
+            }
+            Err(_) => {}
+        }
+    }
+}
+
+/// Records the changes of inventories with an `InventoryHistory`. Changes marked by a `RecordInventoryAction` event
+/// are recorded as actions, any other change clears the history.
+pub fn record_inventory_history(
+    mut actions: EventReader<RecordInventoryAction>,
+    mut histories: Query<(Entity, Ref<Inventory>, &mut InventoryHistory)>,
+) {
+    let actions: Vec<Entity> = actions.read().map(|a| a.inventory()).collect();
+
+    for (entity, inventory, mut history) in &mut histories {
+        if inventory.is_changed() || history.is_added() {
+            history.observe(&inventory, actions.contains(&entity));
+        }
+    }
+}
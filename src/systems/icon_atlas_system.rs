@@ -0,0 +1,13 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+
+/// Creates the texture atlas layouts of the icon atlases of the `ItemDatabase`, whenever the database changed.
+pub fn load_icon_atlases(
+    database: Res<ItemDatabase>,
+    mut icons: ResMut<ItemIcons>,
+    mut layouts: ResMut<Assets<TextureAtlasLayout>>,
+) {
+    if database.is_changed() {
+        icons.update(&database, &mut layouts);
+    }
+}
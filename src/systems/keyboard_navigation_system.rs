@@ -0,0 +1,75 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+
+/// Moves the selection through the inventory with the arrow keys.
+///
+/// If nothing is selected, the first icon gets selected. Disabled cells of the shown inventory are skipped. The newly
+/// selected icon is scrolled into view by sending a `ScrollToSlot` event.
+pub fn keyboard_navigation(
+    keys: Res<ButtonInput<KeyCode>>,
+    inv_style: Res<InventoryStyle>,
+    mut inv_state: ResMut<InventoryState>,
+    inventories: Query<&Inventory>,
+    mut scroll: EventWriter<ScrollToSlot>,
+    mut reset: EventWriter<ResetInventoryIcons>,
+) {
+    // x is the row and y the column of an icon.
+    let step = if keys.just_pressed(KeyCode::ArrowUp) {
+        IVec2::new(-1, 0)
+    } else if keys.just_pressed(KeyCode::ArrowDown) {
+        IVec2::new(1, 0)
+    } else if keys.just_pressed(KeyCode::ArrowLeft) {
+        IVec2::new(0, -1)
+    } else if keys.just_pressed(KeyCode::ArrowRight) {
+        IVec2::new(0, 1)
+    } else {
+        return;
+    };
+
+    let last = IVec2::new(inv_style.get_rows() as i32 - 1, inv_style.get_columns() as i32 - 1);
+    if last.x < 0 || last.y < 0 {
+        return;
+    }
+
+    let inventory = inv_state.get_inventory().and_then(|e| inventories.get(e).ok());
+    let is_enabled = |v: IVec2| match inventory {
+        Some(inventory) => {
+            let pos = SlotPosition::new(inventory.get_current_page(), v.x as usize, v.y as usize);
+            inventory.is_cell_enabled(pos)
+        }
+        None => true,
+    };
+
+    let next = match inv_state.get_selected_icon() {
+        // Walk into the direction until an enabled cell is found.
+        Some(current) => {
+            let mut next = current + step;
+            while next.cmpge(IVec2::ZERO).all() && next.cmple(last).all() && !is_enabled(next) {
+                next += step;
+            }
+
+            if next.cmpge(IVec2::ZERO).all() && next.cmple(last).all() {
+                next
+            } else {
+                current
+            }
+        }
+
+        // Select the first enabled cell.
+        None => {
+            let columns = last.y + 1;
+            let Some(first) = (0..(last.x + 1) * columns)
+                .map(|i| IVec2::new(i / columns, i % columns))
+                .find(|v| is_enabled(*v))
+            else {
+                return;
+            };
+            first
+        }
+    };
+
+    inv_state.set_selected_icon(Some(next));
+    reset.send(ResetInventoryIcons);
+    let page = inventory.map_or(0, |i| i.get_current_page());
+    scroll.send(ScrollToSlot::new(SlotPosition::new(page, next.x as usize, next.y as usize)));
+}
@@ -1,56 +1,175 @@
-use bevy::{input::mouse::{MouseScrollUnit, MouseWheel}, prelude::*};
+use bevy::{input::mouse::{MouseScrollUnit, MouseWheel}, prelude::*, window::PrimaryWindow};
 use crate::prelude::*;
 
+/// Returns how far the content of a scroll panel can be scrolled inside its viewport along both axes.
+pub(crate) fn max_scroll(content: &Node, viewport: &Node) -> Vec2 {
+    (content.size() - viewport.size()).max(Vec2::ZERO)
+}
+
+/// Scrolls the panel below the mouse cursor, when the mouse wheel is used.
+///
+/// Holding shift scrolls horizontally. The wheel only moves the target offset of the panel, `smooth_scroll` then
+/// moves the panel towards it.
 pub fn mouse_scroll(
     mut mouse_wheel_events: EventReader<MouseWheel>,
-    mut query_list: Query<(&mut ScrollPanel, &mut Style, &Parent, &Node)>,
-    query_node: Query<&Node>,
+    mut panels: Query<(&mut ScrollPanel, &Parent, &Node)>,
+    viewports: Query<(&Node, &GlobalTransform)>,
+    windows: Query<&Window, With<PrimaryWindow>>,
     keys: Res<ButtonInput<KeyCode>>,
+    settings: Res<InventorySettings>,
 ) {
+    let Some(cursor) = windows.get_single().ok().and_then(|w| w.cursor_position()) else {
+        mouse_wheel_events.clear();
+        return;
+    };
+
+    let shift_pressed = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
     for mouse_wheel_event in mouse_wheel_events.read() {
-        let mut shift_pressed = false;
-        if keys.pressed(KeyCode::ShiftLeft) {
-            shift_pressed = true;
+        let mut delta = Vec2::new(mouse_wheel_event.x, mouse_wheel_event.y);
+        if mouse_wheel_event.unit == MouseScrollUnit::Line {
+            delta *= settings.get_scroll_line_height();
+        }
+
+        if shift_pressed {
+            delta = Vec2::new(delta.y, delta.x);
         }
-        if keys.pressed(KeyCode::ShiftRight) {
-            shift_pressed = true;
+
+        for (mut panel, parent, content) in &mut panels {
+            let Ok((viewport, transform)) = viewports.get(parent.get()) else {
+                continue;
+            };
+
+            if !viewport.logical_rect(transform).contains(cursor) {
+                continue;
+            }
+
+            let max = max_scroll(content, viewport);
+            panel.target = (panel.target - delta).clamp(Vec2::ZERO, max);
         }
+    }
+}
 
-        for (mut scrolling_list, mut style, parent, list_node) in &mut query_list {
-            if shift_pressed {
-                let items_width = list_node.size().x;
-                let container_width = query_node.get(parent.get()).unwrap().size().x;
+/// Moves every scroll panel towards its target offset.
+///
+/// The speed is defined by the scroll smoothing in `InventorySettings`. A smoothing of 0.0 jumps to the target
+/// immediately.
+pub fn smooth_scroll(
+    time: Res<Time>,
+    settings: Res<InventorySettings>,
+    mut panels: Query<(&mut ScrollPanel, &mut Style, &Parent, &Node)>,
+    viewports: Query<&Node>,
+) {
+    let rate = settings.get_scroll_smoothing();
 
-                let max_scroll = (container_width - items_width).max(0.0);
+    for (mut panel, mut style, parent, content) in &mut panels {
+        // The content or the viewport may have changed their size.
+        if let Ok(viewport) = viewports.get(parent.get()) {
+            let target = panel.target.clamp(Vec2::ZERO, max_scroll(content, viewport));
+            if target != panel.target {
+                panel.target = target;
+            }
+        }
 
-                let dy = match mouse_wheel_event.unit {
-                    MouseScrollUnit::Line => mouse_wheel_event.y * 20.0,
-                    MouseScrollUnit::Pixel => mouse_wheel_event.y,
-                };
+        let current = Vec2::new(panel.x, panel.y);
+        let mut next = if rate > 0.0 {
+            current.lerp(panel.target, 1.0 - (-rate * time.delta_seconds()).exp())
+        } else {
+            panel.target
+        };
 
-                scrolling_list.y += dy;
-                scrolling_list.y = scrolling_list.y.clamp(0.0, max_scroll);
-                style.left = Val::Px(scrolling_list.y);
-            } else {
-                let items_height = list_node.size().y;
-                let container_height = query_node.get(parent.get()).unwrap().size().y;
+        if next.distance(panel.target) < 0.5 {
+            next = panel.target;
+        }
 
-                let max_scroll = (items_height - container_height).max(0.);
+        if next != current {
+            panel.x = next.x;
+            panel.y = next.y;
+        }
 
-                let dy = match mouse_wheel_event.unit {
-                    MouseScrollUnit::Line => mouse_wheel_event.y * 20.,
-                    MouseScrollUnit::Pixel => mouse_wheel_event.y,
-                };
+        if style.left != Val::Px(-next.x) {
+            style.left = Val::Px(-next.x);
+        }
+        if style.top != Val::Px(-next.y) {
+            style.top = Val::Px(-next.y);
+        }
+    }
+}
 
-                scrolling_list.x += dy;
-                scrolling_list.x = scrolling_list.x.clamp(-max_scroll, 0.0);
-                style.top = Val::Px(scrolling_list.x);
+/// Scrolls the inventory, so that the icon requested by a `ScrollToSlot` event becomes visible.
+///
+/// If the icon is on another page of the shown inventory, a `SetInventoryPage` event is sent and the scrolling waits
+/// until the ui of that page has been rebuilt and laid out.
+#[allow(clippy::complexity)]
+pub fn scroll_to_slot(
+    mut events: EventReader<ScrollToSlot>,
+    mut pending: Local<Option<SlotPosition>>,
+    inv_style: Res<InventoryStyle>,
+    inv_state: Res<InventoryState>,
+    inventories: Query<&Inventory>,
+    mut panels: Query<(&mut ScrollPanel, &Parent, &Node)>,
+    viewports: Query<&Node>,
+    mut set_page: EventWriter<SetInventoryPage>,
+) {
+    let inventory = inv_state.get_inventory().and_then(|e| inventories.get(e).ok());
+    let current_page = inventory.map(|i| i.get_current_page());
 
-                info!(
-                    "Items height: {}, container height: {}",
-                    items_height, container_height
-                );
+    for event in events.read() {
+        let pos = event.position();
+        match inventory {
+            Some(inventory) if pos.page >= inventory.get_page_count() => {}
+            Some(inventory) if pos.page != inventory.get_current_page() => {
+                set_page.send(SetInventoryPage::new(pos.page));
+                *pending = Some(pos);
+            }
+            _ => {
+                *pending = None;
+                scroll_panels(&inv_style, pos, &mut panels, &viewports);
             }
         }
     }
-}
\ No newline at end of file
+
+    // The panel of the new page has a size, once it has been laid out.
+    let Some(pos) = *pending else {
+        return;
+    };
+    if current_page.is_none() {
+        *pending = None;
+    } else if current_page == Some(pos.page) && panels.iter().any(|(_, _, content)| content.size() != Vec2::ZERO) {
+        *pending = None;
+        scroll_panels(&inv_style, pos, &mut panels, &viewports);
+    }
+}
+
+fn scroll_panels(
+    inv_style: &InventoryStyle,
+    pos: SlotPosition,
+    panels: &mut Query<(&mut ScrollPanel, &Parent, &Node)>,
+    viewports: &Query<&Node>,
+) {
+    let min = inv_style.get_icon_offset(pos.x, pos.y);
+    let max = min + Vec2::splat(inv_style.get_icon_size());
+
+    for (mut panel, parent, content) in panels.iter_mut() {
+        let Ok(viewport) = viewports.get(parent.get()) else {
+            continue;
+        };
+
+        let view = viewport.size();
+        let mut target = panel.target;
+
+        if min.x < target.x {
+            target.x = min.x;
+        } else if max.x > target.x + view.x {
+            target.x = max.x - view.x;
+        }
+
+        if min.y < target.y {
+            target.y = min.y;
+        } else if max.y > target.y + view.y {
+            target.y = max.y - view.y;
+        }
+
+        panel.target = target.clamp(Vec2::ZERO, max_scroll(content, viewport));
+    }
+}
@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+
+/// Colors the slots of the displayed inventory and of its open container windows by the rarity tier of their items.
+///
+/// Slots, that are neither hovered nor selected, show the border and background color of the `RarityTier` of their
+/// item, or the normal colors of the `InventoryStyle`, if the item has no tier. Borders of glowing tiers pulse over
+/// time. Hovered and selected slots of the inventory keep the colors set by `button_system` and `reset_icons_events`.
+///
+/// Colors are only written when they differ, so slots without an animated tier are not marked as changed every frame.
+#[allow(clippy::complexity)]
+pub fn apply_rarity_colors(
+    time: Res<Time>,
+    inv_state: Res<InventoryState>,
+    inv_style: Res<InventoryStyle>,
+    database: Res<ItemDatabase>,
+    inventories: Query<&Inventory>,
+    mut icons: Query<(&IconComponent, &Interaction, &mut BackgroundColor, &mut BorderColor)>,
+    mut slots: Query<(&ContainerSlotComponent, &mut BackgroundColor, &mut BorderColor), Without<IconComponent>>,
+    windows: Query<&ContainerWindowComponent>,
+) {
+    let Some(inventory) = inv_state.get_inventory().and_then(|e| inventories.get(e).ok()) else {
+        return;
+    };
+
+    let seconds = time.elapsed_seconds();
+
+    for (icon, interaction, color, border) in &mut icons {
+        let v = icon.get_grid_position();
+        if *interaction != Interaction::None || inv_state.get_selected_icon() == Some(v) {
+            continue;
+        }
+
+        let pos = SlotPosition::new(icon.get_page(), v.x as usize, v.y as usize);
+        set_colors(&inv_style, &database, inventory.get_item(pos), seconds, color, border);
+    }
+
+    for (slot, color, border) in &mut slots {
+        let Some(path) = windows.get(slot.get_window()).ok().and_then(|w| inventory.find_item_path(w.get_container()))
+        else {
+            continue;
+        };
+
+        let item = inventory.get_container(&path).and_then(|c| c.get_item(slot.get_position()));
+        set_colors(&inv_style, &database, item, seconds, color, border);
+    }
+}
+
+/// Sets the colors of a slot holding `item` at `seconds` after the start of the pulse.
+fn set_colors(
+    inv_style: &InventoryStyle,
+    database: &ItemDatabase,
+    item: Option<&InventoryItem>,
+    seconds: f32,
+    mut color: Mut<BackgroundColor>,
+    mut border: Mut<BorderColor>,
+) {
+    let (background_color, border_color) = match item.and_then(|i| inv_style.get_item_rarity_tier(i, database)) {
+        Some(tier) => (*tier.get_background_color(), tier.get_border_color_at(seconds)),
+        None => (*inv_style.get_normal_background_color(), *inv_style.get_normal_border_color()),
+    };
+
+    if color.0 != background_color {
+        color.0 = background_color;
+    }
+    if border.0 != border_color {
+        border.0 = border_color;
+    }
+}
@@ -0,0 +1,141 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use crate::prelude::*;
+use crate::systems::mouse_scroll_system::max_scroll;
+
+fn along(axis: ScrollAxis, v: Vec2) -> f32 {
+    match axis {
+        ScrollAxis::Horizontal => v.x,
+        ScrollAxis::Vertical => v.y,
+    }
+}
+
+/// Sizes and positions the scrollbar thumbs according to the scroll position of their panels.
+///
+/// A scrollbar is hidden, if the content of its panel fits into the viewport along the scrollbar's axis.
+pub fn update_scrollbars(
+    panels: Query<(&ScrollPanel, &Parent, &Node)>,
+    viewports: Query<&Node>,
+    mut tracks: Query<(&ScrollbarComponent, &mut Style), Without<ScrollbarThumbComponent>>,
+    mut thumbs: Query<(&ScrollbarThumbComponent, &mut Style), Without<ScrollbarComponent>>,
+) {
+    for (track, mut style) in &mut tracks {
+        let Ok((_, parent, content)) = panels.get(track.get_panel()) else {
+            continue;
+        };
+        let Ok(viewport) = viewports.get(parent.get()) else {
+            continue;
+        };
+
+        let display = if along(track.get_axis(), max_scroll(content, viewport)) > 0.0 {
+            Display::Flex
+        } else {
+            Display::None
+        };
+
+        if style.display != display {
+            style.display = display;
+        }
+    }
+
+    for (thumb, mut style) in &mut thumbs {
+        let Ok((panel, parent, content)) = panels.get(thumb.get_panel()) else {
+            continue;
+        };
+        let Ok(viewport) = viewports.get(parent.get()) else {
+            continue;
+        };
+
+        let axis = thumb.get_axis();
+        let content_length = along(axis, content.size());
+        if content_length <= 0.0 {
+            continue;
+        }
+
+        let offset = along(axis, Vec2::new(panel.x, panel.y));
+        let length = Val::Percent((along(axis, viewport.size()) / content_length * 100.0).min(100.0));
+        let position = Val::Percent(offset / content_length * 100.0);
+
+        match axis {
+            ScrollAxis::Horizontal => {
+                if style.width != length {
+                    style.width = length;
+                }
+                if style.left != position {
+                    style.left = position;
+                }
+            }
+            ScrollAxis::Vertical => {
+                if style.height != length {
+                    style.height = length;
+                }
+                if style.top != position {
+                    style.top = position;
+                }
+            }
+        }
+    }
+}
+
+/// Scrolls a panel while the thumb of one of its scrollbars is dragged with the left mouse button.
+pub fn drag_scrollbars(
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    mut thumbs: Query<(&Interaction, &mut ScrollbarThumbComponent, &Parent)>,
+    nodes: Query<&Node>,
+    mut panels: Query<(&mut ScrollPanel, &Parent, &Node)>,
+) {
+    let Some(cursor) = windows.get_single().ok().and_then(|w| w.cursor_position()) else {
+        return;
+    };
+
+    for (interaction, mut thumb, parent) in &mut thumbs {
+        if !mouse.pressed(MouseButton::Left) {
+            if thumb.get_drag_start().is_some() {
+                thumb.set_drag_start(None);
+            }
+            continue;
+        }
+
+        let axis = thumb.get_axis();
+        let Ok((mut panel, viewport, content)) = panels.get_mut(thumb.get_panel()) else {
+            continue;
+        };
+
+        match thumb.get_drag_start() {
+            None => {
+                if *interaction == Interaction::Pressed {
+                    let offset = along(axis, Vec2::new(panel.x, panel.y));
+                    thumb.set_drag_start(Some((along(axis, cursor), offset)));
+                }
+            }
+
+            Some((start_cursor, start_offset)) => {
+                let (Ok(track), Ok(viewport)) = (nodes.get(parent.get()), nodes.get(viewport.get())) else {
+                    continue;
+                };
+
+                let track_length = along(axis, track.size());
+                if track_length <= 0.0 {
+                    continue;
+                }
+
+                // The thumb moves over the whole track, while the panel moves over its whole content. Like the
+                // mouse wheel, the thumb can not move the panel past its ends.
+                let offset = start_offset
+                    + (along(axis, cursor) - start_cursor) * along(axis, content.size()) / track_length;
+                let offset = offset.clamp(0.0, along(axis, max_scroll(content, viewport)));
+
+                match axis {
+                    ScrollAxis::Horizontal => {
+                        panel.target.x = offset;
+                        panel.x = offset;
+                    }
+                    ScrollAxis::Vertical => {
+                        panel.target.y = offset;
+                        panel.y = offset;
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,60 +1,104 @@
-use bevy::{a11y::{accesskit::{NodeBuilder, Role}, AccessibilityNode}, prelude::*};
+use bevy::{
+    a11y::{accesskit::{NodeBuilder, Role}, AccessibilityNode},
+    ecs::system::EntityCommands,
+    prelude::*,
+    window::PrimaryWindow,
+};
 use crate::prelude::*;
 
-pub fn receive_toggle_inventory_events_shown(
+/// Opens or closes the inventory, when a `ToggleInventory` event is received.
+///
+/// Toggling while a transition is running reverses the transition. Without an animated transition, see
+/// `InventorySettings::is_transition_animated`, the inventory switches between `Hidden` and `Shown` directly.
+pub fn receive_toggle_inventory_events(
     mut toggle_events: EventReader<ToggleInventory>,
+    settings: Res<InventorySettings>,
+    current_state: Res<State<InventoryStates>>,
     mut next_state: ResMut<NextState<InventoryStates>>,
 ) {
+    let animated = settings.is_transition_animated();
     for _ in toggle_events.read() {
-        next_state.set(InventoryStates::Hidden);
-    }
-}
-
-pub fn receive_toggle_inventory_events_hidden(
-    mut toggle_events: EventReader<ToggleInventory>,
-    mut next_state: ResMut<NextState<InventoryStates>>,
-) {
-    for _ in toggle_events.read() {
-        next_state.set(InventoryStates::Shown);
+        let next = match (current_state.get(), animated) {
+            (InventoryStates::Shown | InventoryStates::Opening, true) => InventoryStates::Closing,
+            (InventoryStates::Shown | InventoryStates::Opening, false) => InventoryStates::Hidden,
+            (InventoryStates::Hidden | InventoryStates::Closing, true) => InventoryStates::Opening,
+            (InventoryStates::Hidden | InventoryStates::Closing, false) => InventoryStates::Shown,
+        };
+        next_state.set(next);
     }
 }
 
+#[allow(clippy::complexity)]
 pub fn show(
     mut commands: Commands,
-    inv_style: Res<InventoryStyle>,
+    mut inv_style: ResMut<InventoryStyle>,
     mut state : ResMut<InventoryState>,
     backgrounds: Res<InventoryBackgroundTiles>,
     assets: Res<AssetServer>,
+    settings: Res<InventorySettings>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    inventories: Query<(&Inventory, Option<&InventoryBackgroundTiles>)>,
+    mut roots: Query<&mut Visibility, With<UiRootComponent>>,
 ) {
     if state.is_shown() { return; }
     state.set_is_shown(true);
-    
-    let height = inv_style.get_icon_size();
-    let width = inv_style.get_icon_size();
-    let margin = inv_style.get_icon_margin();
-    let padding = inv_style.get_icon_padding();
 
-    let slicer = TextureSlicer {
-        border: BorderRect::square(15.0),
-        center_scale_mode: SliceScaleMode::Stretch,
-        sides_scale_mode: SliceScaleMode::Stretch,
-        max_corner_scale: 1.0,
+    // A ui kept alive by `InventoryHideMode::Hide` only needs to be made visible again.
+    if !roots.is_empty() {
+        for mut visibility in &mut roots {
+            *visibility = Visibility::Inherited;
+        }
+        return;
+    }
+
+    let (inventory, tiles) = state.get_inventory().and_then(|e| inventories.get(e).ok()).unzip();
+    let backgrounds = tiles.flatten().unwrap_or(&backgrounds);
+    if let Some(inventory) = inventory {
+        sync_grid_size(&mut inv_style, inventory);
+    }
+
+    let window_height = windows.get_single().map(|w| w.height()).unwrap_or(0.0);
+    spawn_inventory_ui(&mut commands, &inv_style, &settings, backgrounds, &assets, window_height, inventory);
+}
+
+/// Sets the grid dimensions of the style to the size of the current page of `inventory`.
+pub(crate) fn sync_grid_size(inv_style: &mut ResMut<InventoryStyle>, inventory: &Inventory) {
+    let Some(page) = inventory.get_page(inventory.get_current_page()) else {
+        return;
     };
 
+    let (rows, columns) = (page.get_rows() as u64, page.get_columns() as u64);
+    if inv_style.get_rows() != rows || inv_style.get_columns() != columns {
+        inv_style.set_grid_size(rows, columns);
+    }
+}
+
+/// Spawns the inventory ui.
+///
+/// The ui consists of a root node containing the tabs of the pages of `inventory` (if it has more than one page) and
+/// a viewport, which clips the scroll panel holding the icons.
+pub(crate) fn spawn_inventory_ui(
+    commands: &mut Commands,
+    inv_style: &InventoryStyle,
+    settings: &InventorySettings,
+    backgrounds: &InventoryBackgroundTiles,
+    assets: &AssetServer,
+    window_height: f32,
+    inventory: Option<&Inventory>,
+) {
     // A root node for a UI layout.
     let root_node = (
         NodeBundle {
             style: Style {
                 width: Val::Percent(50.0),
                 height: Val::Percent(50.0),
-                align_items: AlignItems::Center,
-                justify_content: JustifyContent::Center,
+                align_items: AlignItems::FlexStart,
+                justify_content: JustifyContent::FlexStart,
                 position_type: PositionType::Absolute,
                 top: Val::Percent(25.0),
                 left: Val::Percent(25.0),
                 flex_direction: FlexDirection::Column,
                 align_self: AlignSelf::Stretch,
-                overflow: Overflow::clip(),
                 ..default()
             },
             ..default()
@@ -66,13 +110,37 @@ pub fn show(
     // Store the root id.
     let root_id = commands.spawn(root_node).id();
 
+    let page = inventory.map(|i| i.get_current_page()).unwrap_or(0);
+    if let Some(inventory) = inventory.filter(|i| i.get_page_count() > 1) {
+        spawn_tabs(commands, inv_style, assets, root_id, inventory);
+    }
+
+    let viewport = (
+        NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                flex_grow: 1.0,
+                align_items: AlignItems::FlexStart,
+                justify_content: JustifyContent::FlexStart,
+                overflow: Overflow::clip(),
+                ..default()
+            },
+            ..default()
+        },
+        UiViewportComponent,
+    );
+    let viewport_id = commands.spawn(viewport).id();
+    commands.entity(root_id).add_child(viewport_id);
+
+    let content_size = inv_style.get_content_size();
     let moving_panel = (
         NodeBundle {
             style: Style {
                 flex_direction: FlexDirection::Column,
                 align_items: AlignItems::Center,
-                width: Val::Px(250.0),
-                height: Val::Px(250.0),
+                width: Val::Px(content_size.x),
+                height: Val::Px(content_size.y),
+                flex_shrink: 0.0,
                 ..default()
             },
             ..default()
@@ -81,73 +149,373 @@ pub fn show(
         ScrollPanel::default(),
     );
     let moving_panel_id = commands.spawn(moving_panel).id();
-    commands.entity(root_id).add_child(moving_panel_id);
+    commands.entity(viewport_id).add_child(moving_panel_id);
+
+    spawn_scrollbar(commands, inv_style, viewport_id, moving_panel_id, ScrollAxis::Vertical);
+    spawn_scrollbar(commands, inv_style, viewport_id, moving_panel_id, ScrollAxis::Horizontal);
+
+    // The balances of the wallet. They are listed by `update_wallet_footer`.
+    let footer = (
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font: assets.load(inv_style.get_normal_font().clone()),
+                font_size: inv_style.get_normal_font_size(),
+                color: *inv_style.get_normal_font_color(),
+            },
+        )
+        .with_style(Style {
+            flex_shrink: 0.0,
+            margin: UiRect::top(Val::Px(inv_style.get_icon_margin() * 2.0)),
+            display: Display::None,
+            ..default()
+        }),
+        WalletFooterComponent,
+        IconLabelComponent,
+    );
+    let footer_id = commands.spawn(footer).id();
+    commands.entity(root_id).add_child(footer_id);
+
+    // Large inventories only spawn the visible rows plus a buffer. The rows are recycled while scrolling.
+    let virtualized = inv_style.get_rows() * inv_style.get_columns() > settings.get_virtualization_threshold();
+    let rows = if virtualized {
+        virtual_pool_rows(inv_style, settings, window_height)
+    } else {
+        inv_style.get_rows()
+    };
+
+    if virtualized {
+        commands.entity(moving_panel_id).insert(VirtualGridComponent);
+    }
 
     let mut children: Vec<Entity> = Vec::new();
-    for x in 0..inv_style.get_rows() {
+    for x in 0..rows {
         for y in 0..inv_style.get_columns() {
-            let icon = backgrounds.get_random_tile_small();
-            let image = assets.load(icon);
+            let id = spawn_icon(commands, inv_style, backgrounds, assets, page, x as usize, y as usize);
+            if virtualized {
+                commands.entity(id).insert(VirtualRowComponent::new(x as usize));
+            }
+            children.push(id);
+        }
+    }
 
-            let top = Val::Px((height * x as f32) + 2.0 * margin);
-            let left = Val::Px((width * y as f32) + 2.0 * margin);
+    commands.entity(moving_panel_id).push_children(&children);
+}
 
-            let id = commands
-                .spawn(NodeBundle {
-                    style: Style {
-                        align_items: AlignItems::Center,
-                        justify_content: JustifyContent::Center,
-                        position_type: PositionType::Absolute,
-                        top,
-                        left,
-                        ..default()
-                    },
-                    ..default()
-                })
-                .with_children(|parent| {
-                    parent
-                        .spawn((
-                            ButtonBundle {
+/// Spawns a row of tab buttons, one for every page of `inventory`.
+fn spawn_tabs(
+    commands: &mut Commands,
+    inv_style: &InventoryStyle,
+    assets: &AssetServer,
+    root: Entity,
+    inventory: &Inventory,
+) {
+    let font = assets.load(inv_style.get_normal_font().clone());
+    let padding = Val::Px(inv_style.get_icon_padding() * 2.0);
+
+    let bar = commands
+        .spawn(NodeBundle {
+            style: Style {
+                flex_direction: FlexDirection::Row,
+                width: Val::Percent(100.0),
+                flex_shrink: 0.0,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            for (i, page) in inventory.get_pages().iter().enumerate() {
+                parent
+                    .spawn((
+                        ButtonBundle {
+                            style: Style {
+                                align_items: AlignItems::Center,
+                                padding: UiRect::all(padding),
+                                margin: UiRect::right(Val::Px(inv_style.get_icon_margin())),
+                                border: UiRect::all(Val::Px(inv_style.get_border_with())),
+                                ..default()
+                            },
+                            ..default()
+                        },
+                        InventoryTabComponent::new(i),
+                    ))
+                    .with_children(|parent| {
+                        if let Some(icon) = page.get_icon() {
+                            let size = Val::Px(inv_style.get_normal_font_size());
+                            parent.spawn(ImageBundle {
                                 style: Style {
-                                    width: Val::Px(width - (margin + padding)),
-                                    height: Val::Px(height - (margin + padding)),
-                                    justify_content: JustifyContent::Center,
-                                    align_items: AlignItems::FlexEnd,
-                                    border: UiRect::all(Val::Px(inv_style.get_border_with())),
+                                    width: size,
+                                    height: size,
+                                    margin: UiRect::right(padding),
                                     ..default()
                                 },
-                                image: image.clone().into(),
+                                image: assets.load(icon.to_string()).into(),
                                 ..default()
-                            },
-                            ImageScaleMode::Sliced(slicer.clone()),
-                            IconComponent::new(x as i32, y as i32),
-                        ))
-                        .with_children(|parent| {
-                            parent.spawn(TextBundle::from_section(
-                                format!("({}, {})", y, x),
+                            });
+                        }
+
+                        parent.spawn((
+                            TextBundle::from_section(
+                                page.get_name(),
                                 TextStyle {
-                                    font: assets.load(inv_style.get_normal_font().clone()),
+                                    font: font.clone(),
                                     font_size: inv_style.get_normal_font_size(),
                                     color: *inv_style.get_normal_font_color(),
                                 },
-                            ));
-                        });
-                })
-                .id();
-            children.push(id);
+                            ),
+                            IconLabelComponent,
+                        ));
+                    });
+            }
+        })
+        .id();
+
+    commands.entity(root).add_child(bar);
+}
+
+/// Returns the number of rows a virtualized grid needs to cover a viewport of the given height.
+pub(crate) fn virtual_pool_rows(inv_style: &InventoryStyle, settings: &InventorySettings, viewport_height: f32) -> u64 {
+    let visible_rows = (viewport_height / inv_style.get_icon_size()).ceil() as u64 + 1;
+    (visible_rows + 2 * settings.get_virtualization_buffer_rows()).min(inv_style.get_rows())
+}
+
+/// Returns the text shown on the icon in row `x` and column `y`.
+pub(crate) fn icon_label(x: usize, y: usize) -> String {
+    format!("({}, {})", y, x)
+}
+
+/// Spawns the icon in row `x` and column `y` of `page` and returns the node that positions it inside the scroll panel.
+pub(crate) fn spawn_icon(
+    commands: &mut Commands,
+    inv_style: &InventoryStyle,
+    backgrounds: &InventoryBackgroundTiles,
+    assets: &AssetServer,
+    page: usize,
+    x: usize,
+    y: usize,
+) -> Entity {
+    let height = inv_style.get_icon_size();
+    let width = inv_style.get_icon_size();
+    let margin = inv_style.get_icon_margin();
+    let padding = inv_style.get_icon_padding();
+
+    let offset = inv_style.get_icon_offset(x, y);
+    let top = Val::Px(offset.y);
+    let left = Val::Px(offset.x);
+
+    commands
+        .spawn(NodeBundle {
+            style: Style {
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                position_type: PositionType::Absolute,
+                top,
+                left,
+                ..default()
+            },
+            ..default()
+        })
+        .with_children(|parent| {
+            let mut slot = parent.spawn((
+                ButtonBundle {
+                    style: Style {
+                        width: Val::Px(width - (margin + padding)),
+                        height: Val::Px(height - (margin + padding)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::FlexEnd,
+                        border: UiRect::all(Val::Px(inv_style.get_border_with())),
+                        ..default()
+                    },
+                    ..default()
+                },
+                IconComponent::new(x as i32, y as i32).with_page(page),
+            ));
+
+            let grid = (inv_style.get_rows() as usize, inv_style.get_columns() as usize);
+            insert_background_tile(&mut slot, inv_style, backgrounds, assets, SlotPosition::new(page, x, y), grid);
+
+            slot
+                .with_children(|parent| {
+                    // The item stored in the slot. It is shown by `update_slot_contents`.
+                    parent.spawn((
+                        ImageBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                top: Val::Px(padding),
+                                left: Val::Px(padding),
+                                right: Val::Px(padding),
+                                bottom: Val::Px(padding),
+                                ..default()
+                            },
+                            visibility: Visibility::Hidden,
+                            ..default()
+                        },
+                        ItemIconComponent,
+                    ));
+
+                    parent.spawn((
+                        TextBundle::from_section(
+                            "",
+                            TextStyle {
+                                font: assets.load(inv_style.get_normal_font().clone()),
+                                font_size: inv_style.get_normal_font_size(),
+                                color: *inv_style.get_normal_font_color(),
+                            },
+                        )
+                        .with_style(Style {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(padding),
+                            right: Val::Px(padding * 2.0),
+                            ..default()
+                        }),
+                        ItemCountComponent,
+                    ));
+
+                    parent.spawn((
+                        TextBundle::from_section(
+                            icon_label(x, y),
+                            TextStyle {
+                                font: assets.load(inv_style.get_normal_font().clone()),
+                                font_size: inv_style.get_normal_font_size(),
+                                color: *inv_style.get_normal_font_color(),
+                            },
+                        ),
+                        IconLabelComponent,
+                    ));
+
+                    // The durability of the item stored in the slot. It is shown by `update_slot_contents`.
+                    parent.spawn((
+                        NodeBundle {
+                            style: Style {
+                                position_type: PositionType::Absolute,
+                                left: Val::Px(padding),
+                                bottom: Val::Px(padding),
+                                height: Val::Px(inv_style.get_durability_bar_height()),
+                                ..default()
+                            },
+                            visibility: Visibility::Hidden,
+                            ..default()
+                        },
+                        DurabilityBarComponent,
+                    ));
+                });
+        })
+        .id()
+}
+
+/// Shows the background tile of the slot at `pos` in a grid of `(rows, columns)` on the slot button `slot`.
+///
+/// Tiles of a sprite sheet are stretched over the slot, images are nine-sliced with the slicer of the style.
+pub(crate) fn insert_background_tile(
+    slot: &mut EntityCommands,
+    inv_style: &InventoryStyle,
+    backgrounds: &InventoryBackgroundTiles,
+    assets: &AssetServer,
+    pos: SlotPosition,
+    (rows, columns): (usize, usize),
+) {
+    let Some((image, atlas)) = backgrounds.get_tile(pos, rows, columns) else {
+        return;
+    };
+
+    slot.insert(UiImage::new(assets.load(image.to_string())));
+    match atlas {
+        Some(atlas) => {
+            slot.insert(atlas).remove::<ImageScaleMode>();
+        }
+        None => {
+            slot.insert(ImageScaleMode::Sliced(inv_style.get_texture_slicer())).remove::<TextureAtlas>();
         }
     }
+}
 
-    commands.entity(moving_panel_id).push_children(&children);
+/// Spawns a scrollbar for `panel` along one edge of `viewport`.
+fn spawn_scrollbar(
+    commands: &mut Commands,
+    inv_style: &InventoryStyle,
+    viewport: Entity,
+    panel: Entity,
+    axis: ScrollAxis,
+) {
+    let size = Val::Px(inv_style.get_scrollbar_width());
+    let (track_style, thumb_style) = match axis {
+        ScrollAxis::Vertical => (
+            Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(0.0),
+                right: Val::Px(0.0),
+                bottom: size,
+                width: size,
+                ..default()
+            },
+            Style {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                ..default()
+            },
+        ),
+        ScrollAxis::Horizontal => (
+            Style {
+                position_type: PositionType::Absolute,
+                left: Val::Px(0.0),
+                right: size,
+                bottom: Val::Px(0.0),
+                height: size,
+                ..default()
+            },
+            Style {
+                position_type: PositionType::Absolute,
+                height: Val::Percent(100.0),
+                ..default()
+            },
+        ),
+    };
+
+    let track = commands
+        .spawn((
+            NodeBundle {
+                style: track_style,
+                background_color: (*inv_style.get_scrollbar_track_color()).into(),
+                ..default()
+            },
+            ScrollbarComponent::new(panel, axis),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                NodeBundle {
+                    style: thumb_style,
+                    background_color: (*inv_style.get_scrollbar_thumb_color()).into(),
+                    ..default()
+                },
+                Interaction::default(),
+                ScrollbarThumbComponent::new(panel, axis),
+            ));
+        })
+        .id();
+
+    commands.entity(viewport).add_child(track);
 }
 
 pub fn hide(
     mut commands: Commands,
     mut state : ResMut<InventoryState>,
-    icons: Query<(&UiRootComponent, Entity)>,
+    settings: Res<InventorySettings>,
+    mut icons: Query<(&mut Visibility, Entity), With<UiRootComponent>>,
+    ghosts: Query<Entity, With<DragGhostComponent>>,
 ) {
     state.set_is_shown(false);
-    for (_, e) in &icons {
-        commands.entity(e).despawn_recursive()
+    state.set_hovered_icon(None);
+    state.set_dragged_item(None);
+    state.set_dragged_container(None);
+
+    for e in &ghosts {
+        commands.entity(e).despawn_recursive();
+    }
+
+    for (mut visibility, e) in &mut icons {
+        match settings.get_hide_mode() {
+            InventoryHideMode::Despawn => commands.entity(e).despawn_recursive(),
+            InventoryHideMode::Hide => *visibility = Visibility::Hidden,
+        }
     }
 }
\ No newline at end of file
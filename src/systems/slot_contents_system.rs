@@ -0,0 +1,143 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+
+/// Shows the items of the displayed inventory in their slots.
+///
+/// Icons of disabled cells are hidden, which also prevents any interaction with them. Items with durability show a bar
+/// along the bottom of their slot. The bar of a broken item spans the whole slot in the broken color. Items not
+/// matching the `InventorySearch` are dimmed.
+///
+/// A slot is updated, when the inventory, the item database, the search or the style changed, or when its icon has
+/// been mapped to another position, e.g. by a virtualized grid.
+#[allow(clippy::complexity)]
+pub fn update_slot_contents(
+    mut commands: Commands,
+    inv_state: Res<InventoryState>,
+    inv_style: Res<InventoryStyle>,
+    database: Res<ItemDatabase>,
+    item_icons: Res<ItemIcons>,
+    search: Res<InventorySearch>,
+    assets: Res<AssetServer>,
+    inventories: Query<Ref<Inventory>>,
+    mut icons: Query<(Ref<IconComponent>, &Children, &mut Visibility), Without<ItemIconComponent>>,
+    mut images: Query<(&mut UiImage, &mut Visibility, Option<&mut TextureAtlas>), With<ItemIconComponent>>,
+    mut counts: Query<&mut Text, With<ItemCountComponent>>,
+    mut tints: Query<&mut BackgroundColor, With<ItemIconComponent>>,
+    mut bars: Query<
+        (&mut Style, &mut BackgroundColor, &mut Visibility),
+        (With<DurabilityBarComponent>, Without<IconComponent>, Without<ItemIconComponent>),
+    >,
+) {
+    let Some(inventory) = inv_state.get_inventory().and_then(|e| inventories.get(e).ok()) else {
+        return;
+    };
+
+    let force = inventory.is_changed()
+        || database.is_changed()
+        || item_icons.is_changed()
+        || search.is_changed()
+        || inv_style.is_changed();
+
+    // The width of the content box of a slot button.
+    let bar_width = inv_style.get_icon_size()
+        - inv_style.get_icon_margin()
+        - 3.0 * inv_style.get_icon_padding()
+        - 2.0 * inv_style.get_border_with();
+
+    for (icon, children, mut icon_visibility) in &mut icons {
+        if !force && !icon.is_changed() {
+            continue;
+        }
+
+        let v = icon.get_grid_position();
+        let pos = SlotPosition::new(icon.get_page(), v.x as usize, v.y as usize);
+
+        let visibility = if inventory.is_cell_enabled(pos) {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        };
+        if *icon_visibility != visibility {
+            *icon_visibility = visibility;
+        }
+
+        let item = inventory.get_item(pos);
+        show_slot_item(&mut commands, item, &database, &item_icons, &assets, children, &mut images, &mut counts);
+
+        let tint = match item {
+            Some(item) if !search.matches(item, &database) => *inv_style.get_dimmed_icon_color(),
+            _ => Color::WHITE,
+        };
+
+        for &child in children.iter() {
+            if let Ok(mut color) = tints.get_mut(child) {
+                color.0 = tint;
+            }
+
+            if let Ok((mut style, mut color, mut visibility)) = bars.get_mut(child) {
+                match item.and_then(|i| i.get_durability()) {
+                    Some(durability) => {
+                        let (fraction, bar_color) = if durability.is_broken() {
+                            (1.0, *inv_style.get_durability_broken_color())
+                        } else {
+                            (durability.get_fraction(), inv_style.get_durability_color(durability.get_fraction()))
+                        };
+
+                        style.width = Val::Px(bar_width.max(0.0) * fraction);
+                        style.height = Val::Px(inv_style.get_durability_bar_height());
+                        *color = bar_color.into();
+                        *visibility = Visibility::Inherited;
+                    }
+                    None => *visibility = Visibility::Hidden,
+                }
+            }
+        }
+    }
+}
+
+/// Shows the icon and count of `item` in the children of a slot, or hides them, if the slot is empty.
+///
+/// Icons of an icon atlas get a `TextureAtlas` with their section, that is removed again for icons without an atlas.
+#[allow(clippy::complexity)]
+pub(crate) fn show_slot_item(
+    commands: &mut Commands,
+    item: Option<&InventoryItem>,
+    database: &ItemDatabase,
+    icons: &ItemIcons,
+    assets: &AssetServer,
+    children: &Children,
+    images: &mut Query<(&mut UiImage, &mut Visibility, Option<&mut TextureAtlas>), With<ItemIconComponent>>,
+    counts: &mut Query<&mut Text, With<ItemCountComponent>>,
+) {
+    let definition = item.and_then(|i| database.get_item(i.get_item_id()));
+
+    for &child in children.iter() {
+        if let Ok((mut image, mut visibility, current)) = images.get_mut(child) {
+            match definition {
+                Some(definition) => {
+                    let (icon, atlas) = icons.get_icon(definition);
+                    image.texture = assets.load(icon.to_string());
+                    match (current, atlas) {
+                        (Some(mut current), Some(atlas)) => *current = atlas,
+                        (None, Some(atlas)) => {
+                            commands.entity(child).insert(atlas);
+                        }
+                        (Some(_), None) => {
+                            commands.entity(child).remove::<TextureAtlas>();
+                        }
+                        (None, None) => {}
+                    }
+                    *visibility = Visibility::Inherited;
+                }
+                None => *visibility = Visibility::Hidden,
+            }
+        }
+
+        if let Ok(mut text) = counts.get_mut(child) {
+            text.sections[0].value = match item {
+                Some(item) if item.get_count() > 1 => item.get_count().to_string(),
+                _ => String::new(),
+            };
+        }
+    }
+}
@@ -0,0 +1,137 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+use crate::prelude::*;
+use crate::systems::show_hide_system::{spawn_inventory_ui, sync_grid_size};
+
+/// Switches the page, when a tab button is clicked.
+pub fn tab_buttons(
+    tabs: Query<(&Interaction, &InventoryTabComponent), Changed<Interaction>>,
+    mut pages: EventWriter<SetInventoryPage>,
+) {
+    for (interaction, tab) in &tabs {
+        if *interaction == Interaction::Pressed {
+            pages.send(SetInventoryPage::new(tab.get_page()));
+        }
+    }
+}
+
+/// Highlights the tab of the current page.
+pub fn update_tabs(
+    inv_style: Res<InventoryStyle>,
+    inv_state: Res<InventoryState>,
+    inventories: Query<Ref<Inventory>>,
+    mut tabs: Query<(Ref<InventoryTabComponent>, &mut BackgroundColor, &mut BorderColor)>,
+) {
+    let Some(inventory) = inv_state.get_inventory().and_then(|e| inventories.get(e).ok()) else {
+        return;
+    };
+
+    let force = inv_style.is_changed() || inventory.is_changed();
+
+    for (tab, mut color, mut border) in &mut tabs {
+        if !force && !tab.is_added() {
+            continue;
+        }
+
+        if tab.get_page() == inventory.get_current_page() {
+            *color = (*inv_style.get_selected_background_color()).into();
+            border.0 = *inv_style.get_selected_border_color();
+        } else {
+            *color = (*inv_style.get_normal_background_color()).into();
+            border.0 = *inv_style.get_normal_border_color();
+        }
+    }
+}
+
+/// Rebuilds the ui, when the current page of the shown inventory has been resized.
+pub fn rebuild_on_resize(
+    inv_style: Res<InventoryStyle>,
+    inv_state: Res<InventoryState>,
+    inventories: Query<Ref<Inventory>>,
+    mut rebuild: EventWriter<RebuildInventory>,
+) {
+    let Some(inventory) = inv_state.get_inventory().and_then(|e| inventories.get(e).ok()) else {
+        return;
+    };
+
+    if !inventory.is_changed() {
+        return;
+    }
+
+    let Some(page) = inventory.get_page(inventory.get_current_page()) else {
+        return;
+    };
+
+    if page.get_rows() as u64 != inv_style.get_rows() || page.get_columns() as u64 != inv_style.get_columns() {
+        rebuild.send(RebuildInventory);
+    }
+}
+
+/// Sets the current page of the shown inventory, when a `SetInventoryPage` event is received, and rebuilds the ui.
+pub fn set_inventory_page(
+    mut events: EventReader<SetInventoryPage>,
+    mut inv_state: ResMut<InventoryState>,
+    mut inventories: Query<&mut Inventory>,
+    mut rebuild: EventWriter<RebuildInventory>,
+) {
+    let Some(page) = events.read().last().map(|e| e.page()) else {
+        return;
+    };
+
+    let Some(mut inventory) = inv_state.get_inventory().and_then(|e| inventories.get_mut(e).ok()) else {
+        return;
+    };
+
+    if page == inventory.get_current_page() || page >= inventory.get_page_count() {
+        return;
+    }
+
+    inventory.set_current_page(page);
+
+    // Selections are made per page.
+    inv_state.set_selected_icon(None);
+    inv_state.set_hovered_icon(None);
+
+    rebuild.send(RebuildInventory);
+}
+
+/// Despawns and respawns an open inventory ui, when a `RebuildInventory` event is received.
+///
+/// The grid dimensions of the style are updated to match the current page of the shown inventory.
+#[allow(clippy::complexity)]
+pub fn rebuild_inventory(
+    mut events: EventReader<RebuildInventory>,
+    mut commands: Commands,
+    mut inv_style: ResMut<InventoryStyle>,
+    mut inv_state: ResMut<InventoryState>,
+    backgrounds: Res<InventoryBackgroundTiles>,
+    assets: Res<AssetServer>,
+    settings: Res<InventorySettings>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    inventories: Query<(&Inventory, Option<&InventoryBackgroundTiles>)>,
+    roots: Query<Entity, With<UiRootComponent>>,
+) {
+    if events.read().last().is_none() {
+        return;
+    }
+
+    let (inventory, tiles) = inv_state.get_inventory().and_then(|e| inventories.get(e).ok()).unzip();
+    let backgrounds = tiles.flatten().unwrap_or(&backgrounds);
+    if let Some(inventory) = inventory {
+        sync_grid_size(&mut inv_style, inventory);
+    }
+
+    for e in &roots {
+        commands.entity(e).despawn_recursive();
+    }
+
+    inv_state.set_dragged_item(None);
+    inv_state.set_dragged_container(None);
+
+    // A hidden ui is built the next time the inventory is shown.
+    if !inv_state.is_shown() {
+        return;
+    }
+
+    let window_height = windows.get_single().map(|w| w.height()).unwrap_or(0.0);
+    spawn_inventory_ui(&mut commands, &inv_style, &settings, backgrounds, &assets, window_height, inventory);
+}
@@ -0,0 +1,76 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+use crate::systems::show_hide_system::insert_background_tile;
+
+/// Applies themes requested by `SetInventoryTheme` events.
+///
+/// The style of the theme replaces the active `InventoryStyle`, but the layout of the active style (grid dimensions,
+/// icon size, margin, padding and positioning) is kept. If the theme provides background tiles, they replace the
+/// active `InventoryBackgroundTiles`. Inventories with tiles of their own keep them.
+///
+/// Open inventory windows are restyled in place: border widths, slicers, background images and labels are updated
+/// directly and the colors of the icons are refreshed by sending a `ResetInventoryIcons` event, so that selected and
+/// hovered icons keep their highlight.
+#[allow(clippy::complexity)]
+pub fn apply_theme(
+    mut events: EventReader<SetInventoryTheme>,
+    mut commands: Commands,
+    mut themes: ResMut<InventoryThemes>,
+    mut inv_style: ResMut<InventoryStyle>,
+    inv_state: Res<InventoryState>,
+    mut backgrounds: ResMut<InventoryBackgroundTiles>,
+    inventory_tiles: Query<&InventoryBackgroundTiles>,
+    assets: Res<AssetServer>,
+    mut icons: Query<(Entity, &IconComponent, &mut Style)>,
+    mut labels: Query<&mut Text, With<IconLabelComponent>>,
+    mut tracks: Query<&mut BackgroundColor, (With<ScrollbarComponent>, Without<ScrollbarThumbComponent>)>,
+    mut thumbs: Query<&mut BackgroundColor, (With<ScrollbarThumbComponent>, Without<ScrollbarComponent>)>,
+    mut reset: EventWriter<ResetInventoryIcons>,
+) {
+    let Some(name) = events.read().last().map(|e| e.name().to_string()) else {
+        return;
+    };
+
+    let Some(theme) = themes.get_theme(&name) else {
+        warn!("Inventory theme '{}' is not registered", name);
+        return;
+    };
+
+    let mut style = theme.get_style().clone();
+    style.copy_layout_from(&inv_style);
+    *inv_style = style;
+
+    if let Some(tiles) = theme.get_background_tiles() {
+        *backgrounds = tiles.clone();
+    }
+
+    themes.set_current_theme(Some(name));
+
+    let backgrounds = inv_state.get_inventory().and_then(|e| inventory_tiles.get(e).ok()).unwrap_or(&backgrounds);
+    let grid = (inv_style.get_rows() as usize, inv_style.get_columns() as usize);
+    for (entity, icon, mut style) in &mut icons {
+        style.border = UiRect::all(Val::Px(inv_style.get_border_with()));
+
+        let v = icon.get_grid_position();
+        let pos = SlotPosition::new(icon.get_page(), v.x as usize, v.y as usize);
+        insert_background_tile(&mut commands.entity(entity), &inv_style, backgrounds, &assets, pos, grid);
+    }
+
+    let font = assets.load(inv_style.get_normal_font().clone());
+    for mut text in &mut labels {
+        for section in text.sections.iter_mut() {
+            section.style.font = font.clone();
+            section.style.font_size = inv_style.get_normal_font_size();
+            section.style.color = *inv_style.get_normal_font_color();
+        }
+    }
+
+    for mut color in &mut tracks {
+        *color = (*inv_style.get_scrollbar_track_color()).into();
+    }
+    for mut color in &mut thumbs {
+        *color = (*inv_style.get_scrollbar_thumb_color()).into();
+    }
+
+    reset.send(ResetInventoryIcons);
+}
@@ -0,0 +1,62 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+
+/// Shows the error of the last `ShowInventoryError` event in a toast above the inventory ui, replacing a toast that
+/// is still shown.
+#[allow(clippy::complexity)]
+pub fn show_error_toasts(
+    mut commands: Commands,
+    mut events: EventReader<ShowInventoryError>,
+    inv_style: Res<InventoryStyle>,
+    settings: Res<InventorySettings>,
+    assets: Res<AssetServer>,
+    toasts: Query<Entity, With<ErrorToastComponent>>,
+    roots: Query<Entity, With<UiRootComponent>>,
+) {
+    let Some(error) = events.read().last().map(|e| e.error()) else {
+        return;
+    };
+    let Some(root) = roots.iter().next() else {
+        return;
+    };
+
+    for e in &toasts {
+        commands.entity(e).despawn_recursive();
+    }
+
+    let text_style = TextStyle {
+        font: assets.load(inv_style.get_normal_font().clone()),
+        font_size: inv_style.get_normal_font_size(),
+        color: *inv_style.get_error_font_color(),
+    };
+
+    let toast = commands
+        .spawn((
+            TextBundle::from_section(error.to_string(), text_style)
+                .with_style(Style {
+                    position_type: PositionType::Absolute,
+                    bottom: Val::Percent(100.0),
+                    margin: UiRect::bottom(Val::Px(inv_style.get_icon_margin() * 2.0)),
+                    padding: UiRect::all(Val::Px(inv_style.get_icon_padding() * 2.0)),
+                    ..default()
+                })
+                .with_background_color(*inv_style.get_panel_background_color()),
+            ErrorToastComponent::new(settings.get_error_toast_duration()),
+        ))
+        .id();
+
+    commands.entity(root).add_child(toast);
+}
+
+/// Removes error toasts, once they have been shown for the duration set in `InventorySettings`.
+pub fn expire_error_toasts(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut toasts: Query<(Entity, &mut ErrorToastComponent)>,
+) {
+    for (e, mut toast) in &mut toasts {
+        if toast.get_timer_mut().tick(time.delta()).finished() {
+            commands.entity(e).despawn_recursive();
+        }
+    }
+}
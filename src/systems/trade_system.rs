@@ -0,0 +1,374 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+use crate::systems::vendor_system::spawn_trade_slot;
+
+/// Handles the buttons of the trade window.
+///
+/// Pressing a slot of the own offer withdraws the item, the confirm and cancel buttons send `ConfirmTrade` and
+/// `CancelTrade` for the party of the shown inventory.
+#[allow(clippy::complexity)]
+pub fn trade_buttons(
+    inv_state: Res<InventoryState>,
+    sessions: Query<&TradeSession>,
+    slots: Query<(&Interaction, &TradeSlotComponent), Changed<Interaction>>,
+    confirms: Query<&Interaction, (Changed<Interaction>, With<TradeConfirmComponent>)>,
+    cancels: Query<&Interaction, (Changed<Interaction>, With<TradeCancelComponent>)>,
+    mut withdraw_events: EventWriter<WithdrawTradeItem>,
+    mut confirm_events: EventWriter<ConfirmTrade>,
+    mut cancel_events: EventWriter<CancelTrade>,
+) {
+    let (Some(entity), Some(party)) = (inv_state.get_trade(), inv_state.get_inventory()) else {
+        return;
+    };
+    let Some(side) = sessions.get(entity).ok().and_then(|s| s.get_side(party)) else {
+        return;
+    };
+
+    for (interaction, slot) in &slots {
+        if *interaction == Interaction::Pressed && slot.get_side() == side {
+            withdraw_events.send(WithdrawTradeItem::new(entity, party, slot.get_position()));
+        }
+    }
+
+    if confirms.iter().any(|i| *i == Interaction::Pressed) {
+        confirm_events.send(ConfirmTrade::new(entity, party));
+    }
+
+    if cancels.iter().any(|i| *i == Interaction::Pressed) {
+        cancel_events.send(CancelTrade::new(entity));
+    }
+}
+
+/// Applies `OfferTradeItem`, `WithdrawTradeItem`, `ConfirmTrade` and `CancelTrade` events.
+///
+/// The exchange is executed, once both parties confirmed. If an inventory lacks space, the confirmations are reset and
+/// the session goes on. A completed or cancelled session is removed from its entity and a `TradeFinished` event is
+/// sent. A session, whose offered items do not fit back into the inventories of their owners, can not be cancelled
+/// and goes on, so no offered item is lost. Sessions have to be ended this way, as despawning the entity of a session
+/// destroys its offers.
+///
+/// Offering, withdrawing, exchanging and returning items check the `InventoryRules` for the inventories of the parties.
+/// A vetoed exchange resets the confirmations. Failures involving the shown inventory are shown in an error toast.
+#[allow(clippy::complexity)]
+pub fn apply_trade_events(
+    mut commands: Commands,
+    mut inv_state: ResMut<InventoryState>,
+    mut offer_events: EventReader<OfferTradeItem>,
+    mut withdraw_events: EventReader<WithdrawTradeItem>,
+    mut confirm_events: EventReader<ConfirmTrade>,
+    mut cancel_events: EventReader<CancelTrade>,
+    rules: Res<InventoryRules>,
+    database: Res<ItemDatabase>,
+    mut sessions: Query<&mut TradeSession>,
+    mut inventories: Query<&mut Inventory>,
+    mut finished: EventWriter<TradeFinished>,
+    mut errors: EventWriter<ShowInventoryError>,
+) {
+    let shown = inv_state.get_inventory();
+
+    for event in offer_events.read() {
+        let Ok(mut session) = sessions.get_mut(event.session()) else {
+            continue;
+        };
+        let (Some(side), Ok(mut inventory)) = (session.get_side(event.party()), inventories.get_mut(event.party()))
+        else {
+            continue;
+        };
+
+        let offered = rules
+            .check_take(event.party(), &inventory, event.position())
+            .and_then(|_| session.offer(side, &mut inventory, event.position(), &database));
+        if let Err(error) = offered {
+            if shown == Some(event.party()) {
+                errors.send(ShowInventoryError::new(error));
+            }
+        }
+    }
+
+    for event in withdraw_events.read() {
+        let Ok(mut session) = sessions.get_mut(event.session()) else {
+            continue;
+        };
+        let (Some(side), Ok(mut inventory)) = (session.get_side(event.party()), inventories.get_mut(event.party()))
+        else {
+            continue;
+        };
+
+        let mut updated = session.clone();
+        let withdrawn = rules.modify(event.party(), &mut inventory, |inventory| {
+            updated.withdraw(side, event.position(), inventory, &database)
+        });
+        match withdrawn {
+            Ok(_) => *session = updated,
+            Err(error) if shown == Some(event.party()) => {
+                errors.send(ShowInventoryError::new(error));
+            }
+            Err(_) => {}
+        }
+    }
+
+    let mut ended = Vec::new();
+
+    for event in confirm_events.read() {
+        let Ok(mut session) = sessions.get_mut(event.session()) else {
+            continue;
+        };
+        let Some(side) = session.get_side(event.party()) else {
+            continue;
+        };
+
+        session.confirm(side);
+        if !session.is_ready() {
+            continue;
+        }
+
+        let parties = [session.get_party(TradeSide::First), session.get_party(TradeSide::Second)];
+        let Ok([mut first, mut second]) = inventories.get_many_mut(parties) else {
+            continue;
+        };
+
+        let mut updated = session.clone();
+        let exchanged = rules.modify_pair((parties[0], &mut first), (parties[1], &mut second), |first, second| {
+            updated.execute(first, second, &database)
+        });
+        match exchanged {
+            Ok(()) => {
+                *session = updated;
+                ended.push((event.session(), true));
+            }
+            Err(error) => {
+                session.reset_confirmations();
+                if shown.is_some_and(|e| parties.contains(&e)) {
+                    errors.send(ShowInventoryError::new(error));
+                }
+            }
+        }
+    }
+
+    for event in cancel_events.read() {
+        let Ok(mut session) = sessions.get_mut(event.session()) else {
+            continue;
+        };
+
+        let parties = [session.get_party(TradeSide::First), session.get_party(TradeSide::Second)];
+        let Ok([mut first, mut second]) = inventories.get_many_mut(parties) else {
+            continue;
+        };
+
+        let mut updated = session.clone();
+        let returned = rules.modify_pair((parties[0], &mut first), (parties[1], &mut second), |first, second| {
+            updated.cancel(first, second, &database)
+        });
+        match returned {
+            Ok(()) => {
+                *session = updated;
+                ended.push((event.session(), false));
+            }
+            Err(error) if shown.is_some_and(|e| parties.contains(&e)) => {
+                errors.send(ShowInventoryError::new(error));
+            }
+            Err(_) => {}
+        }
+    }
+
+    for (entity, completed) in ended {
+        commands.entity(entity).remove::<TradeSession>();
+        if inv_state.get_trade() == Some(entity) {
+            inv_state.set_trade(None);
+        }
+        finished.send(TradeFinished::new(entity, completed));
+    }
+}
+
+/// Shows the trade session set in `InventoryState` in a window below the inventory ui.
+///
+/// The window lists the offers of both parties, the offer of the shown inventory first, and whether they confirmed.
+/// It is rebuilt, whenever the session changed, and removed, when no session is set.
+#[allow(clippy::complexity)]
+pub fn update_trade_window(
+    mut commands: Commands,
+    inv_state: Res<InventoryState>,
+    inv_style: Res<InventoryStyle>,
+    backgrounds: Res<InventoryBackgroundTiles>,
+    database: Res<ItemDatabase>,
+    icons: Res<ItemIcons>,
+    assets: Res<AssetServer>,
+    sessions: Query<Ref<TradeSession>>,
+    windows: Query<(Entity, &TradeWindowComponent)>,
+    roots: Query<Entity, With<UiRootComponent>>,
+) {
+    let Some(root) = roots.iter().next() else {
+        return;
+    };
+
+    let session = inv_state.get_trade().and_then(|e| sessions.get(e).ok().map(|s| (e, s)));
+    let changed = session.as_ref().is_some_and(|(_, s)| s.is_changed());
+
+    let mut shown = false;
+    for (e, window) in &windows {
+        if changed || session.as_ref().map(|(s, _)| *s) != Some(window.get_session()) {
+            commands.entity(e).despawn_recursive();
+        } else {
+            shown = true;
+        }
+    }
+
+    if shown {
+        return;
+    }
+
+    if let Some((entity, session)) = session {
+        let own = inv_state.get_inventory().and_then(|e| session.get_side(e)).unwrap_or(TradeSide::First);
+        let window = spawn_trade_window(
+            &mut commands,
+            &inv_style,
+            &backgrounds,
+            &database,
+            &icons,
+            &assets,
+            entity,
+            &session,
+            own,
+        );
+        commands.entity(root).add_child(window);
+    }
+}
+
+/// Spawns the window of a trade session with the offer of `own` first. The window is placed below the inventory ui
+/// and has to be added as a child of its root.
+#[allow(clippy::complexity)]
+fn spawn_trade_window(
+    commands: &mut Commands,
+    inv_style: &InventoryStyle,
+    backgrounds: &InventoryBackgroundTiles,
+    database: &ItemDatabase,
+    icons: &ItemIcons,
+    assets: &AssetServer,
+    entity: Entity,
+    session: &TradeSession,
+    own: TradeSide,
+) -> Entity {
+    let text_style = TextStyle {
+        font: assets.load(inv_style.get_normal_font().clone()),
+        font_size: inv_style.get_normal_font_size(),
+        color: *inv_style.get_normal_font_color(),
+    };
+
+    let margin = inv_style.get_icon_margin();
+    let padding = inv_style.get_icon_padding();
+    let size = inv_style.get_icon_size() - (margin + padding);
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    top: Val::Percent(100.0),
+                    margin: UiRect::top(Val::Px(margin * 2.0)),
+                    padding: UiRect::all(Val::Px(padding * 2.0)),
+                    column_gap: Val::Px(margin * 4.0),
+                    ..default()
+                },
+                background_color: (*inv_style.get_panel_background_color()).into(),
+                ..default()
+            },
+            TradeWindowComponent::new(entity),
+        ))
+        .with_children(|parent| {
+            for side in [own, own.other()] {
+                let offer = session.get_offer(side);
+                let (rows, columns) = offer.get_page(0).map_or((0, 0), |p| (p.get_rows(), p.get_columns()));
+                let title = match (side == own, session.is_confirmed(side)) {
+                    (true, false) => "Your offer",
+                    (true, true) => "Your offer (confirmed)",
+                    (false, false) => "Their offer",
+                    (false, true) => "Their offer (confirmed)",
+                };
+
+                parent
+                    .spawn(NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|parent| {
+                        parent.spawn((
+                            TextBundle::from_section(title, text_style.clone()).with_style(Style {
+                                margin: UiRect::bottom(Val::Px(margin * 2.0)),
+                                ..default()
+                            }),
+                            IconLabelComponent,
+                        ));
+
+                        let grid = Style {
+                            display: Display::Grid,
+                            grid_template_columns: RepeatedGridTrack::px(columns as u16, size),
+                            grid_template_rows: RepeatedGridTrack::px(rows as u16, size),
+                            row_gap: Val::Px(margin),
+                            column_gap: Val::Px(margin),
+                            ..default()
+                        };
+
+                        parent.spawn(NodeBundle { style: grid, ..default() }).with_children(|parent| {
+                            for x in 0..rows {
+                                for y in 0..columns {
+                                    let pos = SlotPosition::new(0, x, y);
+                                    spawn_trade_slot(
+                                        parent,
+                                        inv_style,
+                                        backgrounds,
+                                        pos,
+                                        (rows, columns),
+                                        database,
+                                        icons,
+                                        assets,
+                                        offer.get_item(pos),
+                                        None,
+                                        &text_style,
+                                        TradeSlotComponent::new(side, pos),
+                                    );
+                                }
+                            }
+                        });
+                    });
+            }
+
+            parent
+                .spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        justify_content: JustifyContent::End,
+                        row_gap: Val::Px(margin * 2.0),
+                        ..default()
+                    },
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (label, confirm) in [("Confirm", true), ("Cancel", false)] {
+                        let mut button = parent.spawn(ButtonBundle {
+                            style: Style {
+                                padding: UiRect::all(Val::Px(padding * 2.0)),
+                                border: UiRect::all(Val::Px(inv_style.get_border_with())),
+                                ..default()
+                            },
+                            background_color: (*inv_style.get_normal_background_color()).into(),
+                            border_color: (*inv_style.get_normal_border_color()).into(),
+                            ..default()
+                        });
+
+                        if confirm {
+                            button.insert(TradeConfirmComponent);
+                        } else {
+                            button.insert(TradeCancelComponent);
+                        }
+
+                        button.with_children(|parent| {
+                            parent.spawn((TextBundle::from_section(label, text_style.clone()), IconLabelComponent));
+                        });
+                    }
+                });
+        })
+        .id()
+}
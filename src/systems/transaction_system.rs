@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+
+/// Applies `ApplyInventoryTransaction` events and sends an `InventoryChanged` event for every applied transaction.
+///
+/// Only the inventories changed by a transaction are written, so unchanged inventories are not marked as changed.
+/// A transaction, that failed or was vetoed by the `InventoryRules`, changes nothing and sends an
+/// `InventoryTransactionFailed` event. If it involves the shown inventory, the error is shown in an error toast.
+#[allow(clippy::complexity)]
+pub fn apply_inventory_transactions(
+    mut events: EventReader<ApplyInventoryTransaction>,
+    inv_state: Res<InventoryState>,
+    rules: Res<InventoryRules>,
+    database: Res<ItemDatabase>,
+    mut inventories: Query<&mut Inventory>,
+    mut changed: EventWriter<InventoryChanged>,
+    mut failed: EventWriter<InventoryTransactionFailed>,
+    mut errors: EventWriter<ShowInventoryError>,
+) {
+    for event in events.read() {
+        let transaction = event.transaction();
+        let (results, changes) = match transaction.resolve(&rules, &database, |e| inventories.get(e).ok()) {
+            Ok(resolved) => resolved,
+            Err(error) => {
+                if inv_state.get_inventory().is_some_and(|e| transaction.get_inventories().contains(&e)) {
+                    errors.send(ShowInventoryError::new(error.clone()));
+                }
+                failed.send(InventoryTransactionFailed::new(transaction.clone(), error));
+                continue;
+            }
+        };
+
+        for (entity, result) in results {
+            if let Ok(mut inventory) = inventories.get_mut(entity) {
+                *inventory = result;
+            }
+        }
+
+        changed.send(InventoryChanged::new(changes));
+    }
+}
@@ -0,0 +1,238 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+
+/// The colors of a ui node before the fade transition scaled their alpha, in the order of `node_colors`.
+#[derive(Component, Debug)]
+pub(crate) struct FadeBase {
+    base: Vec<Color>,
+    faded: Vec<Color>, // The colors last written by the transition
+}
+
+type FadedNodes<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        Option<&'static mut BackgroundColor>,
+        Option<&'static mut BorderColor>,
+        Option<&'static mut Text>,
+        Option<&'static mut FadeBase>,
+    ),
+>;
+
+/// Advances the show/hide transition while the inventory is `Opening` or `Closing`.
+///
+/// The progress stored in `InventoryState` moves towards 1.0 while opening and towards 0.0 while closing. The speed is
+/// defined by the transition duration in `InventorySettings`. Once the end is reached, the state switches to `Shown`
+/// or `Hidden`.
+///
+/// The fade transition scales the alpha of every color of the ui root and all its descendants, including tabs,
+/// scrollbars, the wallet footer and the container, vendor and trade windows. Colors changed by other systems during
+/// the transition are faded as well.
+#[allow(clippy::complexity)]
+pub fn animate_transition(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<InventorySettings>,
+    current_state: Res<State<InventoryStates>>,
+    mut next_state: ResMut<NextState<InventoryStates>>,
+    mut inv_state: ResMut<InventoryState>,
+    mut roots: Query<(Entity, &mut Style, &mut Transform), With<UiRootComponent>>,
+    children: Query<&Children>,
+    mut nodes: FadedNodes,
+) {
+    let step = if settings.is_transition_animated() {
+        time.delta_seconds() / settings.get_transition_duration()
+    } else {
+        1.0
+    };
+
+    let opening = *current_state.get() == InventoryStates::Opening;
+    let progress = if opening {
+        inv_state.get_transition_progress() + step
+    } else {
+        inv_state.get_transition_progress() - step
+    };
+    inv_state.set_transition_progress(progress);
+
+    let progress = inv_state.get_transition_progress();
+    apply_transition(settings.get_transition(), progress, &mut roots);
+
+    if settings.get_transition() == InventoryTransition::Fade {
+        for (root, _, _) in &roots {
+            for entity in std::iter::once(root).chain(children.iter_descendants(root)) {
+                fade_node(&mut commands, entity, &mut nodes, progress);
+            }
+        }
+    }
+
+    if opening && progress >= 1.0 {
+        next_state.set(InventoryStates::Shown);
+    } else if !opening && progress <= 0.0 {
+        next_state.set(InventoryStates::Hidden);
+    }
+}
+
+/// Restores the fully opened look of the inventory, once it has been shown.
+#[allow(clippy::complexity)]
+pub fn finish_transition(
+    mut commands: Commands,
+    settings: Res<InventorySettings>,
+    mut inv_state: ResMut<InventoryState>,
+    mut roots: Query<(Entity, &mut Style, &mut Transform), With<UiRootComponent>>,
+    mut nodes: FadedNodes,
+    mut reset: EventWriter<ResetInventoryIcons>,
+) {
+    inv_state.set_transition_progress(1.0);
+    apply_transition(settings.get_transition(), 1.0, &mut roots);
+    restore_faded_nodes(&mut commands, &mut nodes);
+
+    // Restore the hover and selection colors.
+    reset.send(ResetInventoryIcons);
+}
+
+/// Resets the transition progress, once the inventory has been hidden. The colors of a faded ui, that is kept alive,
+/// are restored, so it is shown opaque, when it is shown without a transition.
+pub fn reset_transition(mut commands: Commands, mut inv_state: ResMut<InventoryState>, mut nodes: FadedNodes) {
+    inv_state.set_transition_progress(0.0);
+    restore_faded_nodes(&mut commands, &mut nodes);
+}
+
+fn scale_alpha(color: &Color, factor: f32) -> Color {
+    color.with_a(color.a() * factor)
+}
+
+fn apply_transition(
+    transition: InventoryTransition,
+    progress: f32,
+    roots: &mut Query<(Entity, &mut Style, &mut Transform), With<UiRootComponent>>,
+) {
+    let remaining = 1.0 - progress;
+
+    for (_, mut style, mut transform) in roots.iter_mut() {
+        style.margin = UiRect::default();
+        transform.scale = Vec3::ONE;
+
+        match transition {
+            InventoryTransition::Slide(InventoryTransitionEdge::Left) => {
+                style.margin.left = Val::Vw(-100.0 * remaining);
+            }
+            InventoryTransition::Slide(InventoryTransitionEdge::Right) => {
+                style.margin.left = Val::Vw(100.0 * remaining);
+            }
+            InventoryTransition::Slide(InventoryTransitionEdge::Top) => {
+                style.margin.top = Val::Vh(-100.0 * remaining);
+            }
+            InventoryTransition::Slide(InventoryTransitionEdge::Bottom) => {
+                style.margin.top = Val::Vh(100.0 * remaining);
+            }
+            InventoryTransition::Scale => {
+                transform.scale = Vec3::splat(progress);
+            }
+            InventoryTransition::Fade | InventoryTransition::None => {}
+        }
+    }
+}
+
+/// Returns the colors of a ui node: its background, which also tints its image, its border and the colors of its text
+/// sections. The colors are returned without marking the components as changed.
+fn node_colors<'a>(
+    background: Option<&'a mut Mut<BackgroundColor>>,
+    border: Option<&'a mut Mut<BorderColor>>,
+    text: Option<&'a mut Mut<Text>>,
+) -> Vec<&'a mut Color> {
+    let mut colors = Vec::new();
+    colors.extend(background.map(|c| &mut c.bypass_change_detection().0));
+    colors.extend(border.map(|c| &mut c.bypass_change_detection().0));
+    if let Some(text) = text {
+        colors.extend(text.bypass_change_detection().sections.iter_mut().map(|s| &mut s.style.color));
+    }
+
+    colors
+}
+
+/// Marks the components of a ui node as changed, after `node_colors` were written.
+fn set_node_changed(
+    background: Option<&mut Mut<BackgroundColor>>,
+    border: Option<&mut Mut<BorderColor>>,
+    text: Option<&mut Mut<Text>>,
+) {
+    if let Some(background) = background {
+        background.set_changed();
+    }
+    if let Some(border) = border {
+        border.set_changed();
+    }
+    if let Some(text) = text {
+        text.set_changed();
+    }
+}
+
+/// Scales the alpha of the colors of `entity` by `progress`. The unfaded colors are kept in a `FadeBase`. A color,
+/// that differs from the one last written by the transition, was changed by another system and becomes the new base.
+fn fade_node(commands: &mut Commands, entity: Entity, nodes: &mut FadedNodes, progress: f32) {
+    let Ok((_, mut background, mut border, mut text, fade)) = nodes.get_mut(entity) else {
+        return;
+    };
+    let mut colors = node_colors(background.as_mut(), border.as_mut(), text.as_mut());
+    if colors.is_empty() {
+        return;
+    }
+
+    let mut created = None;
+    let fade = match fade {
+        Some(fade) => fade.into_inner(),
+        None => created.insert(FadeBase {
+            base: Vec::new(),
+            faded: Vec::new(),
+        }),
+    };
+
+    if fade.base.len() != colors.len() {
+        fade.base = colors.iter().map(|c| **c).collect();
+        fade.faded = fade.base.clone();
+    }
+
+    let mut changed = false;
+    for (i, color) in colors.iter_mut().enumerate() {
+        if **color != fade.faded[i] {
+            fade.base[i] = **color;
+        }
+
+        let faded = scale_alpha(&fade.base[i], progress);
+        if **color != faded {
+            **color = faded;
+            changed = true;
+        }
+        fade.faded[i] = faded;
+    }
+
+    if changed {
+        set_node_changed(background.as_mut(), border.as_mut(), text.as_mut());
+    }
+    if let Some(fade) = created {
+        commands.entity(entity).insert(fade);
+    }
+}
+
+/// Gives every faded node its unfaded colors back and removes its `FadeBase`. Colors changed by other systems since
+/// the last fade are kept.
+fn restore_faded_nodes(commands: &mut Commands, nodes: &mut FadedNodes) {
+    for (entity, mut background, mut border, mut text, fade) in nodes.iter_mut() {
+        let Some(fade) = fade else {
+            continue;
+        };
+
+        let colors = node_colors(background.as_mut(), border.as_mut(), text.as_mut());
+        if colors.len() == fade.base.len() {
+            for ((color, base), faded) in colors.into_iter().zip(&fade.base).zip(&fade.faded) {
+                if *color == *faded {
+                    *color = *base;
+                }
+            }
+            set_node_changed(background.as_mut(), border.as_mut(), text.as_mut());
+        }
+
+        commands.entity(entity).remove::<FadeBase>();
+    }
+}
@@ -0,0 +1,422 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+use crate::systems::show_hide_system::insert_background_tile;
+
+/// Buys items from the shown vendor, when their slots are pressed, and buys back entries of the buyback list.
+///
+/// A click buys a single item, a click while holding shift buys the whole stack.
+#[allow(clippy::complexity)]
+pub fn vendor_buttons(
+    inv_state: Res<InventoryState>,
+    keys: Res<ButtonInput<KeyCode>>,
+    stocks: Query<&Inventory>,
+    slots: Query<(&Interaction, &VendorSlotComponent), Changed<Interaction>>,
+    buybacks: Query<(&Interaction, &BuybackSlotComponent), Changed<Interaction>>,
+    mut buy_events: EventWriter<BuyItem>,
+    mut buyback_events: EventWriter<BuybackItem>,
+) {
+    let (Some(vendor), Some(buyer)) = (inv_state.get_vendor(), inv_state.get_inventory()) else {
+        return;
+    };
+
+    let whole_stack = keys.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+
+    for (interaction, slot) in &slots {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let count = match stocks.get(vendor).ok().and_then(|s| s.get_item(slot.get_position())) {
+            Some(item) if whole_stack => item.get_count(),
+            Some(_) => 1,
+            None => continue,
+        };
+
+        buy_events.send(BuyItem::new(vendor, buyer, slot.get_position(), count));
+    }
+
+    for (interaction, slot) in &buybacks {
+        if *interaction == Interaction::Pressed {
+            buyback_events.send(BuybackItem::new(vendor, buyer, slot.get_index()));
+        }
+    }
+}
+
+/// Applies `BuyItem`, `SellItem` and `BuybackItem` events.
+///
+/// The buying or selling entity needs an `Inventory` and a `Wallet`, the vendor entity a `Vendor` and an `Inventory`.
+/// Every trade checks the `InventoryRules` for the inventories it changes. The payment is made on a copy of the wallet,
+/// so a vetoed trade costs nothing. Failed trades of the shown inventory are shown in an error toast.
+#[allow(clippy::complexity)]
+pub fn apply_vendor_events(
+    mut buy_events: EventReader<BuyItem>,
+    mut sell_events: EventReader<SellItem>,
+    mut buyback_events: EventReader<BuybackItem>,
+    inv_state: Res<InventoryState>,
+    database: Res<ItemDatabase>,
+    rules: Res<InventoryRules>,
+    mut inventories: Query<&mut Inventory>,
+    mut vendors: Query<&mut Vendor>,
+    mut wallets: Query<&mut Wallet>,
+    mut errors: EventWriter<ShowInventoryError>,
+) {
+    let mut fail = |entity: Entity, error: InventoryError| {
+        if inv_state.get_inventory() == Some(entity) {
+            errors.send(ShowInventoryError::new(error));
+        }
+    };
+
+    for event in buy_events.read() {
+        let (Ok([mut stock, mut buyer]), Ok(mut vendor), Ok(mut wallet)) = (
+            inventories.get_many_mut([event.vendor(), event.buyer()]),
+            vendors.get_mut(event.vendor()),
+            wallets.get_mut(event.buyer()),
+        ) else {
+            continue;
+        };
+
+        let mut paid = wallet.clone();
+        let bought = rules.modify_pair((event.vendor(), &mut stock), (event.buyer(), &mut buyer), |stock, buyer| {
+            vendor.buy(stock, event.position(), event.count(), buyer, &mut paid, &database)
+        });
+        match bought {
+            Ok(_) => *wallet = paid,
+            Err(error) => fail(event.buyer(), error),
+        }
+    }
+
+    for event in sell_events.read() {
+        let (Ok(mut seller), Ok(mut vendor), Ok(mut wallet)) = (
+            inventories.get_mut(event.seller()),
+            vendors.get_mut(event.vendor()),
+            wallets.get_mut(event.seller()),
+        ) else {
+            continue;
+        };
+
+        let sold = rules
+            .check_take(event.seller(), &seller, event.position())
+            .and_then(|_| vendor.sell(&mut seller, event.position(), &mut wallet, &database));
+        if let Err(error) = sold {
+            fail(event.seller(), error);
+        }
+    }
+
+    for event in buyback_events.read() {
+        let (Ok(mut buyer), Ok(mut vendor), Ok(mut wallet)) = (
+            inventories.get_mut(event.buyer()),
+            vendors.get_mut(event.vendor()),
+            wallets.get_mut(event.buyer()),
+        ) else {
+            continue;
+        };
+
+        let (mut sold, mut paid) = (vendor.clone(), wallet.clone());
+        let bought = rules.modify(event.buyer(), &mut buyer, |buyer| {
+            sold.buy_back(event.index(), buyer, &mut paid, &database)
+        });
+        match bought {
+            Ok(_) => {
+                *vendor = sold;
+                *wallet = paid;
+            }
+            Err(error) => fail(event.buyer(), error),
+        }
+    }
+}
+
+/// Advances the restock schedules of all vendors by the elapsed game time and sends a `VendorRestocked` event for
+/// every restock.
+///
+/// Game time is the virtual `Time`, so vendors do not restock while the game is paused. A restock vetoed by the
+/// `InventoryRules` is skipped and does not count as a restock, so the following restocks roll the same goods as
+/// without the veto.
+pub fn restock_vendors(
+    time: Res<Time>,
+    database: Res<ItemDatabase>,
+    rules: Res<InventoryRules>,
+    mut vendors: Query<(Entity, &mut VendorRestock, &mut Inventory)>,
+    mut events: EventWriter<VendorRestocked>,
+) {
+    for (entity, mut restock, mut stock) in &mut vendors {
+        for _ in 0..restock.advance(time.delta_seconds()) {
+            let mut updated = restock.clone();
+            let restocked = rules.modify(entity, &mut stock, |stock| {
+                updated.restock(stock, &database);
+                Ok(())
+            });
+            if restocked.is_ok() {
+                *restock = updated;
+                events.send(VendorRestocked::new(entity));
+            }
+        }
+    }
+}
+
+/// Shows the vendor set in `InventoryState` in a window next to the inventory ui.
+///
+/// The window lists the current page of the vendor's stock with the prices for buying and the buyback list. It is
+/// rebuilt, whenever the stock or the vendor changed, and removed, when no vendor is set.
+#[allow(clippy::complexity)]
+pub fn update_vendor_window(
+    mut commands: Commands,
+    inv_state: Res<InventoryState>,
+    inv_style: Res<InventoryStyle>,
+    backgrounds: Res<InventoryBackgroundTiles>,
+    database: Res<ItemDatabase>,
+    icons: Res<ItemIcons>,
+    assets: Res<AssetServer>,
+    vendors: Query<(Ref<Vendor>, Ref<Inventory>, Option<&InventoryBackgroundTiles>)>,
+    windows: Query<(Entity, &VendorWindowComponent)>,
+    roots: Query<Entity, With<UiRootComponent>>,
+) {
+    let Some(root) = roots.iter().next() else {
+        return;
+    };
+
+    let vendor = inv_state.get_vendor().and_then(|e| vendors.get(e).ok().map(|v| (e, v)));
+    let changed = vendor.as_ref().is_some_and(|(_, (v, stock, _))| v.is_changed() || stock.is_changed());
+
+    let mut shown = false;
+    for (e, window) in &windows {
+        if changed || vendor.as_ref().map(|(v, _)| *v) != Some(window.get_vendor()) {
+            commands.entity(e).despawn_recursive();
+        } else {
+            shown = true;
+        }
+    }
+
+    if shown {
+        return;
+    }
+
+    if let Some((entity, (vendor, stock, tiles))) = vendor {
+        let window = spawn_vendor_window(
+            &mut commands,
+            &inv_style,
+            tiles.unwrap_or(&backgrounds),
+            &database,
+            &icons,
+            &assets,
+            entity,
+            &vendor,
+            &stock,
+        );
+        commands.entity(root).add_child(window);
+    }
+}
+
+/// Spawns the window of a vendor. The window is placed left of the inventory ui and has to be added as a child of
+/// its root.
+#[allow(clippy::complexity)]
+fn spawn_vendor_window(
+    commands: &mut Commands,
+    inv_style: &InventoryStyle,
+    backgrounds: &InventoryBackgroundTiles,
+    database: &ItemDatabase,
+    icons: &ItemIcons,
+    assets: &AssetServer,
+    entity: Entity,
+    vendor: &Vendor,
+    stock: &Inventory,
+) -> Entity {
+    let text_style = TextStyle {
+        font: assets.load(inv_style.get_normal_font().clone()),
+        font_size: inv_style.get_normal_font_size(),
+        color: *inv_style.get_normal_font_color(),
+    };
+
+    let margin = inv_style.get_icon_margin();
+    let padding = inv_style.get_icon_padding();
+    let size = inv_style.get_icon_size() - (margin + padding);
+
+    let page = stock.get_current_page();
+    let (rows, columns) = stock.get_page(page).map_or((0, 0), |p| (p.get_rows(), p.get_columns()));
+
+    let grid_style = Style {
+        display: Display::Grid,
+        grid_template_columns: RepeatedGridTrack::px(columns as u16, size),
+        grid_auto_rows: vec![GridTrack::px(size)],
+        row_gap: Val::Px(margin),
+        column_gap: Val::Px(margin),
+        margin: UiRect::bottom(Val::Px(margin * 2.0)),
+        ..default()
+    };
+
+    commands
+        .spawn((
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Percent(100.0),
+                    margin: UiRect::right(Val::Px(margin * 2.0)),
+                    padding: UiRect::all(Val::Px(padding * 2.0)),
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                },
+                background_color: (*inv_style.get_panel_background_color()).into(),
+                ..default()
+            },
+            VendorWindowComponent::new(entity),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                TextBundle::from_section(vendor.get_name(), text_style.clone()).with_style(Style {
+                    margin: UiRect::bottom(Val::Px(margin * 2.0)),
+                    ..default()
+                }),
+                IconLabelComponent,
+            ));
+
+            parent.spawn(NodeBundle { style: grid_style.clone(), ..default() }).with_children(|parent| {
+                for x in 0..rows {
+                    for y in 0..columns {
+                        let pos = SlotPosition::new(page, x, y);
+                        let item = stock.get_item(pos);
+                        let price = item.and_then(|i| {
+                            database.get_item(i.get_item_id()).and_then(|d| vendor.get_buy_price(d, 1))
+                        });
+
+                        spawn_trade_slot(
+                            parent,
+                            inv_style,
+                            backgrounds,
+                            pos,
+                            (rows, columns),
+                            database,
+                            icons,
+                            assets,
+                            item,
+                            price,
+                            &text_style,
+                            VendorSlotComponent::new(pos),
+                        );
+                    }
+                }
+            });
+
+            if vendor.get_buyback().is_empty() {
+                return;
+            }
+
+            parent.spawn((TextBundle::from_section("Buyback", text_style.clone()), IconLabelComponent));
+
+            let buyback_rows = vendor.get_buyback().len().div_ceil(columns.max(1));
+            parent.spawn(NodeBundle { style: grid_style, ..default() }).with_children(|parent| {
+                for (i, entry) in vendor.get_buyback().iter().enumerate() {
+                    let (currency, amount) = entry.get_price();
+                    let pos = SlotPosition::new(0, i / columns.max(1), i % columns.max(1));
+                    spawn_trade_slot(
+                        parent,
+                        inv_style,
+                        backgrounds,
+                        pos,
+                        (buyback_rows, columns),
+                        database,
+                        icons,
+                        assets,
+                        Some(entry.get_item()),
+                        Some((currency.to_string(), amount)),
+                        &text_style,
+                        BuybackSlotComponent::new(i),
+                    );
+                }
+            });
+        })
+        .id()
+}
+
+/// Spawns a slot of the vendor or trade window showing an item and, if given, its price. Slots of items with a rarity
+/// tier use the colors of the tier, their count and price are shown in the title color of the item. The background
+/// tile is picked for the slot at `pos` in a grid of `grid` rows and columns.
+#[allow(clippy::complexity)]
+pub(crate) fn spawn_trade_slot(
+    parent: &mut ChildBuilder,
+    inv_style: &InventoryStyle,
+    backgrounds: &InventoryBackgroundTiles,
+    pos: SlotPosition,
+    grid: (usize, usize),
+    database: &ItemDatabase,
+    icons: &ItemIcons,
+    assets: &AssetServer,
+    item: Option<&InventoryItem>,
+    price: Option<(String, u64)>,
+    text_style: &TextStyle,
+    component: impl Bundle,
+) {
+    let padding = inv_style.get_icon_padding();
+    let definition = item.and_then(|i| database.get_item(i.get_item_id()));
+    let text_style = &TextStyle {
+        color: item.map_or(text_style.color, |i| inv_style.get_item_title_color(i, database)),
+        ..text_style.clone()
+    };
+    let (background_color, border_color) = match item.and_then(|i| inv_style.get_item_rarity_tier(i, database)) {
+        Some(tier) => (*tier.get_background_color(), *tier.get_border_color()),
+        None => (*inv_style.get_normal_background_color(), *inv_style.get_normal_border_color()),
+    };
+
+    let mut slot = parent.spawn((
+        ButtonBundle {
+            style: Style {
+                border: UiRect::all(Val::Px(inv_style.get_border_with())),
+                ..default()
+            },
+            background_color: background_color.into(),
+            border_color: border_color.into(),
+            ..default()
+        },
+        component,
+    ));
+    insert_background_tile(&mut slot, inv_style, backgrounds, assets, pos, grid);
+
+    slot
+        .with_children(|parent| {
+            if let Some(definition) = definition {
+                let (icon, atlas) = icons.get_icon(definition);
+                let mut image = parent.spawn((
+                    ImageBundle {
+                        style: Style {
+                            position_type: PositionType::Absolute,
+                            top: Val::Px(padding),
+                            left: Val::Px(padding),
+                            right: Val::Px(padding),
+                            bottom: Val::Px(padding),
+                            ..default()
+                        },
+                        image: assets.load(icon.to_string()).into(),
+                        ..default()
+                    },
+                    ItemIconComponent,
+                ));
+                if let Some(atlas) = atlas {
+                    image.insert(atlas);
+                }
+            }
+
+            if let Some(item) = item.filter(|i| i.get_count() > 1) {
+                parent.spawn((
+                    TextBundle::from_section(item.get_count().to_string(), text_style.clone()).with_style(Style {
+                        position_type: PositionType::Absolute,
+                        top: Val::Px(padding),
+                        right: Val::Px(padding * 2.0),
+                        ..default()
+                    }),
+                    ItemCountComponent,
+                ));
+            }
+
+            if let Some((currency, amount)) = price {
+                parent.spawn((
+                    TextBundle::from_section(format!("{} {}", amount, currency), text_style.clone()).with_style(
+                        Style {
+                            position_type: PositionType::Absolute,
+                            bottom: Val::Px(padding),
+                            left: Val::Px(padding * 2.0),
+                            ..default()
+                        },
+                    ),
+                    IconLabelComponent,
+                ));
+            }
+        });
+}
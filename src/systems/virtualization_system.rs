@@ -0,0 +1,103 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+use crate::systems::show_hide_system::{icon_label, insert_background_tile, spawn_icon, virtual_pool_rows};
+
+/// Keeps the rows of a virtualized grid in view while scrolling.
+///
+/// Every pooled row shows the logical row in the visible range, that is equal to its pool row modulo the pool size.
+/// When a row scrolls out of view, its icons are moved to the row that scrolls into view on the other side and
+/// their `IconComponent` is remapped, so that events carry the logical position of the item. The icons show the
+/// background tile of their new position.
+///
+/// If the viewport grows, additional rows are added to the pool. The new icons show the current page of the inventory
+/// set in `InventoryState`.
+#[allow(clippy::complexity)]
+pub fn recycle_virtual_rows(
+    mut commands: Commands,
+    inv_style: Res<InventoryStyle>,
+    settings: Res<InventorySettings>,
+    inv_state: Res<InventoryState>,
+    backgrounds: Res<InventoryBackgroundTiles>,
+    inventories: Query<(&Inventory, Option<&InventoryBackgroundTiles>)>,
+    assets: Res<AssetServer>,
+    panels: Query<(Entity, &ScrollPanel, &Parent, &Children), With<VirtualGridComponent>>,
+    viewports: Query<&Node>,
+    mut cells: Query<(&VirtualRowComponent, &mut Style, &Children)>,
+    mut icons: Query<(Entity, &mut IconComponent, &Children)>,
+    mut labels: Query<&mut Text, With<IconLabelComponent>>,
+    mut reset: EventWriter<ResetInventoryIcons>,
+) {
+    let columns = inv_style.get_columns() as usize;
+    if columns == 0 {
+        return;
+    }
+
+    let (inventory, tiles) = inv_state.get_inventory().and_then(|e| inventories.get(e).ok()).unzip();
+    let backgrounds = tiles.flatten().unwrap_or(&backgrounds);
+    let page = inventory.map_or(0, |i| i.get_current_page());
+
+    for (panel_id, panel, parent, panel_children) in &panels {
+        let pool = panel_children.iter().filter(|&&c| cells.contains(c)).count() / columns;
+        if pool == 0 {
+            continue;
+        }
+
+        // Grow the pool, if the viewport shows more rows than have been spawned.
+        if let Ok(viewport) = viewports.get(parent.get()) {
+            let needed = virtual_pool_rows(&inv_style, &settings, viewport.size().y) as usize;
+            if needed > pool {
+                for x in pool..needed {
+                    for y in 0..columns {
+                        let id = spawn_icon(&mut commands, &inv_style, backgrounds, &assets, page, x, y);
+                        commands.entity(id).insert(VirtualRowComponent::new(x));
+                        commands.entity(panel_id).add_child(id);
+                    }
+                }
+            }
+        }
+
+        let rows = inv_style.get_rows() as i64;
+        let buffer = settings.get_virtualization_buffer_rows() as i64;
+        let first_visible = (panel.y / inv_style.get_icon_size()).floor() as i64;
+        let first = (first_visible - buffer).clamp(0, (rows - pool as i64).max(0)) as usize;
+
+        let mut recycled = false;
+        for &cell in panel_children.iter() {
+            let Ok((row, mut style, children)) = cells.get_mut(cell) else {
+                continue;
+            };
+            let x = first + (row.get_pool_row() + pool - first % pool) % pool;
+
+            for &child in children.iter() {
+                let Ok((entity, mut icon, icon_children)) = icons.get_mut(child) else {
+                    continue;
+                };
+
+                let current = icon.get_grid_position();
+                if current.x == x as i32 {
+                    continue;
+                }
+
+                let y = current.y as usize;
+                icon.set_grid_position(IVec2::new(x as i32, y as i32));
+                let pos = SlotPosition::new(icon.get_page(), x, y);
+                let grid = (rows as usize, columns);
+                insert_background_tile(&mut commands.entity(entity), &inv_style, backgrounds, &assets, pos, grid);
+                style.top = Val::Px(inv_style.get_icon_offset(x, y).y);
+
+                for &label in icon_children.iter() {
+                    if let Ok(mut text) = labels.get_mut(label) {
+                        text.sections[0].value = icon_label(x, y);
+                    }
+                }
+
+                recycled = true;
+            }
+        }
+
+        // The selection and hover highlights belong to the logical position, not to the pooled icon.
+        if recycled {
+            reset.send(ResetInventoryIcons);
+        }
+    }
+}
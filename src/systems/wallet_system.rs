@@ -0,0 +1,48 @@
+use bevy::prelude::*;
+use crate::prelude::*;
+
+/// Sends a `CurrencyChanged` event for every balance, that changed since the last frame.
+pub fn send_currency_events(
+    mut wallets: Query<(Entity, &mut Wallet), Changed<Wallet>>,
+    mut events: EventWriter<CurrencyChanged>,
+) {
+    for (entity, mut wallet) in &mut wallets {
+        for change in wallet.bypass_change_detection().take_changes() {
+            events.send(CurrencyChanged::new(entity, change.currency, change.old, change.new));
+        }
+    }
+}
+
+/// Lists the balances of the `Wallet` on the entity of the shown inventory in the footer of the inventory ui.
+///
+/// The footer is hidden, if the entity has no wallet.
+#[allow(clippy::complexity)]
+pub fn update_wallet_footer(
+    inv_state: Res<InventoryState>,
+    wallets: Query<Ref<Wallet>>,
+    mut footers: Query<(Ref<WalletFooterComponent>, &mut Text, &mut Style)>,
+) {
+    let wallet = inv_state.get_inventory().and_then(|e| wallets.get(e).ok());
+
+    for (footer, mut text, mut style) in &mut footers {
+        let changed = wallet.as_ref().is_some_and(|w| w.is_changed());
+        if !changed && !footer.is_added() && !inv_state.is_changed() {
+            continue;
+        }
+
+        let display = if wallet.is_some() { Display::Flex } else { Display::None };
+        if style.display != display {
+            style.display = display;
+        }
+
+        text.sections[0].value = wallet
+            .as_ref()
+            .map(|w| {
+                w.iter()
+                    .map(|(currency, balance)| format!("{}: {}", currency, balance))
+                    .collect::<Vec<_>>()
+                    .join("   ")
+            })
+            .unwrap_or_default();
+    }
+}
@@ -0,0 +1,340 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::inventory::{Inventory, SlotPosition};
+use crate::inventory_error::InventoryError;
+use crate::inventory_item::InventoryItem;
+use crate::item_database::ItemDatabase;
+
+/// One of the two parties of a `TradeSession`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TradeSide {
+    First,
+    Second,
+}
+
+impl TradeSide {
+    /// Returns the other party.
+    pub fn other(self) -> Self {
+        match self {
+            TradeSide::First => TradeSide::Second,
+            TradeSide::Second => TradeSide::First,
+        }
+    }
+
+    fn index(self) -> usize {
+        match self {
+            TradeSide::First => 0,
+            TradeSide::Second => 1,
+        }
+    }
+}
+
+/// A barter between the inventories of two entities, e.g. two players or a player and a NPC.
+///
+/// Each side offers items by moving them out of its inventory into an escrow area. Changing an offer resets the
+/// confirmations of both sides. When both sides confirmed, the exchange is executed at once: either every offered
+/// item is moved to the other side, or nothing changes, if an inventory lacks space.
+///
+/// The offered items are held by the session until the exchange or the cancellation. The session is saved together
+/// with its offers, so a game saved during a trade keeps them. Despawning the entity of a session destroys the offered
+/// items, so a session is ended with `CancelTrade`, which returns them.
+///
+/// The session set in `InventoryState` is shown in a window next to the inventory ui.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// use bevy::prelude::*;
+///
+/// let database = ItemDatabase::default();
+/// let (player, merchant) = (Entity::from_raw(1), Entity::from_raw(2));
+/// let mut session = TradeSession::new(player, merchant, 2, 3);
+///
+/// let mut backpack = Inventory::new(2, 2);
+/// backpack.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("sword", 1), &database);
+/// let mut chest = Inventory::new(2, 2);
+/// chest.insert_item(SlotPosition::new(0, 1, 1), InventoryItem::new("gem", 3), &database);
+///
+/// session.offer(TradeSide::First, &mut backpack, SlotPosition::new(0, 0, 0), &database).unwrap();
+/// session.offer(TradeSide::Second, &mut chest, SlotPosition::new(0, 1, 1), &database).unwrap();
+/// assert_eq!(backpack.count_item("sword"), 0);
+///
+/// // Changing an offer resets the confirmations.
+/// session.confirm(TradeSide::First);
+/// let gem = session.withdraw(TradeSide::Second, SlotPosition::new(0, 0, 0), &mut chest, &database).unwrap();
+/// assert!(!session.is_confirmed(TradeSide::First));
+/// session.offer(TradeSide::Second, &mut chest, gem, &database).unwrap();
+///
+/// session.confirm(TradeSide::First);
+/// session.confirm(TradeSide::Second);
+/// assert!(session.execute(&mut backpack, &mut chest, &database).is_ok());
+/// assert_eq!(session.execute(&mut backpack, &mut chest, &database), Err(InventoryError::NotConfirmed));
+/// assert_eq!(backpack.count_item("gem"), 3);
+/// assert_eq!(chest.count_item("sword"), 1);
+/// ```
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
+pub struct TradeSession {
+    parties: [Entity; 2],
+    offers: [Inventory; 2], // Escrow of the offered items
+    confirmed: [bool; 2],
+    size: (usize, usize), // Rows and columns of an offer
+}
+
+impl TradeSession {
+    /// Creates a session between the entities `first` and `second`, each able to offer `rows * columns` stacks.
+    pub fn new(first: Entity, second: Entity, rows: usize, columns: usize) -> Self {
+        Self {
+            parties: [first, second],
+            offers: [Inventory::new(rows, columns), Inventory::new(rows, columns)],
+            confirmed: [false; 2],
+            size: (rows, columns),
+        }
+    }
+
+    pub fn get_party(&self, side: TradeSide) -> Entity {
+        self.parties[side.index()]
+    }
+
+    /// Returns the side of `entity`, or `None`, if it is no party of the session.
+    pub fn get_side(&self, entity: Entity) -> Option<TradeSide> {
+        if self.parties[0] == entity {
+            Some(TradeSide::First)
+        } else if self.parties[1] == entity {
+            Some(TradeSide::Second)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the escrow holding the items offered by `side`.
+    pub fn get_offer(&self, side: TradeSide) -> &Inventory {
+        &self.offers[side.index()]
+    }
+
+    pub fn is_confirmed(&self, side: TradeSide) -> bool {
+        self.confirmed[side.index()]
+    }
+
+    /// Returns true, if both sides confirmed the current offers.
+    pub fn is_ready(&self) -> bool {
+        self.confirmed[0] && self.confirmed[1]
+    }
+
+    /// Confirms the current offers for `side`.
+    pub fn confirm(&mut self, side: TradeSide) {
+        self.confirmed[side.index()] = true;
+    }
+
+    /// Withdraws the confirmations of both sides, e.g. after the exchange was vetoed.
+    pub fn reset_confirmations(&mut self) {
+        self.confirmed = [false; 2];
+    }
+
+    /// Moves the item at `from` of `source`, the inventory of `side`, into the offer of `side`.
+    ///
+    /// Returns the position of the item in the offer. Fails and changes nothing, if there is no item or the offer is
+    /// full.
+    pub fn offer(
+        &mut self,
+        side: TradeSide,
+        source: &mut Inventory,
+        from: SlotPosition,
+        database: &ItemDatabase,
+    ) -> Result<SlotPosition, InventoryError> {
+        let item = source.get_item(from).ok_or(InventoryError::ItemNotFound)?;
+        let pos = self.offers[side.index()].add_item(item.clone(), database)?;
+
+        source.take_item(from)?;
+        self.reset_confirmations();
+        Ok(pos)
+    }
+
+    /// Moves the item at `pos` of the offer of `side` back into `target`, the inventory of `side`.
+    ///
+    /// Returns the position of the item in `target`. Fails and changes nothing, if there is no item or `target` has no
+    /// free slot accepting it.
+    pub fn withdraw(
+        &mut self,
+        side: TradeSide,
+        pos: SlotPosition,
+        target: &mut Inventory,
+        database: &ItemDatabase,
+    ) -> Result<SlotPosition, InventoryError> {
+        let item = self.offers[side.index()].get_item(pos).ok_or(InventoryError::ItemNotFound)?;
+        let to = target.add_item(item.clone(), database)?;
+
+        self.offers[side.index()].take_item(pos)?;
+        self.reset_confirmations();
+        Ok(to)
+    }
+
+    /// Takes all items out of the offers and leaves both offers empty.
+    fn take_offers(&mut self) -> [Vec<InventoryItem>; 2] {
+        let (rows, columns) = self.size;
+        let empty = [Inventory::new(rows, columns), Inventory::new(rows, columns)];
+        std::mem::replace(&mut self.offers, empty).map(|offer| {
+            offer.get_pages().iter().flat_map(|page| page.iter().map(|(_, item)| item.clone())).collect()
+        })
+    }
+
+    /// Adds copies of all items of `offer` to `target`. Fails, if an item did not fit.
+    fn add_offer(target: &mut Inventory, offer: &Inventory, database: &ItemDatabase) -> Result<(), InventoryError> {
+        for (_, item) in offer.get_pages().iter().flat_map(|page| page.iter()) {
+            target.add_item(item.clone(), database)?;
+        }
+
+        Ok(())
+    }
+
+    /// Exchanges the offers, when both sides confirmed. `first` and `second` are the inventories of the parties.
+    ///
+    /// The exchange is applied to copies of both inventories first. Only if every item fits, the copies replace the
+    /// inventories. Otherwise the inventories do not change, the offers stay in escrow and the confirmations are reset.
+    pub fn execute(
+        &mut self,
+        first: &mut Inventory,
+        second: &mut Inventory,
+        database: &ItemDatabase,
+    ) -> Result<(), InventoryError> {
+        if !self.is_ready() {
+            return Err(InventoryError::NotConfirmed);
+        }
+
+        let (mut new_first, mut new_second) = (first.clone(), second.clone());
+        let exchanged = Self::add_offer(&mut new_first, &self.offers[1], database)
+            .and_then(|_| Self::add_offer(&mut new_second, &self.offers[0], database));
+        if let Err(error) = exchanged {
+            self.reset_confirmations();
+            return Err(error);
+        }
+
+        self.take_offers();
+        self.reset_confirmations();
+        *first = new_first;
+        *second = new_second;
+        Ok(())
+    }
+
+    /// Ends the session without exchanging anything and returns the offered items to `first` and `second`, the
+    /// inventories of the parties.
+    ///
+    /// Like `execute`, the items are returned to copies of both inventories first. Fails and changes nothing, if an
+    /// item does not fit back into the inventory of its side. The items then stay in escrow, until there is room.
+    pub fn cancel(
+        &mut self,
+        first: &mut Inventory,
+        second: &mut Inventory,
+        database: &ItemDatabase,
+    ) -> Result<(), InventoryError> {
+        let (mut new_first, mut new_second) = (first.clone(), second.clone());
+        Self::add_offer(&mut new_first, &self.offers[0], database)?;
+        Self::add_offer(&mut new_second, &self.offers[1], database)?;
+
+        self.take_offers();
+        self.reset_confirmations();
+        *first = new_first;
+        *second = new_second;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> TradeSession {
+        TradeSession::new(Entity::from_raw(1), Entity::from_raw(2), 1, 2)
+    }
+
+    fn inventory(items: &[&str], slots: usize) -> Inventory {
+        let database = ItemDatabase::default();
+        let mut inventory = Inventory::new(1, slots);
+        for (y, id) in items.iter().enumerate() {
+            inventory.insert_item(SlotPosition::new(0, 0, y), InventoryItem::new(*id, 1), &database).unwrap();
+        }
+        inventory
+    }
+
+    #[test]
+    fn execute_without_space_changes_nothing() {
+        let database = ItemDatabase::default();
+        let mut session = session();
+        let mut first = inventory(&["sword"], 1);
+        let mut second = inventory(&["gem", "coin"], 2);
+        session.offer(TradeSide::First, &mut first, SlotPosition::new(0, 0, 0), &database).unwrap();
+        session.offer(TradeSide::Second, &mut second, SlotPosition::new(0, 0, 0), &database).unwrap();
+        first.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("rock", 1), &database).unwrap();
+
+        session.confirm(TradeSide::First);
+        session.confirm(TradeSide::Second);
+        let (before_first, before_second) = (first.clone(), second.clone());
+        assert_eq!(session.execute(&mut first, &mut second, &database), Err(InventoryError::InventoryFull));
+        assert_eq!(first, before_first);
+        assert_eq!(second, before_second);
+        assert!(!session.is_ready());
+        assert_eq!(session.get_offer(TradeSide::First).count_item("sword"), 1);
+        assert_eq!(session.get_offer(TradeSide::Second).count_item("gem"), 1);
+    }
+
+    #[test]
+    fn cancel_without_space_keeps_offers() {
+        let database = ItemDatabase::default();
+        let mut session = session();
+        let mut first = inventory(&["sword"], 1);
+        let mut second = inventory(&[], 1);
+        session.offer(TradeSide::First, &mut first, SlotPosition::new(0, 0, 0), &database).unwrap();
+        first.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("rock", 1), &database).unwrap();
+
+        assert_eq!(session.cancel(&mut first, &mut second, &database), Err(InventoryError::InventoryFull));
+        assert_eq!(session.get_offer(TradeSide::First).count_item("sword"), 1);
+        assert_eq!(first.count_item("sword"), 0);
+
+        first.take_item(SlotPosition::new(0, 0, 0)).unwrap();
+        assert_eq!(session.cancel(&mut first, &mut second, &database), Ok(()));
+        assert_eq!(first.count_item("sword"), 1);
+        assert!(session.get_offer(TradeSide::First).get_nested_items().is_empty());
+    }
+
+    #[test]
+    fn withdraw_without_space_keeps_offer() {
+        let database = ItemDatabase::default();
+        let mut session = session();
+        let mut first = inventory(&["sword"], 1);
+        let pos = session.offer(TradeSide::First, &mut first, SlotPosition::new(0, 0, 0), &database).unwrap();
+        first.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("rock", 1), &database).unwrap();
+        session.confirm(TradeSide::Second);
+
+        assert_eq!(session.withdraw(TradeSide::First, pos, &mut first, &database), Err(InventoryError::InventoryFull));
+        assert_eq!(session.get_offer(TradeSide::First).count_item("sword"), 1);
+        assert!(session.is_confirmed(TradeSide::Second));
+    }
+
+    #[test]
+    fn offer_into_full_escrow_changes_nothing() {
+        let database = ItemDatabase::default();
+        let mut session = session();
+        let mut first = inventory(&["sword", "axe", "bow"], 3);
+        session.offer(TradeSide::First, &mut first, SlotPosition::new(0, 0, 0), &database).unwrap();
+        session.offer(TradeSide::First, &mut first, SlotPosition::new(0, 0, 1), &database).unwrap();
+
+        let result = session.offer(TradeSide::First, &mut first, SlotPosition::new(0, 0, 2), &database);
+        assert_eq!(result, Err(InventoryError::InventoryFull));
+        assert_eq!(first.count_item("bow"), 1);
+    }
+
+    #[test]
+    fn saved_session_keeps_offers() {
+        let database = ItemDatabase::default();
+        let mut session = session();
+        let mut first = inventory(&["sword"], 1);
+        session.offer(TradeSide::First, &mut first, SlotPosition::new(0, 0, 0), &database).unwrap();
+        session.confirm(TradeSide::Second);
+
+        let loaded: TradeSession = ron::from_str(&ron::to_string(&session).unwrap()).unwrap();
+        assert_eq!(loaded.get_party(TradeSide::Second), Entity::from_raw(2));
+        assert_eq!(loaded.get_offer(TradeSide::First), session.get_offer(TradeSide::First));
+        assert!(loaded.is_confirmed(TradeSide::Second));
+    }
+}
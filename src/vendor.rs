@@ -0,0 +1,449 @@
+use std::collections::{BTreeSet, VecDeque};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::inventory::{Inventory, SlotPosition};
+use crate::inventory_error::InventoryError;
+use crate::inventory_item::InventoryItem;
+use crate::item_database::{ItemDatabase, ItemDefinition};
+use crate::wallet::{Wallet, WalletTransaction};
+
+/// An item sold to a vendor, that can be bought back for the price it was sold for.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BuybackEntry {
+    item: InventoryItem,
+    currency: String,
+    price: u64,
+}
+
+impl BuybackEntry {
+    pub fn get_item(&self) -> &InventoryItem {
+        &self.item
+    }
+
+    pub fn get_price(&self) -> (&str, u64) {
+        (&self.currency, self.price)
+    }
+}
+
+/// Turns an entity with an `Inventory` into a shop.
+///
+/// The inventory of the vendor holds its stock. Prices are the base prices of the item definitions multiplied with the
+/// factors of the vendor. Items of an infinite stock stay in the inventory of the vendor, when they are bought. Items
+/// sold to the vendor go to a bounded buyback list, the most recent first.
+///
+/// The vendor set in `InventoryState` is shown in a window next to the inventory ui.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let mut database = ItemDatabase::default();
+/// database.add_item(ItemDefinition::new("potion", "Potion", "Items/potion.png").with_price("gold", 10).with_max_stack(5));
+///
+/// let mut vendor = Vendor::new("Alchemist").with_multipliers(1.5, 0.5).with_infinite_stock("potion");
+/// let mut stock = Inventory::new(2, 4);
+/// stock.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("potion", 5), &database);
+///
+/// let mut backpack = Inventory::new(2, 2);
+/// let mut wallet = Wallet::new();
+/// wallet.deposit("gold", 40);
+///
+/// // Buy two potions for 15 gold each. The stock is infinite.
+/// let bought = vendor.buy(&mut stock, SlotPosition::new(0, 0, 0), 2, &mut backpack, &mut wallet, &database).unwrap();
+/// assert_eq!(wallet.get_balance("gold"), 10);
+/// assert_eq!(stock.count_item("potion"), 5);
+///
+/// // Sell them back for 5 gold each and buy them back again.
+/// assert!(vendor.sell(&mut backpack, bought, &mut wallet, &database).is_ok());
+/// assert_eq!(wallet.get_balance("gold"), 20);
+/// assert_eq!(vendor.get_buyback().len(), 1);
+/// assert!(vendor.buy_back(0, &mut backpack, &mut wallet, &database).is_ok());
+/// assert_eq!(wallet.get_balance("gold"), 10);
+///
+/// // The next buy back fails, because the list is empty.
+/// assert_eq!(vendor.buy_back(0, &mut backpack, &mut wallet, &database), Err(InventoryError::ItemNotFound));
+///
+/// // At most a full stack of five potions is bought and paid for at once.
+/// wallet.deposit("gold", 90);
+/// let bought = vendor.buy(&mut stock, SlotPosition::new(0, 0, 0), 50, &mut backpack, &mut wallet, &database).unwrap();
+/// assert_eq!(backpack.get_item(bought).unwrap().get_count(), 5);
+/// assert_eq!(wallet.get_balance("gold"), 25);
+/// ```
+#[derive(Component, Clone, Debug, Serialize, Deserialize)]
+pub struct Vendor {
+    name: String,
+    buy_multiplier: f32,  // Applied to the prices the player pays
+    sell_multiplier: f32, // Applied to the prices the vendor pays
+    infinite_stock: BTreeSet<String>,
+    buyback: VecDeque<BuybackEntry>,
+    buyback_limit: usize,
+}
+
+impl Vendor {
+    /// Creates a vendor, that sells for the base price and buys for a quarter of it.
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            buy_multiplier: 1.0,
+            sell_multiplier: 0.25,
+            infinite_stock: BTreeSet::new(),
+            buyback: VecDeque::new(),
+            buyback_limit: 10,
+        }
+    }
+
+    /// Sets the factors applied to the base prices, when the player buys from and sells to the vendor.
+    pub fn with_multipliers(mut self, buy: f32, sell: f32) -> Self {
+        self.buy_multiplier = buy.max(0.0);
+        self.sell_multiplier = sell.max(0.0);
+        self
+    }
+
+    /// Makes the stock of the item with the given id infinite.
+    pub fn with_infinite_stock(mut self, item_id: impl Into<String>) -> Self {
+        self.infinite_stock.insert(item_id.into());
+        self
+    }
+
+    /// Sets the number of sold items kept for buying back.
+    pub fn with_buyback_limit(mut self, limit: usize) -> Self {
+        self.buyback_limit = limit;
+        self.buyback.truncate(limit);
+        self
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn get_buy_multiplier(&self) -> f32 {
+        self.buy_multiplier
+    }
+
+    pub fn get_sell_multiplier(&self) -> f32 {
+        self.sell_multiplier
+    }
+
+    pub fn has_infinite_stock(&self, item_id: &str) -> bool {
+        self.infinite_stock.contains(item_id)
+    }
+
+    /// Returns the items that can be bought back, the most recently sold first.
+    pub fn get_buyback(&self) -> &VecDeque<BuybackEntry> {
+        &self.buyback
+    }
+
+    fn price(definition: &ItemDefinition, multiplier: f32, count: u32) -> Option<(String, u64)> {
+        let (currency, amount) = definition.get_price()?;
+        let unit = (amount as f64 * multiplier as f64).round();
+
+        // `u64::MAX as f64` is 2^64, the first value, that does not fit.
+        if !(0.0..u64::MAX as f64).contains(&unit) {
+            return None;
+        }
+
+        let price = (unit as u64).checked_mul(count as u64)?;
+        Some((currency.to_string(), price))
+    }
+
+    /// Returns the price the player pays for `count` items, or `None`, if the item has no price or the price does not
+    /// fit into a `u64`.
+    pub fn get_buy_price(&self, definition: &ItemDefinition, count: u32) -> Option<(String, u64)> {
+        Self::price(definition, self.buy_multiplier, count)
+    }
+
+    /// Returns the price the vendor pays for `count` items, or `None`, if the item has no price or the price does not
+    /// fit into a `u64`.
+    pub fn get_sell_price(&self, definition: &ItemDefinition, count: u32) -> Option<(String, u64)> {
+        Self::price(definition, self.sell_multiplier, count)
+    }
+
+    /// Buys `count` items of the stack at `pos` of the vendor's `stock` and puts them into the first free slot of the
+    /// current page of `buyer`. The price is withdrawn from `wallet`. At most the maximum stack size of the item is
+    /// bought at once and only the items actually bought are paid for.
+    ///
+    /// The items are put into `buyer` first, the payment and the removal from the stock follow. Returns the position
+    /// of the bought items. Fails and changes nothing, if the item has no price, the wallet can not afford it, there
+    /// is no free slot or a container stack would have to be split.
+    #[allow(clippy::complexity)]
+    pub fn buy(
+        &mut self,
+        stock: &mut Inventory,
+        pos: SlotPosition,
+        count: u32,
+        buyer: &mut Inventory,
+        wallet: &mut Wallet,
+        database: &ItemDatabase,
+    ) -> Result<SlotPosition, InventoryError> {
+        let item = stock.get_item(pos).ok_or(InventoryError::ItemNotFound)?;
+        let infinite = self.has_infinite_stock(item.get_item_id());
+        let max_stack = database.get_item(item.get_item_id()).map_or(1, |d| d.get_max_stack());
+        let available = if infinite { max_stack } else { item.get_count().min(max_stack) };
+        let count = count.clamp(1, available.max(1));
+        let whole_stack = !infinite && count == item.get_count();
+
+        let (currency, price) = Self::quote(database, item.get_item_id(), |d| self.get_buy_price(d, count))?;
+        let payment = WalletTransaction::new().withdraw(currency, price);
+
+        if !wallet.can_apply(&payment) {
+            return Err(InventoryError::CannotAfford);
+        }
+
+        // Buying the whole stack hands over the instance, a part of it becomes a new instance.
+        let bought = if whole_stack {
+            item.clone()
+        } else if infinite || item.get_contents().is_none() {
+            item.duplicate(count)
+        } else {
+            return Err(InventoryError::CannotSplit);
+        };
+
+        let page = buyer.get_current_page();
+        let bought = buyer.add_item_to_page(page, bought, database)?;
+
+        if !wallet.apply(&payment) {
+            buyer.take_item(bought)?;
+            return Err(InventoryError::CannotAfford);
+        }
+
+        let removed = if infinite {
+            Ok(())
+        } else if whole_stack {
+            stock.take_item(pos).map(|_| ())
+        } else {
+            stock.get_item_mut(pos).map(|i| i.set_count(i.get_count() - count)).ok_or(InventoryError::ItemNotFound)
+        };
+
+        if let Err(error) = removed {
+            wallet.apply(&payment.inverse());
+            buyer.take_item(bought)?;
+            return Err(error);
+        }
+
+        Ok(bought)
+    }
+
+    /// Returns the price of the item with the given id, `NoPrice`, if it has none, or `CannotAfford`, if it does not
+    /// fit into a `u64`.
+    fn quote(
+        database: &ItemDatabase,
+        item_id: &str,
+        price: impl Fn(&ItemDefinition) -> Option<(String, u64)>,
+    ) -> Result<(String, u64), InventoryError> {
+        let definition = database.get_item(item_id).filter(|d| d.get_price().is_some());
+        let definition = definition.ok_or(InventoryError::NoPrice)?;
+        price(definition).ok_or(InventoryError::CannotAfford)
+    }
+
+    /// Sells the item at `pos` of `seller` to the vendor. The price is deposited into `wallet` and the item is added
+    /// to the buyback list.
+    ///
+    /// Fails and changes nothing, if the item has no price or the price exceeds a cap of the wallet.
+    pub fn sell(
+        &mut self,
+        seller: &mut Inventory,
+        pos: SlotPosition,
+        wallet: &mut Wallet,
+        database: &ItemDatabase,
+    ) -> Result<(), InventoryError> {
+        let item = seller.get_item(pos).ok_or(InventoryError::ItemNotFound)?;
+        let count = item.get_count();
+        let (currency, price) = Self::quote(database, item.get_item_id(), |d| self.get_sell_price(d, count))?;
+
+        if !wallet.apply(&WalletTransaction::new().deposit(currency.clone(), price)) {
+            return Err(InventoryError::CannotAfford);
+        }
+
+        let item = seller.take_item(pos)?;
+        self.buyback.push_front(BuybackEntry { item, currency, price });
+        self.buyback.truncate(self.buyback_limit);
+
+        Ok(())
+    }
+
+    /// Buys back the entry at `index` of the buyback list for the price it was sold for. The item keeps its instance
+    /// id and data.
+    ///
+    /// The item is put into `buyer` first, the payment and the removal from the buyback list follow. Returns the
+    /// position of the item in `buyer`. Fails and changes nothing, if the wallet can not afford it or there is no free
+    /// slot accepting the item.
+    pub fn buy_back(
+        &mut self,
+        index: usize,
+        buyer: &mut Inventory,
+        wallet: &mut Wallet,
+        database: &ItemDatabase,
+    ) -> Result<SlotPosition, InventoryError> {
+        let entry = self.buyback.get(index).ok_or(InventoryError::ItemNotFound)?;
+        let payment = WalletTransaction::new().withdraw(entry.currency.clone(), entry.price);
+
+        if !wallet.can_apply(&payment) {
+            return Err(InventoryError::CannotAfford);
+        }
+
+        let page = buyer.get_current_page();
+        let bought = buyer.add_item_to_page(page, entry.item.clone(), database)?;
+
+        if !wallet.apply(&payment) {
+            buyer.take_item(bought)?;
+            return Err(InventoryError::CannotAfford);
+        }
+
+        self.buyback.remove(index);
+        Ok(bought)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn database() -> ItemDatabase {
+        let mut database = ItemDatabase::default();
+        let potion = ItemDefinition::new("potion", "Potion", "Items/potion.png").with_max_stack(5);
+        database.add_item(potion.with_price("gold", 10));
+        database.add_item(ItemDefinition::new("rock", "Rock", "Items/rock.png"));
+        database
+    }
+
+    fn stock(item: InventoryItem) -> Inventory {
+        let mut stock = Inventory::new(1, 2);
+        stock.insert_item(SlotPosition::new(0, 0, 0), item, &database()).unwrap();
+        stock
+    }
+
+    fn wallet(gold: u64) -> Wallet {
+        let mut wallet = Wallet::new();
+        wallet.deposit("gold", gold);
+        wallet
+    }
+
+    #[test]
+    fn buy_into_full_inventory_changes_nothing() {
+        let database = database();
+        let mut vendor = Vendor::new("Alchemist");
+        let mut stock = stock(InventoryItem::new("potion", 3));
+        let mut buyer = Inventory::new(1, 1);
+        buyer.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("rock", 1), &database).unwrap();
+        let mut wallet = wallet(100);
+
+        let result = vendor.buy(&mut stock, SlotPosition::new(0, 0, 0), 2, &mut buyer, &mut wallet, &database);
+        assert_eq!(result, Err(InventoryError::InventoryFull));
+        assert_eq!(wallet.get_balance("gold"), 100);
+        assert_eq!(stock.count_item("potion"), 3);
+        assert_eq!(buyer.count_item("potion"), 0);
+    }
+
+    #[test]
+    fn buy_without_money_changes_nothing() {
+        let database = database();
+        let mut vendor = Vendor::new("Alchemist");
+        let mut stock = stock(InventoryItem::new("potion", 3));
+        let mut buyer = Inventory::new(1, 1);
+        let mut wallet = wallet(15);
+
+        let result = vendor.buy(&mut stock, SlotPosition::new(0, 0, 0), 2, &mut buyer, &mut wallet, &database);
+        assert_eq!(result, Err(InventoryError::CannotAfford));
+        assert_eq!(wallet.get_balance("gold"), 15);
+        assert_eq!(stock.count_item("potion"), 3);
+        assert!(buyer.get_item(SlotPosition::new(0, 0, 0)).is_none());
+    }
+
+    #[test]
+    fn buy_whole_stack_keeps_instance() {
+        let database = database();
+        let mut vendor = Vendor::new("Alchemist");
+        let potion = InventoryItem::new("potion", 2);
+        let id = potion.get_instance_id();
+        let mut stock = stock(potion);
+        let mut buyer = Inventory::new(1, 1);
+        let mut wallet = wallet(100);
+
+        let pos = vendor.buy(&mut stock, SlotPosition::new(0, 0, 0), 5, &mut buyer, &mut wallet, &database).unwrap();
+        assert_eq!(buyer.get_item(pos).unwrap().get_instance_id(), id);
+        assert_eq!(wallet.get_balance("gold"), 80);
+        assert!(stock.get_item(SlotPosition::new(0, 0, 0)).is_none());
+    }
+
+    #[test]
+    fn buy_is_limited_to_max_stack() {
+        let database = database();
+        let mut vendor = Vendor::new("Alchemist").with_infinite_stock("potion");
+        let mut stock = stock(InventoryItem::new("potion", 1));
+        let mut buyer = Inventory::new(1, 2);
+        let mut wallet = wallet(1000);
+
+        let pos = vendor.buy(&mut stock, SlotPosition::new(0, 0, 0), 20, &mut buyer, &mut wallet, &database).unwrap();
+        assert_eq!(buyer.get_item(pos).unwrap().get_count(), 5);
+        assert_eq!(wallet.get_balance("gold"), 950);
+
+        // A finite stack larger than a stack is bought in parts as well.
+        let mut vendor = Vendor::new("Alchemist");
+        let mut stock = Inventory::new(1, 1);
+        stock.replace_slot(SlotPosition::new(0, 0, 0), Some(InventoryItem::new("potion", 8))).unwrap();
+        let pos = vendor.buy(&mut stock, SlotPosition::new(0, 0, 0), 8, &mut buyer, &mut wallet, &database).unwrap();
+        assert_eq!(buyer.get_item(pos).unwrap().get_count(), 5);
+        assert_eq!(stock.count_item("potion"), 3);
+        assert_eq!(wallet.get_balance("gold"), 900);
+    }
+
+    #[test]
+    fn items_without_price_are_not_traded() {
+        let database = database();
+        let mut vendor = Vendor::new("Alchemist");
+        let mut stock = stock(InventoryItem::new("rock", 1));
+        let mut buyer = Inventory::new(1, 1);
+        let mut wallet = wallet(100);
+
+        let result = vendor.buy(&mut stock, SlotPosition::new(0, 0, 0), 1, &mut buyer, &mut wallet, &database);
+        assert_eq!(result, Err(InventoryError::NoPrice));
+        let result = vendor.sell(&mut stock, SlotPosition::new(0, 0, 0), &mut wallet, &database);
+        assert_eq!(result, Err(InventoryError::NoPrice));
+        assert_eq!(stock.count_item("rock"), 1);
+        assert_eq!(wallet.get_balance("gold"), 100);
+    }
+
+    #[test]
+    fn splitting_a_container_fails() {
+        let mut database = database();
+        database.add_item(ItemDefinition::new("pouch", "Pouch", "Items/pouch.png").with_price("gold", 1));
+        let mut vendor = Vendor::new("Tailor");
+        let mut stock = stock(InventoryItem::new("pouch", 2).with_contents(Inventory::new(1, 1)));
+        let mut buyer = Inventory::new(1, 1);
+        let mut wallet = wallet(100);
+
+        let result = vendor.buy(&mut stock, SlotPosition::new(0, 0, 0), 1, &mut buyer, &mut wallet, &database);
+        assert_eq!(result, Err(InventoryError::CannotSplit));
+        assert_eq!(wallet.get_balance("gold"), 100);
+        assert_eq!(stock.count_item("pouch"), 2);
+    }
+
+    #[test]
+    fn prices_do_not_overflow() {
+        let vendor = Vendor::new("Banker").with_multipliers(2.0, 1.0);
+        let huge = ItemDefinition::new("deed", "Deed", "Items/deed.png").with_price("gold", u64::MAX / 2 + 1);
+        assert_eq!(vendor.get_buy_price(&huge, 1), None);
+        assert_eq!(vendor.get_sell_price(&huge, 2), None);
+
+        let exact = ItemDefinition::new("gem", "Gem", "Items/gem.png").with_price("gold", 1 << 40);
+        assert_eq!(vendor.get_sell_price(&exact, 3), Some(("gold".to_string(), 3 << 40)));
+    }
+
+    #[test]
+    fn buy_back_into_full_inventory_keeps_entry() {
+        let database = database();
+        let mut vendor = Vendor::new("Alchemist");
+        let mut seller = stock(InventoryItem::new("potion", 1));
+        let mut wallet = wallet(0);
+        vendor.sell(&mut seller, SlotPosition::new(0, 0, 0), &mut wallet, &database).unwrap();
+        assert_eq!(wallet.get_balance("gold"), 3);
+
+        let mut buyer = Inventory::new(1, 1);
+        buyer.insert_item(SlotPosition::new(0, 0, 0), InventoryItem::new("rock", 1), &database).unwrap();
+        assert_eq!(vendor.buy_back(0, &mut buyer, &mut wallet, &database), Err(InventoryError::InventoryFull));
+        assert_eq!(vendor.get_buyback().len(), 1);
+        assert_eq!(wallet.get_balance("gold"), 3);
+    }
+}
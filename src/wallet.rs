@@ -0,0 +1,211 @@
+use std::collections::BTreeMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// A change of the balance of a single currency.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CurrencyChange {
+    pub currency: String,
+    pub old: u64,
+    pub new: u64,
+}
+
+/// A set of deposits and withdrawals in several currencies, that is applied to a `Wallet` as a whole.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let price = WalletTransaction::new().withdraw("gold", 120).withdraw("faction_token", 2);
+/// assert_eq!(price.get_changes().len(), 2);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WalletTransaction {
+    changes: Vec<(String, i128)>, // (currency, signed amount)
+}
+
+impl WalletTransaction {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `amount` to the balance of `currency`.
+    pub fn deposit(mut self, currency: impl Into<String>, amount: u64) -> Self {
+        self.changes.push((currency.into(), amount as i128));
+        self
+    }
+
+    /// Removes `amount` from the balance of `currency`.
+    pub fn withdraw(mut self, currency: impl Into<String>, amount: u64) -> Self {
+        self.changes.push((currency.into(), -(amount as i128)));
+        self
+    }
+
+    /// Returns the changes of the transaction as pairs of currency and signed amount.
+    pub fn get_changes(&self) -> &[(String, i128)] {
+        &self.changes
+    }
+
+    /// Returns the transaction, that undoes this one.
+    pub fn inverse(&self) -> Self {
+        Self {
+            changes: self.changes.iter().map(|(c, a)| (c.clone(), -a)).collect(),
+        }
+    }
+}
+
+/// Holds the balances of currencies like gold, gems or faction tokens, outside of the inventory grid.
+///
+/// Every currency can have a cap. Deposits above the cap are not stored. The balances of the `Wallet` on the entity of
+/// the shown inventory are listed in the footer of the inventory window. Every change of a balance sends a
+/// `CurrencyChanged` event.
+///
+/// # Example
+///
+/// ```
+/// use bevy_inventory_system::prelude::*;
+/// let mut wallet = Wallet::new().with_cap("gems", 100);
+///
+/// assert_eq!(wallet.deposit("gold", 500), 0);
+/// assert_eq!(wallet.deposit("gems", 120), 20); // 20 gems overflow
+/// assert_eq!(wallet.get_balance("gems"), 100);
+///
+/// // Transactions are applied completely or not at all.
+/// let too_expensive = WalletTransaction::new().withdraw("gold", 100).withdraw("gems", 200);
+/// assert!(!wallet.apply(&too_expensive));
+/// assert_eq!(wallet.get_balance("gold"), 500);
+///
+/// let trade = WalletTransaction::new().withdraw("gold", 100).deposit("faction_token", 1);
+/// assert!(wallet.apply(&trade));
+/// assert_eq!(wallet.get_balance("gold"), 400);
+/// assert_eq!(wallet.get_balance("faction_token"), 1);
+/// ```
+#[derive(Component, Clone, Debug, Default, Serialize, Deserialize)]
+pub struct Wallet {
+    balances: BTreeMap<String, u64>,
+    caps: BTreeMap<String, u64>,
+    #[serde(skip)]
+    changes: Vec<CurrencyChange>, // Changes not yet sent as events
+}
+
+impl Wallet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limits the balance of `currency` to `cap`.
+    pub fn with_cap(mut self, currency: impl Into<String>, cap: u64) -> Self {
+        self.set_cap(currency, Some(cap));
+        self
+    }
+
+    pub fn get_balance(&self, currency: &str) -> u64 {
+        self.balances.get(currency).copied().unwrap_or(0)
+    }
+
+    pub fn get_cap(&self, currency: &str) -> Option<u64> {
+        self.caps.get(currency).copied()
+    }
+
+    /// Sets or removes the cap of `currency`. A balance above the new cap is reduced to the cap.
+    pub fn set_cap(&mut self, currency: impl Into<String>, cap: Option<u64>) {
+        let currency = currency.into();
+        match cap {
+            Some(cap) => {
+                if self.get_balance(&currency) > cap {
+                    self.set_balance(&currency, cap);
+                }
+                self.caps.insert(currency, cap);
+            }
+            None => {
+                self.caps.remove(&currency);
+            }
+        }
+    }
+
+    /// Iterates over all currencies with a balance above zero, ordered by currency.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, u64)> {
+        self.balances.iter().filter(|(_, b)| **b > 0).map(|(c, b)| (c.as_str(), *b))
+    }
+
+    /// Adds `amount` to the balance of `currency`, up to its cap. Returns the amount that did not fit.
+    pub fn deposit(&mut self, currency: &str, amount: u64) -> u64 {
+        let balance = self.get_balance(currency);
+        let cap = self.get_cap(currency).unwrap_or(u64::MAX);
+        let new = balance.saturating_add(amount).min(cap.max(balance));
+
+        self.set_balance(currency, new);
+        amount - (new - balance)
+    }
+
+    /// Removes `amount` from the balance of `currency`. Returns false and changes nothing, if the balance is too low.
+    pub fn withdraw(&mut self, currency: &str, amount: u64) -> bool {
+        let balance = self.get_balance(currency);
+        if balance < amount {
+            return false;
+        }
+
+        self.set_balance(currency, balance - amount);
+        true
+    }
+
+    /// Returns the balances after applying `transaction`, or `None`, if a balance would drop below zero or exceed its
+    /// cap.
+    fn resulting_balances(&self, transaction: &WalletTransaction) -> Option<BTreeMap<String, u64>> {
+        let mut balances: BTreeMap<String, i128> = BTreeMap::new();
+        for (currency, amount) in transaction.get_changes() {
+            *balances.entry(currency.clone()).or_insert(self.get_balance(currency) as i128) += amount;
+        }
+
+        balances
+            .into_iter()
+            .map(|(currency, balance)| {
+                let cap = self.get_cap(&currency).unwrap_or(u64::MAX) as i128;
+                if balance < 0 || balance > cap {
+                    None
+                } else {
+                    Some((currency, balance as u64))
+                }
+            })
+            .collect()
+    }
+
+    /// Returns true, if `transaction` can be applied.
+    pub fn can_apply(&self, transaction: &WalletTransaction) -> bool {
+        self.resulting_balances(transaction).is_some()
+    }
+
+    /// Applies all changes of `transaction` at once. Returns false and changes nothing, if a balance would drop below
+    /// zero or exceed its cap.
+    pub fn apply(&mut self, transaction: &WalletTransaction) -> bool {
+        let Some(balances) = self.resulting_balances(transaction) else {
+            return false;
+        };
+
+        for (currency, balance) in balances {
+            self.set_balance(&currency, balance);
+        }
+
+        true
+    }
+
+    fn set_balance(&mut self, currency: &str, value: u64) {
+        let old = self.get_balance(currency);
+        if old == value {
+            return;
+        }
+
+        self.balances.insert(currency.to_string(), value);
+        self.changes.push(CurrencyChange {
+            currency: currency.to_string(),
+            old,
+            new: value,
+        });
+    }
+
+    /// Removes and returns the changes, that have not been sent as `CurrencyChanged` events yet.
+    pub(crate) fn take_changes(&mut self) -> Vec<CurrencyChange> {
+        std::mem::take(&mut self.changes)
+    }
+}